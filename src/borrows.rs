@@ -0,0 +1,180 @@
+// borrowing, checked as the code runs
+
+// the borrow checker makes sure, at compile time, that there's either one
+// mutable reference to a value, or any number of shared ones. a RefCell
+// checks the very same rule, only while the program runs, and panics when
+// it's broken, which lets a value be changed through a shared reference,
+// as long as nobody else is looking at it right then.
+
+// a Tracked value is a RefCell that keeps a table of who's borrowing it,
+// each borrow has a name, and is in the table until it's dropped, so the
+// lesson can show the table as it changes, and say who was in the way
+// when a borrow isn't allowed.
+
+use std::cell::{Cell, Ref, RefCell, RefMut};
+use std::fmt;
+use std::ops::{Deref, DerefMut};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kind {
+    Shared,
+    Mutable,
+}
+
+impl Kind {
+    pub fn name(&self) -> &'static str {
+        match *self {
+            Kind::Shared => "shared",
+            Kind::Mutable => "mutable",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Borrow {
+    // tells apart two borrows that were given the same name.
+    id: usize,
+    pub name: &'static str,
+    pub kind: Kind,
+}
+
+pub struct Tracked<T> {
+    name: &'static str,
+    cell: RefCell<T>,
+    table: RefCell<Vec<Borrow>>,
+    next: Cell<usize>,
+}
+
+// a borrow that isn't allowed, and the ones that are in the way.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Conflict {
+    pub cell: &'static str,
+    pub name: &'static str,
+    pub kind: Kind,
+    pub holders: Vec<Borrow>,
+}
+
+impl fmt::Display for Conflict {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let holders: Vec<String> = self.holders.iter().map(|holder| holder.name.to_string()).collect();
+        let how = match self.holders.first().map(|holder| holder.kind) {
+            Some(Kind::Mutable) => "borrowing it mutably",
+            _ if holders.len() > 1 => "all borrowing it",
+            _ => "borrowing it",
+        };
+        write!(f, "{} can't borrow {} as {}, {} {} {}", self.name, self.cell, self.kind.name(),
+               holders.join(" and "), if holders.len() > 1 { "are" } else { "is" }, how)
+    }
+}
+
+impl<T> Tracked<T> {
+    pub fn new(name: &'static str, value: T) -> Tracked<T> {
+        Tracked {
+            name,
+            cell: RefCell::new(value),
+            table: RefCell::new(Vec::new()),
+            next: Cell::new(0),
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    // the borrows that are still alive, oldest first.
+    pub fn table(&self) -> Vec<Borrow> {
+        self.table.borrow().clone()
+    }
+
+    fn enter(&self, name: &'static str, kind: Kind) -> usize {
+        let id = self.next.get() + 1;
+        self.next.set(id);
+        self.table.borrow_mut().push(Borrow { id, name, kind });
+        id
+    }
+
+    fn leave(&self, id: usize) {
+        self.table.borrow_mut().retain(|borrow| borrow.id != id);
+    }
+
+    fn conflict(&self, name: &'static str, kind: Kind) -> Conflict {
+        Conflict {
+            cell: self.name,
+            name,
+            kind,
+            holders: self.table(),
+        }
+    }
+
+    // like RefCell::borrow, it panics if there's a mutable borrow.
+    pub fn borrow(&self, name: &'static str) -> Shared<'_, T> {
+        let guard = self.cell.borrow();
+        Shared { id: self.enter(name, Kind::Shared), tracked: self, guard }
+    }
+
+    // like RefCell::borrow_mut, it panics if there's any other borrow.
+    pub fn borrow_mut(&self, name: &'static str) -> Exclusive<'_, T> {
+        let guard = self.cell.borrow_mut();
+        Exclusive { id: self.enter(name, Kind::Mutable), tracked: self, guard }
+    }
+
+    pub fn try_borrow(&self, name: &'static str) -> Result<Shared<'_, T>, Conflict> {
+        match self.cell.try_borrow() {
+            Ok(guard) => Ok(Shared { id: self.enter(name, Kind::Shared), tracked: self, guard }),
+            Err(_) => Err(self.conflict(name, Kind::Shared)),
+        }
+    }
+
+    pub fn try_borrow_mut(&self, name: &'static str) -> Result<Exclusive<'_, T>, Conflict> {
+        match self.cell.try_borrow_mut() {
+            Ok(guard) => Ok(Exclusive { id: self.enter(name, Kind::Mutable), tracked: self, guard }),
+            Err(_) => Err(self.conflict(name, Kind::Mutable)),
+        }
+    }
+}
+
+pub struct Shared<'a, T: 'a> {
+    id: usize,
+    tracked: &'a Tracked<T>,
+    guard: Ref<'a, T>,
+}
+
+impl<'a, T> Drop for Shared<'a, T> {
+    fn drop(&mut self) {
+        self.tracked.leave(self.id);
+    }
+}
+
+impl<'a, T> Deref for Shared<'a, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.guard
+    }
+}
+
+pub struct Exclusive<'a, T: 'a> {
+    id: usize,
+    tracked: &'a Tracked<T>,
+    guard: RefMut<'a, T>,
+}
+
+impl<'a, T> Drop for Exclusive<'a, T> {
+    fn drop(&mut self) {
+        self.tracked.leave(self.id);
+    }
+}
+
+impl<'a, T> Deref for Exclusive<'a, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.guard
+    }
+}
+
+impl<'a, T> DerefMut for Exclusive<'a, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.guard
+    }
+}
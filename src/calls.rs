@@ -0,0 +1,213 @@
+// who called whom
+
+// the functions lesson calls one function from another, and another from
+// that one, call! is how it shows it, wrapped around a call, like
+//
+//     let returned = call!(return_sth());
+//     call!(do_sth(ctx; 999));
+//
+// it writes down the function's name, the arguments after the `;`, what it
+// returned, and how long it took, the ones before the `;`, like ctx, are
+// passed along as they are, without being written down.
+
+// nothing is written down unless the calls are being recorded, with
+// record(), which the lessons do when run with --calls, and print the tree
+// of every call made once the lesson is done, see lessons/mod.rs. any lesson
+// can use call!, without recording it's no more than the call itself.
+
+// the calls being made live in a thread local, like the heap's log, so a
+// function doesn't need to be handed anything to be recorded.
+
+use std::cell::RefCell;
+use std::fmt;
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Call {
+    pub name: &'static str,
+    // how each argument was written, and its value.
+    pub args: Vec<(&'static str, String)>,
+    // None if it panicked, or hasn't returned yet.
+    pub returned: Option<String>,
+    // from the call, to the return, the calls it made included.
+    pub time: Duration,
+    pub calls: Vec<Call>,
+}
+
+impl Call {
+    fn new(name: &'static str) -> Call {
+        Call {
+            name,
+            args: vec![],
+            returned: None,
+            time: Duration::from_secs(0),
+            calls: vec![],
+        }
+    }
+}
+
+// "do_sth(999) -> ()" is just "do_sth(999)", and "do_sth(x = 999)" when the
+// argument is written differently than its value.
+impl fmt::Display for Call {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let args: Vec<String> = self.args.iter().map(|&(written, ref value)| {
+            if written == value { value.clone() } else { format!("{} = {}", written, value) }
+        }).collect();
+        write!(f, "{}({})", self.name, args.join(", "))?;
+        match self.returned {
+            Some(ref returned) if returned == "()" => Ok(()),
+            Some(ref returned) => write!(f, " -> {}", returned),
+            None => write!(f, " never returned"),
+        }
+    }
+}
+
+// the calls that haven't returned yet, innermost last, and when each one
+// was made. the first one of each recording only holds the calls made from
+// the top, it isn't a real call.
+struct Stack {
+    open: Vec<(Call, Instant)>,
+}
+
+thread_local! {
+    static STACK: RefCell<Stack> = const { RefCell::new(Stack { open: vec![] }) };
+}
+
+fn recording() -> bool {
+    STACK.with(|stack| !stack.borrow().open.is_empty())
+}
+
+fn enter(name: &'static str) {
+    STACK.with(|stack| stack.borrow_mut().open.push((Call::new(name), Instant::now())));
+}
+
+fn leave(returned: Option<String>) {
+    STACK.with(|stack| {
+        let mut stack = stack.borrow_mut();
+        let (mut call, started) = stack.open.pop().expect("a call to leave");
+        call.time = started.elapsed();
+        call.returned = returned;
+        if let Some(&mut (ref mut caller, _)) = stack.open.last_mut() {
+            caller.calls.push(call);
+        }
+    })
+}
+
+// leaves the call when dropped, so a call that panics is still left, and
+// the ones around it end up where they belong.
+struct Frame {
+    returned: Option<String>,
+}
+
+impl Drop for Frame {
+    fn drop(&mut self) {
+        leave(self.returned.take());
+    }
+}
+
+// stops a recording, even one that panicked, and hands back its calls.
+struct Recording {
+    depth: usize,
+}
+
+impl Recording {
+    fn stop(&self) -> Vec<Call> {
+        STACK.with(|stack| {
+            let mut stack = stack.borrow_mut();
+            stack.open.truncate(self.depth + 1);
+            match stack.open.pop() {
+                Some((top, _)) => {
+                    // a recording inside another one, the calls are in both.
+                    if let Some(&mut (ref mut caller, _)) = stack.open.last_mut() {
+                        caller.calls.extend(top.calls.iter().cloned());
+                    }
+                    top.calls
+                }
+                None => vec![],
+            }
+        })
+    }
+}
+
+// only does anything if f panicked, and the recording was never stopped.
+impl Drop for Recording {
+    fn drop(&mut self) {
+        STACK.with(|stack| stack.borrow_mut().open.truncate(self.depth));
+    }
+}
+
+// runs f, and hands back what it returned, along with every call made in
+// it, as they were made. recordings can be nested, the calls made in the
+// inner one are in both.
+pub fn record<R, F: FnOnce() -> R>(f: F) -> (R, Vec<Call>) {
+    let depth = STACK.with(|stack| stack.borrow().open.len());
+    enter("");
+    let recording = Recording { depth };
+    let result = f();
+    (result, recording.stop())
+}
+
+// the call! macro, enters a call, with what it's given, and leaves it with
+// what f returned.
+pub fn call<R: fmt::Debug, F: FnOnce() -> R>(name: &'static str, f: F) -> R {
+    if !recording() {
+        return f();
+    }
+    enter(name);
+    let mut frame = Frame { returned: None };
+    let returned = f();
+    frame.returned = Some(format!("{:?}", returned));
+    returned
+}
+
+// an argument of the call just entered, handed back as it is.
+pub fn arg<T: fmt::Debug>(written: &'static str, value: T) -> T {
+    STACK.with(|stack| {
+        if let Some(&mut (ref mut call, _)) = stack.borrow_mut().open.last_mut() {
+            call.args.push((written, format!("{:?}", value)));
+        }
+    });
+    value
+}
+
+// call!(f(a, b)) records a call to f, with a and b, call!(f(ctx; a, b))
+// passes ctx along too, without writing it down.
+#[macro_export]
+macro_rules! call {
+    ($f:ident($($pass:expr),+ ; $($arg:expr),*)) => {
+        $crate::calls::call(stringify!($f), || $f($($pass,)+ $($crate::calls::arg(stringify!($arg), $arg)),*))
+    };
+    ($f:ident($($arg:expr),*)) => {
+        $crate::calls::call(stringify!($f), || $f($($crate::calls::arg(stringify!($arg), $arg)),*))
+    };
+}
+
+// "1.5ms", to the microsecond, or "250ns" for anything quicker.
+pub fn duration(time: Duration) -> String {
+    let nanos = time.as_nanos();
+    if nanos < 1_000 {
+        format!("{}ns", nanos)
+    } else if nanos < 1_000_000 {
+        format!("{:.1}µs", nanos as f64 / 1e3)
+    } else if nanos < 1_000_000_000 {
+        format!("{:.3}ms", nanos as f64 / 1e6)
+    } else {
+        format!("{:.3}s", time.as_secs_f64())
+    }
+}
+
+// a line for every call, indented under the one that made it, with how long
+// it took at the end, in brackets.
+pub fn tree(calls: &[Call]) -> Vec<String> {
+    let mut lines = vec![];
+    grow(calls, "", &mut lines);
+    lines
+}
+
+fn grow(calls: &[Call], indent: &str, lines: &mut Vec<String>) {
+    for (i, call) in calls.iter().enumerate() {
+        let last = i + 1 == calls.len();
+        lines.push(format!("{}{}{}  [{}]", indent, if last { "`- " } else { "|- " }, call, duration(call.time)));
+        grow(&call.calls, &format!("{}{}", indent, if last { "   " } else { "|  " }), lines);
+    }
+}
@@ -0,0 +1,106 @@
+// command line arguments
+
+// the binaries take a handful of options, like "--threads 4" or a
+// subcommand like "bench", this is a tiny helper to pick them out of
+// std::env::args, without pulling in a whole argument parsing crate.
+
+// options are taken out of the list as they are looked up, so whatever is
+// left at the end was not understood, and can be reported as an error.
+
+use std::fmt::Display;
+use std::process;
+use std::str::FromStr;
+
+// how much the binaries should say, "--quiet" keeps it to the results,
+// "--verbose" prints every step of a loop, and normal is in between.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Verbosity {
+    Quiet,
+    Normal,
+    Verbose,
+}
+
+pub struct Args {
+    args: Vec<String>,
+}
+
+impl Args {
+    // the first argument is the name of the program itself, so it's skipped.
+    pub fn from_env() -> Args {
+        Args::from_vec(std::env::args().skip(1).collect())
+    }
+
+    pub fn from_vec(args: Vec<String>) -> Args {
+        Args { args }
+    }
+
+    // true if the flag, e.g. "--quiet", was given.
+    pub fn flag(&mut self, name: &str) -> bool {
+        match self.args.iter().position(|arg| arg == name) {
+            Some(i) => {
+                self.args.remove(i);
+                true
+            }
+            None => false,
+        }
+    }
+
+    // the value of an option, given either as "--name value" or "--name=value",
+    // and parsed into whatever type is asked for.
+    pub fn value<T>(&mut self, name: &str) -> Result<Option<T>, String>
+    where
+        T: FromStr,
+        T::Err: Display,
+    {
+        let prefix = format!("{}=", name);
+
+        let raw = if let Some(i) = self.args.iter().position(|arg| arg == name) {
+            if i + 1 >= self.args.len() {
+                return Err(format!("{} expects a value", name));
+            }
+            self.args.remove(i);
+            self.args.remove(i)
+        } else if let Some(i) = self.args.iter().position(|arg| arg.starts_with(&prefix)) {
+            self.args.remove(i)[prefix.len()..].to_string()
+        } else {
+            return Ok(None);
+        };
+
+        raw.parse()
+            .map(Some)
+            .map_err(|e| format!("invalid value `{}` for {}: {}", raw, name, e))
+    }
+
+    // the next positional argument, e.g. a subcommand, anything starting
+    // with "-" is an option, and not a positional argument.
+    pub fn positional(&mut self) -> Option<String> {
+        match self.args.iter().position(|arg| !arg.starts_with('-')) {
+            Some(i) => Some(self.args.remove(i)),
+            None => None,
+        }
+    }
+
+    pub fn verbosity(&mut self) -> Result<Verbosity, String> {
+        match (self.flag("--quiet"), self.flag("--verbose")) {
+            (true, true) => Err("--quiet and --verbose cannot be used together".to_string()),
+            (true, false) => Ok(Verbosity::Quiet),
+            (false, true) => Ok(Verbosity::Verbose),
+            (false, false) => Ok(Verbosity::Normal),
+        }
+    }
+
+    // errors out if anything was left over, that nobody asked for.
+    pub fn finish(self) -> Result<(), String> {
+        match self.args.first() {
+            Some(arg) => Err(format!("unexpected argument `{}`", arg)),
+            None => Ok(()),
+        }
+    }
+}
+
+// prints the error along with the usage text, and exits, the exit code 2 is
+// what most command line tools use for wrong usage.
+pub fn exit_with_usage(error: &str, usage: &str) -> ! {
+    eprintln!("error: {}\n\n{}", error, usage);
+    process::exit(2)
+}
@@ -0,0 +1,177 @@
+// the context a lesson runs in
+
+// a lesson never touches stdin or stdout directly, it reads and writes
+// through its context instead. the binaries hand it a context wired up to
+// the terminal, while tests, or anything else that wants to run a lesson,
+// can hand it one that reads from a string and writes into a buffer.
+
+use std::io::{self, BufRead, Cursor};
+use std::ops::{Deref, DerefMut};
+use std::path::PathBuf;
+
+use cli::{Args, Verbosity};
+use output::{Capture, Format, Output};
+use store;
+
+// the options every lesson is run with, they are read from the command line
+// up front, so any mistake is reported before the lesson starts talking.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Options {
+    pub verbosity: Verbosity,
+    pub threads: Option<usize>,
+    pub max: Option<i64>,
+    pub command: Option<String>,
+    // go through the lesson a section at a time.
+    pub step: bool,
+    // print every call! made, as a tree, once the lesson is done.
+    pub calls: bool,
+    // picks the quiz order, a fresh one every time if not given.
+    pub seed: Option<u64>,
+    // whose quiz results these are.
+    pub user: Option<String>,
+}
+
+impl Default for Options {
+    fn default() -> Options {
+        Options {
+            verbosity: Verbosity::Normal,
+            threads: None,
+            max: None,
+            command: None,
+            step: false,
+            calls: false,
+            seed: None,
+            user: None,
+        }
+    }
+}
+
+pub const OPTIONS: &str = "options:
+  --format human|json   print prose, or one json event per line
+  --quiet | --verbose   say less, or print every step of long loops
+  --step                go through a lesson a section at a time
+  --calls               show the functions the lesson called, and how long they took
+  --threads N           split long loops over N threads
+  --max N               the largest number a benchmark goes up to
+  --seed N              ask quiz questions in the same order every time
+  --user NAME           whose progress to keep, $RUSTY_USER or $USER by default";
+
+impl Options {
+    // reads the options, and a command, if it's one of the commands given.
+    pub fn from_args(args: &mut Args, commands: &[&str]) -> Result<Options, String> {
+        let mut options = Options::read(args)?;
+        options.command = args.positional();
+        options.check_command(commands)?;
+        Ok(options)
+    }
+
+    // reads the options alone, leaving any positional arguments, like the
+    // lesson, or the command, where they are. the options with values have
+    // to go first, so a value, like the name in "--user bob", isn't taken
+    // for one of them.
+    pub fn read(args: &mut Args) -> Result<Options, String> {
+        let options = Options {
+            verbosity: args.verbosity()?,
+            threads: args.value("--threads")?,
+            max: args.value("--max")?,
+            step: args.flag("--step"),
+            calls: args.flag("--calls"),
+            seed: args.value("--seed")?,
+            user: args.value("--user")?,
+            command: None,
+        };
+
+        if options.threads == Some(0) {
+            return Err("--threads must be at least 1".to_string());
+        }
+
+        if let Some(ref user) = options.user {
+            store::check_user(user)?;
+        }
+
+        Ok(options)
+    }
+
+    // errors out if the command isn't one of the commands given.
+    pub fn check_command(&self, commands: &[&str]) -> Result<(), String> {
+        match self.command {
+            Some(ref command) if !commands.contains(&command.as_str()) => {
+                Err(format!("unknown command `{}`", command))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+pub struct Context {
+    pub out: Output,
+    pub options: Options,
+    // where to keep things between runs, like the learner's progress, see
+    // store.rs, with none, nothing is kept.
+    pub home: Option<PathBuf>,
+    input: Box<dyn BufRead>,
+}
+
+impl Context {
+    pub fn new(out: Output, input: Box<dyn BufRead>, options: Options) -> Context {
+        Context {
+            out,
+            options,
+            home: None,
+            input,
+        }
+    }
+
+    // a context reading from stdin, writing to stdout, and keeping things
+    // in the learner's home directory.
+    pub fn stdio(bin: &str, format: Format, options: Options) -> Context {
+        let mut ctx = Context::new(
+            Output::new(bin, format),
+            Box::new(io::stdin().lock()),
+            options,
+        );
+        ctx.home = store::home().ok();
+        ctx
+    }
+
+    // a context reading the given input, and capturing everything written,
+    // the capture can be read back at any time. it keeps nothing, unless
+    // given a home.
+    pub fn capture(format: Format, options: Options, input: &str) -> (Context, Capture) {
+        let capture = Capture::new();
+        let out = Output::with_writer("rust_commons", format, Box::new(capture.clone()));
+        let input = Box::new(Cursor::new(input.as_bytes().to_vec()));
+        (Context::new(out, input, options), capture)
+    }
+
+    // works just like io::stdin().read_line, it appends a line to buf, and
+    // returns how many bytes were read, 0 meaning there is nothing left.
+    pub fn read_line(&mut self, buf: &mut String) -> io::Result<usize> {
+        self.input.read_line(buf)
+    }
+
+    // whose progress, and results, these are.
+    pub fn user(&self) -> String {
+        self.options.user.clone().unwrap_or_else(store::default_user)
+    }
+
+    pub fn finish(self) {
+        self.out.finish()
+    }
+}
+
+// a context is mostly its output, so everything an output can do, like
+// ctx.value("x", &x), or outln!(ctx, ..), can be done on the context directly.
+impl Deref for Context {
+    type Target = Output;
+
+    fn deref(&self) -> &Output {
+        &self.out
+    }
+}
+
+impl DerefMut for Context {
+    fn deref_mut(&mut self) -> &mut Output {
+        &mut self.out
+    }
+}
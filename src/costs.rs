@@ -0,0 +1,202 @@
+// what a move, a clone, and a borrow cost
+
+// the ownership lesson's takes_and_gives_back clones its String twice, just
+// so it can hand one back. "rust_commons ownership bench" measures how much
+// that costs, next to moving, and borrowing, for Strings and Vecs of growing
+// sizes, both in time, and in what's asked of the allocator, see heap.rs.
+
+// the allocations are counted on a single call, with the counting allocator,
+// they're the same every time, the time is the average over many calls, as
+// a single one is too quick to measure.
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use heap;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Strategy {
+    // hand the value over, and get it back.
+    Move,
+    // hand over a clone, and keep the original.
+    Clone,
+    // hand over a reference.
+    Borrow,
+}
+
+impl Strategy {
+    pub fn name(&self) -> &'static str {
+        match *self {
+            Strategy::Move => "move",
+            Strategy::Clone => "clone",
+            Strategy::Borrow => "borrow",
+        }
+    }
+}
+
+pub const STRATEGIES: [Strategy; 3] = [Strategy::Move, Strategy::Clone, Strategy::Borrow];
+
+// what's handed to the functions being measured.
+pub trait Payload: Clone {
+    fn name() -> &'static str;
+    // a payload of size bytes, or elements.
+    fn make(size: usize) -> Self;
+    fn size(&self) -> usize;
+}
+
+impl Payload for String {
+    fn name() -> &'static str {
+        "String"
+    }
+
+    fn make(size: usize) -> String {
+        "x".repeat(size)
+    }
+
+    fn size(&self) -> usize {
+        self.len()
+    }
+}
+
+impl Payload for Vec<u64> {
+    fn name() -> &'static str {
+        "Vec<u64>"
+    }
+
+    fn make(size: usize) -> Vec<u64> {
+        (0..size as u64).collect()
+    }
+
+    fn size(&self) -> usize {
+        self.len()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Measurement {
+    pub payload: &'static str,
+    pub what: &'static str,
+    pub size: usize,
+    // the average time of a call.
+    pub time: Duration,
+    // allocs and reallocs, and the bytes they asked for, None when the
+    // counting allocator isn't installed.
+    pub allocs: Option<usize>,
+    pub bytes: Option<usize>,
+}
+
+// how many times a call is made to time it, fewer for bigger payloads.
+pub fn rounds(size: usize) -> usize {
+    (1_000_000 / size.max(1)).clamp(1, 1000)
+}
+
+// times f over every input, and counts the allocations of one more call.
+fn measure<I, F: FnMut(I)>(payload: &'static str, what: &'static str, size: usize, mut inputs: Vec<I>, mut f: F) -> Measurement {
+    let last = inputs.pop().expect("at least one input");
+    let rounds = inputs.len().max(1);
+
+    let start = Instant::now();
+    for input in inputs {
+        f(input);
+    }
+    let time = start.elapsed() / rounds as u32;
+
+    let (_, trace) = heap::trace(|| f(last));
+    let counted = heap::installed();
+    Measurement {
+        payload,
+        what,
+        size,
+        time,
+        allocs: if counted { Some(trace.allocs() + trace.reallocs()) } else { None },
+        bytes: if counted { Some(trace.bytes()) } else { None },
+    }
+}
+
+fn takes_and_gives_back<T: Payload>(value: T) -> (T, usize) {
+    let size = value.size();
+    (value, size)
+}
+
+fn takes<T: Payload>(value: T) -> usize {
+    value.size()
+}
+
+fn borrows<T: Payload>(value: &T) -> usize {
+    value.size()
+}
+
+// hands a payload of the given size to a function, the way the strategy says.
+pub fn strategy<T: Payload>(strategy: Strategy, size: usize) -> Measurement {
+    let value = T::make(size);
+    let inputs = vec![(); rounds(size) + 1];
+    match strategy {
+        Strategy::Move => {
+            let mut value = Some(value);
+            measure(T::name(), strategy.name(), size, inputs, |_| {
+                let (back, size) = takes_and_gives_back(value.take().unwrap());
+                black_box(size);
+                value = Some(back);
+            })
+        }
+        Strategy::Clone => measure(T::name(), strategy.name(), size, inputs, |_| {
+            black_box(takes(value.clone()));
+        }),
+        Strategy::Borrow => measure(T::name(), strategy.name(), size, inputs, |_| {
+            black_box(borrows(&value));
+        }),
+    }
+}
+
+// the ownership lesson's takes_and_gives_back, as it's written, cloning the
+// String passed in, to make tempstr, and tempstr again, to hand back.
+#[allow(clippy::redundant_clone)]
+pub fn with_two_clones(a_string: String) -> (String, usize) {
+    let mut tempstr: String = a_string.clone();
+    tempstr.push_str(", and shadowed from the outside!");
+    let a_string = tempstr.clone();
+    let length = a_string.len();
+    (a_string, length)
+}
+
+// the String is ours already, so it can be changed, and handed back, as it is.
+pub fn without_clones(mut a_string: String) -> (String, usize) {
+    a_string.push_str(", and shadowed from the outside!");
+    let length = a_string.len();
+    (a_string, length)
+}
+
+// or only borrowed, with a new String made for what's handed back, see the
+// references and borrowing lesson.
+pub fn borrowed(a_string: &str) -> (String, usize) {
+    let shadowed = format!("{}, and shadowed from the outside!", a_string);
+    let length = shadowed.len();
+    (shadowed, length)
+}
+
+pub const VARIANTS: [&str; 3] = ["two clones", "no clones", "borrowed"];
+
+// one of the VARIANTS of takes_and_gives_back, on a String of the given size.
+pub fn variant(name: &'static str, size: usize) -> Measurement {
+    let payload = "takes_and_gives_back";
+    let inputs = vec![String::make(size); rounds(size) + 1];
+    match name {
+        "two clones" => measure(payload, name, size, inputs, |s| drop(black_box(with_two_clones(s)))),
+        "no clones" => measure(payload, name, size, inputs, |s| drop(black_box(without_clones(s)))),
+        _ => measure(payload, name, size, inputs, |s| drop(black_box(borrowed(&s)))),
+    }
+}
+
+// 10, 100, 1000, .. up to max.
+pub fn sizes(max: usize) -> Vec<usize> {
+    let mut sizes = vec![];
+    let mut size = 10;
+    while size <= max.max(10) {
+        sizes.push(size);
+        size = match size.checked_mul(10) {
+            Some(next) => next,
+            None => break,
+        };
+    }
+    sizes
+}
@@ -0,0 +1,195 @@
+// counting, the controlflow lesson's loop, as a library
+
+// the controlflow lesson counts from 0 up (or down) to a chosen number, and
+// for every step adds up each element of a small array, multiplied by the
+// counter. here that same computation is available in three flavours:
+// single threaded, split across worker threads, and in closed form.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::thread;
+
+// how many counters a worker goes through before it reports its progress,
+// updating the shared count on every single step would slow things down.
+const REPORT_EVERY: i64 = 4096;
+
+pub const ARR_NULL: [i128; 9] = [1, 2, 3, 4, 5, 6, 7, 8, 9];
+
+// how many counters the loop visits, including the 0 it starts at.
+pub fn steps(mynum: i64) -> u64 {
+    (mynum as i128).unsigned_abs() as u64 + 1
+}
+
+// the counters visited by the loop, as an inclusive range, the loop always
+// starts at 0 and walks towards mynum, so for negative numbers the range is
+// mynum..=0, but the set of visited counters is the same either way around.
+pub fn bounds(mynum: i64) -> (i64, i64) {
+    if mynum < 0 {
+        (mynum, 0)
+    } else {
+        (0, mynum)
+    }
+}
+
+// the inner part of the loop, for all counters between lo and hi inclusive.
+pub fn partial_sum(lo: i64, hi: i64) -> i128 {
+    let mut sum: i128 = 0;
+    for counter in lo..=hi {
+        for element in ARR_NULL.iter() {
+            sum += element * counter as i128;
+        }
+    }
+    sum
+}
+
+pub fn sum_serial(mynum: i64) -> i128 {
+    let (lo, hi) = bounds(mynum);
+    partial_sum(lo, hi)
+}
+
+// splits lo..=hi into at most `parts` contiguous chunks of nearly equal size,
+// the chunks are returned in order, so combining them in order always visits
+// the counters in the same sequence, no matter how many chunks there are.
+pub fn chunks(lo: i64, hi: i64, parts: usize) -> Vec<(i64, i64)> {
+    let len = (hi as i128 - lo as i128 + 1) as u128;
+    let parts = (parts.max(1) as u128).min(len);
+    let mut out = Vec::with_capacity(parts as usize);
+
+    let mut start = lo as i128;
+    for i in 0..parts {
+        // spread the remainder over the first few chunks.
+        let size = len / parts + if i < len % parts { 1 } else { 0 };
+        let end = start + size as i128 - 1;
+        out.push((start as i64, end as i64));
+        start = end + 1;
+    }
+    out
+}
+
+// the counter the loop is at after `step` steps, it starts at 0, and walks
+// towards mynum.
+pub fn counter(mynum: i64, step: u64) -> i64 {
+    if mynum < 0 {
+        -(step as i64)
+    } else {
+        step as i64
+    }
+}
+
+// the running workers of a threaded sum, started by spawn, and combined
+// into the final value by join. keeping the two apart lets the caller do
+// something else, like reporting progress, while the workers are busy.
+pub struct Workers {
+    handles: Vec<thread::JoinHandle<i128>>,
+    // how many counters each worker has gone through, and how many it has,
+    // in the order the loop would get to them.
+    done: Vec<(Arc<AtomicU64>, u64)>,
+}
+
+pub fn spawn(mynum: i64, threads: usize) -> Workers {
+    let (lo, hi) = bounds(mynum);
+    let down = mynum < 0;
+    let mut done = vec![];
+
+    // each worker owns its own chunk, and hands back its partial sum when
+    // joined, the only thing shared is how far along each one is. a worker
+    // walks its chunk the way the loop does, up from 0, or down from it, so
+    // the counters it's done are always the first ones in its chunk.
+    let handles = chunks(lo, hi, threads)
+        .into_iter()
+        .map(|(lo, hi)| {
+            let count = Arc::new(AtomicU64::new(0));
+            done.push((Arc::clone(&count), (hi as i128 - lo as i128 + 1) as u64));
+            thread::spawn(move || {
+                let mut sum = 0;
+                let (mut lo, mut hi) = (lo, hi);
+                loop {
+                    let (start, end) = if down {
+                        (lo.max(hi.saturating_sub(REPORT_EVERY - 1)), hi)
+                    } else {
+                        (lo, hi.min(lo.saturating_add(REPORT_EVERY - 1)))
+                    };
+                    sum += partial_sum(start, end);
+                    count.fetch_add((end - start + 1) as u64, Ordering::Relaxed);
+                    if down && start > lo {
+                        hi = start - 1;
+                    } else if !down && end < hi {
+                        lo = end + 1;
+                    } else {
+                        break;
+                    }
+                }
+                sum
+            })
+        })
+        .collect();
+
+    // counting down, the chunk with 0 in it, the last one, comes first.
+    if down {
+        done.reverse();
+    }
+    Workers { handles, done }
+}
+
+impl Workers {
+    // how many counters the workers have gone through so far.
+    pub fn done(&self) -> u64 {
+        self.done.iter().map(|(count, _)| count.load(Ordering::Relaxed)).sum()
+    }
+
+    // how many counters, from 0 on, the workers are all past, which is how
+    // far the loop would have got, the chunks after one that isn't done yet
+    // don't count.
+    pub fn reached(&self) -> u64 {
+        let mut reached = 0;
+        for (count, len) in self.done.iter() {
+            let count = count.load(Ordering::Acquire);
+            reached += count;
+            if count < *len {
+                break;
+            }
+        }
+        reached
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.handles.iter().all(|handle| handle.is_finished())
+    }
+
+    // waits for every worker, and adds up the partial sums in chunk order.
+    pub fn join(self) -> i128 {
+        self.handles
+            .into_iter()
+            .map(|handle| handle.join().expect("counting worker panicked"))
+            .sum()
+    }
+}
+
+pub fn sum_threaded(mynum: i64, threads: usize) -> i128 {
+    spawn(mynum, threads).join()
+}
+
+// every step adds (1 + 2 + ... + 9) * counter = 45 * counter, so the whole
+// loop adds 45 times the sum of all counters, and the sum of 0..=n is
+// n * (n + 1) / 2, for negative numbers the counters are the same, just
+// with their signs flipped. n * (n + 1) always fits in an i128, even for
+// i64::MIN, but 45 times half of it doesn't once n is past about 2.7e18,
+// so then there's no answer, rather than a wrong one, or a panic.
+pub fn sum_closed_form(mynum: i64) -> Option<i128> {
+    let weight: i128 = ARR_NULL.iter().sum();
+    let n = (mynum as i128).abs();
+    let sum = weight.checked_mul(n * (n + 1) / 2)?;
+
+    if mynum < 0 {
+        Some(-sum)
+    } else {
+        Some(sum)
+    }
+}
+
+// the number of threads to use when the user doesn't say.
+pub fn default_threads() -> usize {
+    thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(1)
+}
@@ -0,0 +1,512 @@
+// arithmetic expressions
+
+// the lessons ask for numbers at a prompt, instead of only accepting what
+// str::parse understands, we accept small arithmetic expressions like
+// "2**10 - 1", "(3+4)*5" or "-0x10", and evaluate them to an integer.
+
+// this is done in the classic three steps, a tokenizer turns the text into
+// tokens, a parser turns the tokens into a tree of operations, respecting
+// precedence, and an evaluator walks that tree and computes the value.
+
+// every token and tree node remembers the span of text it came from, so
+// when something goes wrong we can point a caret right at the culprit.
+
+use std::any::type_name;
+use std::convert::TryFrom;
+use std::fmt;
+
+// a span is a range of byte offsets into the input text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+}
+
+impl Span {
+    pub fn new(start: usize, end: usize) -> Span {
+        Span { start, end }
+    }
+
+    pub fn to(self, other: Span) -> Span {
+        Span::new(self.start, other.end)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Error {
+    pub span: Span,
+    pub message: String,
+}
+
+impl Error {
+    fn new(span: Span, message: String) -> Error {
+        Error { span, message }
+    }
+
+    // renders the input with a caret line underneath, pointing at the span
+    // of the error, e.g.
+    //
+    //   (3+4)*5)
+    //          ^ unexpected `)`
+    pub fn render(&self, input: &str) -> String {
+        // columns are counted in chars, not bytes, so the caret lines up
+        // even if the input contains multi-byte characters.
+        let start = input[..self.span.start.min(input.len())].chars().count();
+        let width = input[self.span.start.min(input.len())..self.span.end.min(input.len())]
+            .chars()
+            .count()
+            .max(1);
+
+        format!(
+            "{}\n{}{} {}",
+            input,
+            " ".repeat(start),
+            "^".repeat(width),
+            self.message
+        )
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} (at column {})", self.message, self.span.start + 1)
+    }
+}
+
+// TOKENIZER
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Tok {
+    Num(i128),
+    Plus,
+    Minus,
+    Star,
+    Pow,
+    Slash,
+    Percent,
+    LParen,
+    RParen,
+}
+
+impl Tok {
+    fn describe(&self) -> String {
+        match *self {
+            Tok::Num(n) => format!("number `{}`", n),
+            Tok::Plus => "`+`".to_string(),
+            Tok::Minus => "`-`".to_string(),
+            Tok::Star => "`*`".to_string(),
+            Tok::Pow => "`**`".to_string(),
+            Tok::Slash => "`/`".to_string(),
+            Tok::Percent => "`%`".to_string(),
+            Tok::LParen => "`(`".to_string(),
+            Tok::RParen => "`)`".to_string(),
+        }
+    }
+}
+
+fn tokenize(input: &str) -> Result<Vec<(Tok, Span)>, Error> {
+    let bytes = input.as_bytes();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let c = bytes[i];
+
+        if c.is_ascii_whitespace() {
+            i += 1;
+            continue;
+        }
+
+        if c.is_ascii_digit() {
+            let (value, end) = number(input, i)?;
+            tokens.push((Tok::Num(value), Span::new(i, end)));
+            i = end;
+            continue;
+        }
+
+        let (tok, len) = match c {
+            b'+' => (Tok::Plus, 1),
+            b'-' => (Tok::Minus, 1),
+            b'*' if bytes.get(i + 1) == Some(&b'*') => (Tok::Pow, 2),
+            b'*' => (Tok::Star, 1),
+            b'/' => (Tok::Slash, 1),
+            b'%' => (Tok::Percent, 1),
+            b'(' => (Tok::LParen, 1),
+            b')' => (Tok::RParen, 1),
+            _ => {
+                // the offending character may be more than one byte long.
+                let ch = input[i..].chars().next().unwrap();
+                let hint = if ch == '^' {
+                    ", use `**` for powers"
+                } else {
+                    ""
+                };
+                return Err(Error::new(
+                    Span::new(i, i + ch.len_utf8()),
+                    format!("unexpected character `{}`{}", ch, hint),
+                ));
+            }
+        };
+
+        tokens.push((tok, Span::new(i, i + len)));
+        i += len;
+    }
+
+    Ok(tokens)
+}
+
+// reads an integer literal starting at `start`, in decimal, or with one of
+// the 0x, 0o and 0b prefixes, '_' is accepted as a visual separator, just
+// like in rust source code.
+fn number(input: &str, start: usize) -> Result<(i128, usize), Error> {
+    let bytes = input.as_bytes();
+
+    let (radix, digits_start) = match (bytes[start], bytes.get(start + 1)) {
+        (b'0', Some(&b'x')) => (16, start + 2),
+        (b'0', Some(&b'o')) => (8, start + 2),
+        (b'0', Some(&b'b')) => (2, start + 2),
+        _ => (10, start),
+    };
+
+    let mut end = digits_start;
+    while end < bytes.len() && (bytes[end].is_ascii_alphanumeric() || bytes[end] == b'_') {
+        end += 1;
+    }
+
+    let span = Span::new(start, end);
+    let mut value: i128 = 0;
+    let mut seen_digit = false;
+
+    for (offset, ch) in input[digits_start..end].char_indices() {
+        if ch == '_' {
+            continue;
+        }
+        let digit = match ch.to_digit(radix) {
+            Some(digit) => digit,
+            None => {
+                let at = digits_start + offset;
+                return Err(Error::new(
+                    Span::new(at, at + 1),
+                    format!("invalid digit `{}` in base {} number", ch, radix),
+                ));
+            }
+        };
+        seen_digit = true;
+        value = match value
+            .checked_mul(i128::from(radix))
+            .and_then(|v| v.checked_add(i128::from(digit)))
+        {
+            Some(value) => value,
+            None => return Err(Error::new(span, "number is too large".to_string())),
+        };
+    }
+
+    if !seen_digit {
+        return Err(Error::new(span, "expected digits after the prefix".to_string()));
+    }
+
+    Ok((value, end))
+}
+
+// PARSER
+
+// the tree of operations, each node carries the span of the text it covers,
+// binary operations also keep the span of the operator itself, so errors
+// like division by zero can point at the operator.
+#[derive(Debug)]
+enum Node {
+    Num(i128, Span),
+    Neg(Box<Node>, Span),
+    Bin(Op, Span, Box<Node>, Box<Node>),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Op {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Rem,
+    Pow,
+}
+
+impl Node {
+    fn span(&self) -> Span {
+        match *self {
+            Node::Num(_, span) | Node::Neg(_, span) => span,
+            Node::Bin(_, _, ref lhs, ref rhs) => lhs.span().to(rhs.span()),
+        }
+    }
+}
+
+// the grammar, from loosest to tightest binding:
+//
+//   expr   := term (('+' | '-') term)*
+//   term   := unary (('*' | '/' | '%') unary)*
+//   unary  := ('-' | '+') unary | power
+//   power  := atom ('**' unary)?
+//   atom   := number | '(' expr ')'
+//
+// '**' binds tighter than a leading '-', so -2**2 is -(2**2) = -4, and it
+// is right associative, 2**3**2 is 2**(3**2) = 512.
+
+// the parser calls itself for every '(' and every sign, and every operator
+// puts what came before it one level further down the tree, which the
+// evaluator walks by calling itself too, so an expression only goes so
+// deep, far deeper than anyone types in, but not so deep it runs out of
+// stack, and takes the whole program down with it.
+const MAX_DEPTH: usize = 256;
+
+struct Parser<'a> {
+    tokens: &'a [(Tok, Span)],
+    pos: usize,
+    end: usize,
+    // how many '(', signs and operators we're inside of.
+    depth: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn peek(&self) -> Option<Tok> {
+        self.tokens.get(self.pos).map(|&(tok, _)| tok)
+    }
+
+    fn next(&mut self) -> Option<(Tok, Span)> {
+        let tok = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        tok
+    }
+
+    // where the next token is, or the end of the input if there is none.
+    fn here(&self) -> Span {
+        match self.tokens.get(self.pos) {
+            Some(&(_, span)) => span,
+            None => Span::new(self.end, self.end),
+        }
+    }
+
+    // one level deeper, for the token at span.
+    fn enter(&mut self, span: Span) -> Result<(), Error> {
+        if self.depth == MAX_DEPTH {
+            return Err(Error::new(
+                span,
+                format!("too deeply nested, at most {} levels are allowed", MAX_DEPTH),
+            ));
+        }
+        self.depth += 1;
+        Ok(())
+    }
+
+    fn expr(&mut self) -> Result<Node, Error> {
+        let mut lhs = self.term()?;
+        let depth = self.depth;
+        loop {
+            let op = match self.peek() {
+                Some(Tok::Plus) => Op::Add,
+                Some(Tok::Minus) => Op::Sub,
+                _ => {
+                    self.depth = depth;
+                    return Ok(lhs);
+                }
+            };
+            let (_, op_span) = self.next().unwrap();
+            self.enter(op_span)?;
+            let rhs = self.term()?;
+            lhs = Node::Bin(op, op_span, Box::new(lhs), Box::new(rhs));
+        }
+    }
+
+    fn term(&mut self) -> Result<Node, Error> {
+        let mut lhs = self.unary()?;
+        let depth = self.depth;
+        loop {
+            let op = match self.peek() {
+                Some(Tok::Star) => Op::Mul,
+                Some(Tok::Slash) => Op::Div,
+                Some(Tok::Percent) => Op::Rem,
+                _ => {
+                    self.depth = depth;
+                    return Ok(lhs);
+                }
+            };
+            let (_, op_span) = self.next().unwrap();
+            self.enter(op_span)?;
+            let rhs = self.unary()?;
+            lhs = Node::Bin(op, op_span, Box::new(lhs), Box::new(rhs));
+        }
+    }
+
+    fn unary(&mut self) -> Result<Node, Error> {
+        match self.peek() {
+            Some(Tok::Minus) => {
+                let (_, span) = self.next().unwrap();
+                self.enter(span)?;
+                let operand = self.unary()?;
+                self.depth -= 1;
+                let span = span.to(operand.span());
+                Ok(Node::Neg(Box::new(operand), span))
+            }
+            Some(Tok::Plus) => {
+                let (_, span) = self.next().unwrap();
+                self.enter(span)?;
+                let operand = self.unary()?;
+                self.depth -= 1;
+                Ok(operand)
+            }
+            _ => self.power(),
+        }
+    }
+
+    fn power(&mut self) -> Result<Node, Error> {
+        let base = self.atom()?;
+        if self.peek() == Some(Tok::Pow) {
+            let (_, op_span) = self.next().unwrap();
+            self.enter(op_span)?;
+            let exponent = self.unary()?;
+            self.depth -= 1;
+            return Ok(Node::Bin(Op::Pow, op_span, Box::new(base), Box::new(exponent)));
+        }
+        Ok(base)
+    }
+
+    fn atom(&mut self) -> Result<Node, Error> {
+        let here = self.here();
+        match self.next() {
+            Some((Tok::Num(n), span)) => Ok(Node::Num(n, span)),
+            Some((Tok::LParen, open)) => {
+                self.enter(open)?;
+                let inner = self.expr()?;
+                self.depth -= 1;
+                match self.next() {
+                    Some((Tok::RParen, close)) => {
+                        // keep the parentheses in the span, for nicer carets.
+                        Ok(match inner {
+                            Node::Num(n, _) => Node::Num(n, open.to(close)),
+                            Node::Neg(operand, _) => Node::Neg(operand, open.to(close)),
+                            bin => bin,
+                        })
+                    }
+                    Some((tok, span)) => Err(Error::new(
+                        span,
+                        format!("expected `)` to close the `(`, found {}", tok.describe()),
+                    )),
+                    None => Err(Error::new(
+                        open,
+                        "this `(` is never closed".to_string(),
+                    )),
+                }
+            }
+            Some((tok, span)) => Err(Error::new(
+                span,
+                format!("expected a number or `(`, found {}", tok.describe()),
+            )),
+            None => Err(Error::new(here, "expected a number".to_string())),
+        }
+    }
+}
+
+fn parse(input: &str) -> Result<Node, Error> {
+    let tokens = tokenize(input)?;
+    let mut parser = Parser {
+        tokens: &tokens,
+        pos: 0,
+        end: input.len(),
+        depth: 0,
+    };
+
+    let node = parser.expr()?;
+
+    // anything left over means the expression didn't end where it should.
+    if let Some((tok, span)) = parser.next() {
+        return Err(Error::new(span, format!("unexpected {}", tok.describe())));
+    }
+
+    Ok(node)
+}
+
+// EVALUATOR
+
+fn eval(node: &Node) -> Result<i128, Error> {
+    match *node {
+        Node::Num(n, _) => Ok(n),
+        Node::Neg(ref operand, span) => eval(operand)?
+            .checked_neg()
+            .ok_or_else(|| Error::new(span, "negation overflows".to_string())),
+        Node::Bin(op, op_span, ref lhs, ref rhs) => {
+            let a = eval(lhs)?;
+            let b = eval(rhs)?;
+
+            // every operation is checked, an overflow is reported as an error
+            // pointing at the operator, rather than panicking or wrapping.
+            let value = match op {
+                Op::Add => a.checked_add(b),
+                Op::Sub => a.checked_sub(b),
+                Op::Mul => a.checked_mul(b),
+                Op::Div | Op::Rem if b == 0 => {
+                    return Err(Error::new(rhs.span(), "division by zero".to_string()))
+                }
+                Op::Div => a.checked_div(b),
+                Op::Rem => a.checked_rem(b),
+                Op::Pow => {
+                    if b < 0 {
+                        return Err(Error::new(
+                            rhs.span(),
+                            "exponent must not be negative".to_string(),
+                        ));
+                    }
+                    if b > i128::from(u32::MAX) {
+                        None
+                    } else {
+                        a.checked_pow(b as u32)
+                    }
+                }
+            };
+
+            value.ok_or_else(|| {
+                Error::new(op_span, format!("result of `{}` overflows", symbol(op)))
+            })
+        }
+    }
+}
+
+fn symbol(op: Op) -> &'static str {
+    match op {
+        Op::Add => "+",
+        Op::Sub => "-",
+        Op::Mul => "*",
+        Op::Div => "/",
+        Op::Rem => "%",
+        Op::Pow => "**",
+    }
+}
+
+// evaluates an expression using 128 bit integers.
+pub fn eval_i128(input: &str) -> Result<i128, Error> {
+    eval(&parse(input)?)
+}
+
+// evaluates an expression, and checks that the result fits into the type
+// asked for, the intermediate steps are still computed using i128.
+pub fn eval_as<T: TryFrom<i128>>(input: &str) -> Result<T, Error> {
+    let node = parse(input)?;
+    let value = eval(&node)?;
+
+    T::try_from(value).map_err(|_| {
+        Error::new(
+            node.span(),
+            format!("{} does not fit into {}", value, article(type_name::<T>())),
+        )
+    })
+}
+
+// "an i64", "a u8", type names are read out a letter at a time, so it's
+// the sound of the first letter that counts, and u sounds like "you".
+fn article(name: &str) -> String {
+    match name.chars().next() {
+        Some('a') | Some('e') | Some('f') | Some('i') | Some('o') => format!("an {}", name),
+        _ => format!("a {}", name),
+    }
+}
+
+pub fn eval_i64(input: &str) -> Result<i64, Error> {
+    eval_as(input)
+}
@@ -0,0 +1,804 @@
+// floating point, up close
+
+// an f32 or an f64 is a sign, an exponent and a mantissa, packed into 32 or
+// 64 bits the way IEEE-754 lays them out. most decimal numbers, even one as
+// plain as 0.1, have no exact binary form, so what gets stored is the
+// nearest number that does, and the answer to every operation is rounded
+// to the nearest one that does, again.
+
+// this takes a float apart, bit by bit, and works out the exact decimal
+// value it holds, there always is one, a binary fraction always ends in
+// decimal, even if it takes a few hundred digits. it also says how far
+// away the floats either side of it are, and how far off a literal, a cast
+// or an operation ended up, from what it would have been without rounding.
+
+use std::cmp::Ordering;
+use std::fmt;
+
+use literal::{self, Number};
+use overflow::Op;
+
+// DECIMALS
+
+// a decimal number of any size, all of its digits, as a whole number, and
+// how many of them are after the point, plenty to hold any float exactly.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Decimal {
+    negative: bool,
+    // least significant first, without any zeros on the high end.
+    digits: Vec<u8>,
+    scale: usize,
+}
+
+fn trim(digits: &mut Vec<u8>) {
+    while digits.last() == Some(&0) {
+        digits.pop();
+    }
+}
+
+fn compare(a: &[u8], b: &[u8]) -> Ordering {
+    a.len().cmp(&b.len()).then_with(|| a.iter().rev().cmp(b.iter().rev()))
+}
+
+fn add(a: &[u8], b: &[u8]) -> Vec<u8> {
+    let mut sum = Vec::with_capacity(a.len().max(b.len()) + 1);
+    let mut carry = 0;
+    for i in 0..a.len().max(b.len()) {
+        let digit = a.get(i).unwrap_or(&0) + b.get(i).unwrap_or(&0) + carry;
+        sum.push(digit % 10);
+        carry = digit / 10;
+    }
+    if carry > 0 {
+        sum.push(carry);
+    }
+    sum
+}
+
+// a - b, a is the larger of the two.
+fn subtract(a: &[u8], b: &[u8]) -> Vec<u8> {
+    let mut difference = Vec::with_capacity(a.len());
+    let mut borrow = 0;
+    for (i, &digit) in a.iter().enumerate() {
+        let taken = b.get(i).unwrap_or(&0) + borrow;
+        if digit >= taken {
+            difference.push(digit - taken);
+            borrow = 0;
+        } else {
+            difference.push(digit + 10 - taken);
+            borrow = 1;
+        }
+    }
+    trim(&mut difference);
+    difference
+}
+
+fn multiply(a: &[u8], b: &[u8]) -> Vec<u8> {
+    if a.is_empty() || b.is_empty() {
+        return Vec::new();
+    }
+    let mut product = vec![0u32; a.len() + b.len()];
+    for (i, &x) in a.iter().enumerate() {
+        for (j, &y) in b.iter().enumerate() {
+            product[i + j] += x as u32 * y as u32;
+        }
+        // carrying as we go keeps every column well inside a u32.
+        for k in i..product.len() - 1 {
+            product[k + 1] += product[k] / 10;
+            product[k] %= 10;
+        }
+    }
+    let mut product: Vec<u8> = product.into_iter().map(|digit| digit as u8).collect();
+    trim(&mut product);
+    product
+}
+
+fn times_small(digits: &mut Vec<u8>, by: u8) {
+    let mut carry = 0;
+    for digit in digits.iter_mut() {
+        let product = *digit * by + carry;
+        *digit = product % 10;
+        carry = product / 10;
+    }
+    if carry > 0 {
+        digits.push(carry);
+    }
+}
+
+// long division, the way it's done on paper, the quotient and the remainder.
+fn divide(n: &[u8], d: &[u8]) -> (Vec<u8>, Vec<u8>) {
+    let mut quotient = vec![0; n.len()];
+    let mut remainder = Vec::new();
+    for i in (0..n.len()).rev() {
+        remainder.insert(0, n[i]);
+        trim(&mut remainder);
+        while compare(&remainder, d) != Ordering::Less {
+            remainder = subtract(&remainder, d);
+            quotient[i] += 1;
+        }
+    }
+    trim(&mut quotient);
+    (quotient, remainder)
+}
+
+impl Decimal {
+    fn new(negative: bool, mut digits: Vec<u8>, mut scale: usize) -> Decimal {
+        trim(&mut digits);
+        let zeros = digits.iter().take(scale).take_while(|&&digit| digit == 0).count();
+        digits.drain(..zeros);
+        scale -= zeros;
+        if digits.is_empty() {
+            scale = 0;
+        }
+        Decimal { negative: negative && !digits.is_empty(), digits, scale }
+    }
+
+    // -0.0 is a float of its own, with the sign bit set, so a zero taken
+    // from a float keeps its sign, as does one that's negated, or comes out
+    // of arithmetic the way it would with floats, -0 × 1 is -0, and -0 + 0
+    // is 0. otherwise it's the same as 0.
+    fn keeping_sign(mut self, negative: bool) -> Decimal {
+        if self.is_zero() {
+            self.negative = negative;
+        }
+        self
+    }
+
+    pub fn zero() -> Decimal {
+        Decimal::new(false, Vec::new(), 0)
+    }
+
+    pub fn from_int(negative: bool, mut n: u128) -> Decimal {
+        let mut digits = Vec::new();
+        while n > 0 {
+            digits.push((n % 10) as u8);
+            n /= 10;
+        }
+        Decimal::new(negative, digits, 0)
+    }
+
+    // m × 2^power, which is what every float is, 2^-n is 5^n / 10^n, so
+    // it always ends.
+    fn from_binary(negative: bool, m: u64, power: i32) -> Decimal {
+        let mut digits = Decimal::from_int(false, m as u128).digits;
+        let by = if power < 0 { 5 } else { 2 };
+        for _ in 0..power.abs() {
+            times_small(&mut digits, by);
+        }
+        Decimal::new(negative, digits, if power < 0 { -power as usize } else { 0 }).keeping_sign(negative)
+    }
+
+    // reads the digits of a decimal number, like "-23.32", "1_000.5" or
+    // "2.5e-4", the exponent can't go past a few thousand, that's more
+    // digits than any float needs.
+    pub fn parse(text: &str) -> Option<Decimal> {
+        let text: String = text.chars().filter(|&c| c != '_').collect();
+        let (negative, text) = match text.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, &text[..]),
+        };
+        let (mantissa, exponent) = match text.find(['e', 'E']) {
+            Some(i) => (&text[..i], text[i + 1..].trim_start_matches('+').parse::<i64>().ok()?),
+            None => (text, 0),
+        };
+        if exponent.abs() > 4000 {
+            return None;
+        }
+        let (whole, fraction) = match mantissa.find('.') {
+            Some(i) => (&mantissa[..i], &mantissa[i + 1..]),
+            None => (mantissa, ""),
+        };
+        if whole.is_empty() || !whole.bytes().chain(fraction.bytes()).all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+        let mut digits: Vec<u8> = whole.bytes().chain(fraction.bytes()).rev().map(|b| b - b'0').collect();
+        let scale = fraction.len() as i64 - exponent;
+        if scale < 0 {
+            digits.splice(0..0, std::iter::repeat_n(0, -scale as usize));
+        }
+        Some(Decimal::new(negative, digits, scale.max(0) as usize))
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.digits.is_empty()
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.negative
+    }
+
+    pub fn negated(&self) -> Decimal {
+        Decimal::new(!self.negative, self.digits.clone(), self.scale).keeping_sign(!self.negative)
+    }
+
+    fn abs(&self) -> Decimal {
+        Decimal::new(false, self.digits.clone(), self.scale)
+    }
+
+    // the digits, with zeros put on the end, so there are `scale` of them
+    // after the point.
+    fn aligned(&self, scale: usize) -> Vec<u8> {
+        let mut digits = vec![0; scale - self.scale];
+        if !self.is_zero() {
+            digits.extend_from_slice(&self.digits);
+        }
+        digits
+    }
+
+    pub fn plus(&self, other: &Decimal) -> Decimal {
+        let scale = self.scale.max(other.scale);
+        let (a, b) = (self.aligned(scale), other.aligned(scale));
+        if self.negative == other.negative {
+            return Decimal::new(self.negative, add(&a, &b), scale).keeping_sign(self.negative);
+        }
+        let (a, b) = (Decimal::new(false, a, 0), Decimal::new(false, b, 0));
+        match compare(&a.digits, &b.digits) {
+            Ordering::Less => Decimal::new(other.negative, subtract(&b.digits, &a.digits), scale),
+            _ => Decimal::new(self.negative, subtract(&a.digits, &b.digits), scale),
+        }
+    }
+
+    pub fn minus(&self, other: &Decimal) -> Decimal {
+        self.plus(&other.negated())
+    }
+
+    pub fn times(&self, other: &Decimal) -> Decimal {
+        let negative = self.negative != other.negative;
+        Decimal::new(negative, multiply(&self.digits, &other.digits), self.scale + other.scale).keeping_sign(negative)
+    }
+
+    // self / other, to at least `precision` significant digits, and whether
+    // that's all of them, 1 / 3 goes on forever. other isn't zero.
+    pub fn divided(&self, other: &Decimal, precision: usize) -> (Decimal, bool) {
+        // adding zeros to the end of self, before dividing, is what gives
+        // the quotient its digits after the point.
+        let zeros = (precision + other.digits.len()).saturating_sub(self.digits.len());
+        let mut n = vec![0; zeros];
+        n.extend_from_slice(&self.digits);
+        let (mut quotient, remainder) = divide(&n, &other.digits);
+        let scale = self.scale as i64 + zeros as i64 - other.scale as i64;
+        if scale < 0 {
+            quotient.splice(0..0, std::iter::repeat_n(0, -scale as usize));
+        }
+        let negative = self.negative != other.negative;
+        (Decimal::new(negative, quotient, scale.max(0) as usize).keeping_sign(negative), remainder.is_empty())
+    }
+
+    // the whole part of self / other, dropping whatever's after the point.
+    pub fn whole_divided(&self, other: &Decimal) -> Decimal {
+        let scale = self.scale.max(other.scale);
+        let (a, b) = (Decimal::new(false, self.aligned(scale), 0), Decimal::new(false, other.aligned(scale), 0));
+        let (quotient, _) = divide(&a.digits, &b.digits);
+        Decimal::new(self.negative != other.negative, quotient, 0)
+    }
+
+    // cut short, to the first n significant digits.
+    pub fn significant(&self, n: usize) -> Decimal {
+        let dropped = self.digits.len().saturating_sub(n);
+        let digits = std::iter::repeat_n(0, dropped).chain(self.digits[dropped..].iter().cloned()).collect();
+        Decimal::new(self.negative, digits, self.scale)
+    }
+
+    // the plain digits when there aren't many, otherwise scientific
+    // notation, and past 40 significant digits, only the first 17 of them,
+    // which is about all anyone reads.
+    pub fn readable(&self) -> String {
+        let plain = self.to_string();
+        if plain.len() <= 12 {
+            plain
+        } else if self.digits.len() <= 40 {
+            self.scientific()
+        } else {
+            format!("about {}", self.significant(17).scientific())
+        }
+    }
+
+    pub fn to_f64(&self) -> f64 {
+        self.to_string().parse().expect("a decimal is a valid f64")
+    }
+
+    // all the digits, but in scientific notation, like 2.5e-17, which is
+    // easier to read than seventeen zeros.
+    pub fn scientific(&self) -> String {
+        if self.is_zero() {
+            return self.to_string();
+        }
+        let digits: String = self.digits.iter().rev().map(|&d| (b'0' + d) as char).collect();
+        let exponent = digits.len() as i64 - 1 - self.scale as i64;
+        let rest = digits[1..].trim_end_matches('0');
+        format!("{}{}{}{}e{}", if self.negative { "-" } else { "" }, &digits[..1],
+                if rest.is_empty() { "" } else { "." }, rest, exponent)
+    }
+}
+
+impl fmt::Display for Decimal {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.is_zero() {
+            return write!(f, "{}0", if self.negative { "-" } else { "" });
+        }
+        let mut digits: String = self.digits.iter().rev().map(|&d| (b'0' + d) as char).collect();
+        if self.scale > 0 {
+            if digits.len() <= self.scale {
+                digits = format!("{}{}", "0".repeat(self.scale + 1 - digits.len()), digits);
+            }
+            digits.insert(digits.len() - self.scale, '.');
+        }
+        write!(f, "{}{}", if self.negative { "-" } else { "" }, digits)
+    }
+}
+
+// FLOATS
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Float {
+    F32(f32),
+    F64(f64),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Class {
+    Zero,
+    Subnormal,
+    Normal,
+    Infinite,
+    Nan,
+}
+
+impl Class {
+    pub fn name(self) -> &'static str {
+        match self {
+            Class::Zero => "zero",
+            Class::Subnormal => "subnormal",
+            Class::Normal => "normal",
+            Class::Infinite => "infinite",
+            Class::Nan => "not a number",
+        }
+    }
+}
+
+impl Float {
+    // the nearest float of the type to a decimal number, which is what
+    // rust's parse gives, it rounds correctly, however many digits there are.
+    pub fn nearest(ty: &str, decimal: &Decimal) -> Float {
+        let text = decimal.to_string();
+        match ty {
+            "f32" => Float::F32(text.parse().expect("a decimal is a valid f32")),
+            _ => Float::F64(text.parse().expect("a decimal is a valid f64")),
+        }
+    }
+
+    pub fn negated(self) -> Float {
+        match self {
+            Float::F32(x) => Float::F32(-x),
+            Float::F64(x) => Float::F64(-x),
+        }
+    }
+
+    pub fn ty(self) -> &'static str {
+        match self {
+            Float::F32(_) => "f32",
+            Float::F64(_) => "f64",
+        }
+    }
+
+    // how many bits the exponent and the mantissa get.
+    pub fn widths(self) -> (u32, u32) {
+        match self {
+            Float::F32(_) => (8, 23),
+            Float::F64(_) => (11, 52),
+        }
+    }
+
+    // what's taken off the stored exponent, so it can be negative too.
+    pub fn bias(self) -> i32 {
+        (1 << (self.widths().0 - 1)) - 1
+    }
+
+    pub fn to_bits(self) -> u64 {
+        match self {
+            Float::F32(x) => x.to_bits() as u64,
+            Float::F64(x) => x.to_bits(),
+        }
+    }
+
+    pub fn to_f64(self) -> f64 {
+        match self {
+            Float::F32(x) => x as f64,
+            Float::F64(x) => x,
+        }
+    }
+
+    // the sign, the exponent and the mantissa, as they're stored.
+    pub fn fields(self) -> (u64, u64, u64) {
+        let (exponent_bits, mantissa_bits) = self.widths();
+        let bits = self.to_bits();
+        (
+            bits >> (exponent_bits + mantissa_bits),
+            (bits >> mantissa_bits) & ((1 << exponent_bits) - 1),
+            bits & ((1 << mantissa_bits) - 1),
+        )
+    }
+
+    pub fn class(self) -> Class {
+        let (_, exponent, mantissa) = self.fields();
+        let all_ones = (1 << self.widths().0) - 1;
+        match (exponent, mantissa) {
+            (0, 0) => Class::Zero,
+            (0, _) => Class::Subnormal,
+            (e, 0) if e == all_ones => Class::Infinite,
+            (e, _) if e == all_ones => Class::Nan,
+            _ => Class::Normal,
+        }
+    }
+
+    // the power of two the significand is multiplied by, and the
+    // significand as a whole number, a normal float has a hidden 1 bit in
+    // front of its mantissa, a subnormal one doesn't, and it's exponent is
+    // stuck at the smallest there is.
+    fn parts(self) -> (i32, u64) {
+        let (_, exponent, mantissa) = self.fields();
+        let mantissa_bits = self.widths().1;
+        match self.class() {
+            Class::Normal => (exponent as i32 - self.bias(), mantissa | 1 << mantissa_bits),
+            _ => (1 - self.bias(), mantissa),
+        }
+    }
+
+    // the exact value, nothing rounded, as long as it's a number.
+    pub fn exact(self) -> Option<Decimal> {
+        match self.class() {
+            Class::Infinite | Class::Nan => None,
+            _ => {
+                let (power, significand) = self.parts();
+                let negative = self.fields().0 == 1;
+                Some(Decimal::from_binary(negative, significand, power - self.widths().1 as i32))
+            }
+        }
+    }
+
+    pub fn next_up(self) -> Float {
+        match self {
+            Float::F32(x) => Float::F32(x.next_up()),
+            Float::F64(x) => Float::F64(x.next_up()),
+        }
+    }
+
+    pub fn next_down(self) -> Float {
+        match self {
+            Float::F32(x) => Float::F32(x.next_down()),
+            Float::F64(x) => Float::F64(x.next_down()),
+        }
+    }
+}
+
+impl fmt::Display for Float {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Float::F32(x) => write!(f, "{:?}", x),
+            Float::F64(x) => write!(f, "{:?}", x),
+        }
+    }
+}
+
+// TAKING A FLOAT APART
+
+pub struct Anatomy {
+    pub value: Float,
+    pub sign: u64,
+    pub exponent: u64,
+    pub mantissa: u64,
+    pub class: Class,
+    // the exponent, less the bias.
+    pub power: i32,
+    // 1.mantissa for a normal float, 0.mantissa for a subnormal one.
+    pub significand: Decimal,
+    pub exact: Option<Decimal>,
+    // the floats either side, and how far away they are.
+    pub below: Option<(Float, Decimal)>,
+    pub above: Option<(Float, Decimal)>,
+}
+
+impl Anatomy {
+    // the bits, with a space between the sign, the exponent and the mantissa.
+    pub fn bits(&self) -> String {
+        let (exponent_bits, mantissa_bits) = self.value.widths();
+        format!("{} {:0e$b} {:0m$b}", self.sign, self.exponent, self.mantissa,
+                e = exponent_bits as usize, m = mantissa_bits as usize)
+    }
+}
+
+pub fn anatomy(value: Float) -> Anatomy {
+    let (sign, exponent, mantissa) = value.fields();
+    let (power, significand) = value.parts();
+    let exact = value.exact();
+    let neighbour = |other: Float| match (&exact, other.exact()) {
+        (Some(exact), Some(other_exact)) => Some((other, other_exact.minus(exact).abs())),
+        _ => None,
+    };
+    Anatomy {
+        value,
+        sign,
+        exponent,
+        mantissa,
+        class: value.class(),
+        power,
+        significand: Decimal::from_binary(false, significand, -(value.widths().1 as i32)),
+        below: neighbour(value.next_down()),
+        above: neighbour(value.next_up()),
+        exact,
+    }
+}
+
+// ROUNDING
+
+// a literal, a cast or an operation, and how far its answer is from the
+// one there would have been if floats could hold any number.
+pub struct Rounding {
+    pub expression: String,
+    pub result: Float,
+    // the answer without rounding, none when there isn't one, like for 1 / 0.
+    pub exact: Option<Decimal>,
+    // whether exact is all of it, 1 / 3 goes on forever, so it's cut short.
+    pub terminates: bool,
+    // result - exact, none when the result isn't a number.
+    pub error: Option<Decimal>,
+    // the error, in units in the last place, the gap between the result
+    // and the next float towards the exact answer, rounding to the nearest
+    // float never makes it more than 0.5.
+    pub ulps: Option<f64>,
+}
+
+impl Rounding {
+    fn new(expression: String, result: Float, exact: Option<Decimal>, terminates: bool) -> Rounding {
+        let error = match (result.exact(), &exact) {
+            (Some(stored), Some(exact)) => Some(stored.minus(exact)),
+            _ => None,
+        };
+        let ulps = error.as_ref().and_then(|error| {
+            let towards = if error.is_negative() { result.next_up() } else { result.next_down() };
+            let gap = towards.exact()?.minus(&result.exact()?).abs();
+            Some(if error.is_zero() { 0.0 } else { error.to_f64() / gap.to_f64() })
+        });
+        Rounding { expression, result, exact, terminates, error, ulps }
+    }
+}
+
+// writing a decimal number down, as a float of the type.
+pub fn literal(text: &str, ty: &str) -> Result<Rounding, String> {
+    let exact = Decimal::parse(text).ok_or_else(|| format!("`{}` has too many digits to work with", text))?;
+    let result = Float::nearest(ty, &exact);
+    Ok(Rounding::new(text.to_string(), result, Some(exact), true))
+}
+
+// value as f32, or as f64, going up to an f64 never rounds.
+pub fn cast(expression: &str, value: Float, ty: &str) -> Rounding {
+    let result = match ty {
+        "f32" => Float::F32(value.to_f64() as f32),
+        _ => Float::F64(value.to_f64()),
+    };
+    Rounding::new(expression.to_string(), result, value.exact(), true)
+}
+
+// an integer as f32, or as f64, past 2^24, or 2^53, not every integer
+// fits, 16777217 as f32 is 16777216.
+pub fn cast_int(expression: &str, negative: bool, n: u128, ty: &str) -> Rounding {
+    let exact = Decimal::from_int(negative, n);
+    Rounding::new(expression.to_string(), Float::nearest(ty, &exact), Some(exact), true)
+}
+
+// a op b, where a and b are the same type of float.
+pub fn operation(expression: &str, a: Float, op: Op, b: Float) -> Result<Rounding, String> {
+    let result = match (a, b) {
+        (Float::F32(x), Float::F32(y)) => Float::F32(match op {
+            Op::Add => x + y,
+            Op::Sub => x - y,
+            Op::Mul => x * y,
+            Op::Div => x / y,
+            Op::Rem => x % y,
+            _ => return Err(format!("`{}` isn't something a float can do", op.symbol())),
+        }),
+        (Float::F64(x), Float::F64(y)) => Float::F64(match op {
+            Op::Add => x + y,
+            Op::Sub => x - y,
+            Op::Mul => x * y,
+            Op::Div => x / y,
+            Op::Rem => x % y,
+            _ => return Err(format!("`{}` isn't something a float can do", op.symbol())),
+        }),
+        _ => return Err(format!("mismatched types, expected `{}`, found `{}`", a.ty(), b.ty())),
+    };
+
+    let (x, y) = match (a.exact(), b.exact()) {
+        (Some(x), Some(y)) => (x, y),
+        _ => return Ok(Rounding::new(expression.to_string(), result, None, true)),
+    };
+    let divides = !y.is_zero() || !matches!(op, Op::Div | Op::Rem);
+    let (exact, terminates) = match op {
+        _ if !divides => return Ok(Rounding::new(expression.to_string(), result, None, true)),
+        Op::Add => (x.plus(&y), true),
+        Op::Sub => (x.minus(&y), true),
+        Op::Mul => (x.times(&y), true),
+        Op::Div => x.divided(&y, 40),
+        // the remainder is always exact, it takes a whole number of b's
+        // away from a, and what's left fits in the bits a has.
+        _ => (x.minus(&x.whole_divided(&y).times(&y)), true),
+    };
+    Ok(Rounding::new(expression.to_string(), result, Some(exact), terminates))
+}
+
+// EXPLORING
+
+pub const EXAMPLES: &str = "0.1, 0.1 + 0.2, 23.32f32 % 2.0, 16777217 as f32, 1e-45f32 or f64::NAN";
+
+// the constants f32 and f64 have, the ones no literal can be written for,
+// like NAN, and the ones that are hard to write down, like MAX.
+const CONSTANTS: &[&str] = &["NAN", "INFINITY", "NEG_INFINITY", "MAX", "MIN", "MIN_POSITIVE", "EPSILON"];
+
+fn constant(ty: &str, name: &str) -> Option<f64> {
+    let f32 = |x: f32| x as f64;
+    Some(match (ty, name) {
+        (_, "NAN") => f64::NAN,
+        (_, "INFINITY") => f64::INFINITY,
+        (_, "NEG_INFINITY") => f64::NEG_INFINITY,
+        ("f32", "MAX") => f32(f32::MAX),
+        ("f32", "MIN") => f32(f32::MIN),
+        ("f32", "MIN_POSITIVE") => f32(f32::MIN_POSITIVE),
+        ("f32", "EPSILON") => f32(f32::EPSILON),
+        (_, "MAX") => f64::MAX,
+        (_, "MIN") => f64::MIN,
+        (_, "MIN_POSITIVE") => f64::MIN_POSITIVE,
+        (_, "EPSILON") => f64::EPSILON,
+        _ => return None,
+    })
+}
+
+// a number literal, and its exact value, or why it isn't one.
+struct Operand {
+    ty: &'static str,
+    defaulted: bool,
+    negative: bool,
+    integer: Option<u128>,
+    // without the minus, so -0.0 keeps its sign, as the nearest float.
+    magnitude: Decimal,
+    // NaN, inf, or a constant, like f64::MAX, as it is, with its minus, if
+    // it had one, f32's are held in an f64 just as well.
+    special: Option<f64>,
+}
+
+impl Operand {
+    // none for NaN and the infinities, they aren't numbers.
+    fn exact(&self) -> Option<Decimal> {
+        match self.special {
+            Some(x) => Float::F64(x).exact(),
+            None if self.negative => Some(self.magnitude.negated()),
+            None => Some(self.magnitude.clone()),
+        }
+    }
+
+    fn float(&self, ty: &str) -> Float {
+        if let Some(x) = self.special {
+            return match ty {
+                "f32" => Float::F32(x as f32),
+                _ => Float::F64(x),
+            };
+        }
+        let float = Float::nearest(ty, &self.magnitude);
+        if self.negative { float.negated() } else { float }
+    }
+}
+
+// "NaN", "inf" or "infinity", in any case, the way str::parse reads them,
+// which take the type of the other side, like a literal without a suffix,
+// or one of the constants, like "f32::MAX", all with a minus, or without.
+fn special(text: &str) -> Result<Option<Operand>, String> {
+    let (negative, rest) = match text.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, text),
+    };
+    let lower = rest.to_ascii_lowercase();
+    let (ty, defaulted, x) = if lower == "nan" {
+        ("f64", true, f64::NAN)
+    } else if lower == "inf" || lower == "infinity" {
+        ("f64", true, f64::INFINITY)
+    } else if let Some((ty, name)) = rest.split_once("::") {
+        let ty = match literal::FLOAT_TYPES.iter().find(|&&float| float == ty) {
+            Some(ty) => *ty,
+            None => return Ok(None),
+        };
+        let x = constant(ty, name).ok_or_else(|| {
+            format!("`{}` isn't one of {}'s constants, try {}", rest, ty, CONSTANTS.join(", "))
+        })?;
+        (ty, false, x)
+    } else {
+        return Ok(None);
+    };
+    Ok(Some(Operand {
+        ty,
+        defaulted,
+        negative,
+        integer: None,
+        magnitude: Decimal::zero(),
+        special: Some(if negative { -x } else { x }),
+    }))
+}
+
+fn operand(text: &str) -> Result<Operand, String> {
+    if let Some(operand) = special(text)? {
+        return Ok(operand);
+    }
+    let inspection = literal::inspect(text).map_err(|e| e.message)?;
+    let (integer, magnitude) = match inspection.literal.number {
+        Number::Int(n) => (Some(n), Decimal::from_int(false, n)),
+        Number::Float(_) => {
+            // the f64 the literal was read into has already been rounded,
+            // so its digits are read again, without the minus or the suffix.
+            let start = if inspection.negative { 1 } else { 0 };
+            let digits = &text[start..text.len() - inspection.literal.suffix.map_or(0, str::len)];
+            let magnitude = Decimal::parse(digits).ok_or_else(|| format!("`{}` has too many digits to work with", text))?;
+            (None, magnitude)
+        }
+    };
+    Ok(Operand {
+        ty: inspection.ty,
+        defaulted: inspection.defaulted,
+        negative: inspection.negative,
+        integer,
+        magnitude,
+        special: None,
+    })
+}
+
+fn float_operand(text: &str) -> Result<Operand, String> {
+    let operand = operand(text)?;
+    if operand.integer.is_some() {
+        return Err(format!("`{}` is an integer, and rust doesn't mix integers and floats, write it as {}.0", text, text));
+    }
+    Ok(operand)
+}
+
+// reads a float literal, like "0.1", or NaN, inf, or a constant, like
+// f32::MAX, a cast, like "16777217 as f32", or an operation on two floats,
+// like "0.1 + 0.2", and works out how it's rounded.
+pub fn explore(line: &str) -> Result<Rounding, String> {
+    let words: Vec<&str> = line.split_whitespace().collect();
+    match words[..] {
+        [text] => {
+            let operand = operand(text)?;
+            if operand.integer.is_some() {
+                return Err(format!("`{}` is an integer, try {}.0, or {} as f32", text, text, text));
+            }
+            Ok(Rounding::new(text.to_string(), operand.float(operand.ty), operand.exact(), true))
+        }
+        [text, "as", ty] => {
+            if !literal::FLOAT_TYPES.contains(&ty) {
+                return Err(format!("`{}` isn't a float type, only f32 and f64 are", ty));
+            }
+            let operand = operand(text)?;
+            Ok(match operand.integer {
+                Some(n) => cast_int(line, operand.negative, n, ty),
+                None => cast(line, operand.float(operand.ty), ty),
+            })
+        }
+        [a, symbol, b] => {
+            let op = match symbol {
+                "+" => Op::Add,
+                "-" => Op::Sub,
+                "*" => Op::Mul,
+                "/" => Op::Div,
+                "%" => Op::Rem,
+                _ => return Err(format!("`{}` isn't an operator, try +, -, *, / or %", symbol)),
+            };
+            // a literal without a suffix takes the type of the other side.
+            let (left, right) = (float_operand(a)?, float_operand(b)?);
+            let ty = match (left.defaulted, right.defaulted) {
+                (false, false) if left.ty != right.ty => {
+                    return Err(format!("mismatched types, expected `{}`, found `{}`", left.ty, right.ty));
+                }
+                (false, _) => left.ty,
+                (true, false) => right.ty,
+                (true, true) => "f64",
+            };
+            operation(line, left.float(ty), op, right.float(ty))
+        }
+        _ => Err(format!("that isn't a float, a cast or an operation, try {}", EXAMPLES)),
+    }
+}
@@ -1,4 +1,6 @@
 extern crate rand; // let rust know that we will using external dependencies in this section.
+#[macro_use]
+extern crate rusty; // our own library, shared by all the lessons, lives in src/lib.rs
 
 // Since we don't know what traits rand has and what to use, we can use cargo doc --open command to
 // let cargo build documentation locally for all our defined dependencies and open it in browser
@@ -6,25 +8,59 @@ extern crate rand; // let rust know that we will using external dependencies in
 use std::io;   // rust brings in limited number of types by default,
 use rand::Rng; // for extra, we have to bring in our own types
                // into scope explicitly using "use"
+use rand::{SeedableRng, StdRng};
 
 use std::cmp::Ordering; // another enum like std::io::stdin.read_line.expect => Result
                         // here, the enumerated variants are Less, Greater and Equal
 
-fn main() {
-    println!("take a guess!");
+use rusty::cli::{self, Args};
+use rusty::expr;
+use rusty::json::Value;
+use rusty::output::Output;
+
+const USAGE: &str = "usage: guessing_game [--seed N] [--format human|json]";
 
-    let secret_num = rand::thread_rng().gen_range(1, 101);
+fn main() {
+    let mut args = Args::from_env();
+    let seed: Option<u64> = args.value("--seed")
+        .unwrap_or_else(|e| cli::exit_with_usage(&e, USAGE));
+    let format = args.format()
+        .unwrap_or_else(|e| cli::exit_with_usage(&e, USAGE));
+    args.finish().unwrap_or_else(|e| cli::exit_with_usage(&e, USAGE));
+
+    // out prints everything for us, as prose, or as json with --format json.
+    let mut out = Output::new("guessing_game", format);
+
+    outln!(out, "take a guess!");
+
+    // with --seed, the secret number is the same on every run, which comes in
+    // handy for testing, without it, a fresh one is picked every time.
+    let secret_num = match seed {
+        Some(seed) => {
+            let mut bytes = [0u8; 32];
+            bytes[..8].copy_from_slice(&seed.to_le_bytes());
+            StdRng::from_seed(bytes).gen_range(1, 101)
+        }
+        None => rand::thread_rng().gen_range(1, 101),
+    };
+    let mut guesses = 0;
 
     loop {
-        println!("Enter a number ..");
+        outln!(out, "Enter a number ..");
 
         let mut guess = String::new();
         // let is used to create variables mut makes it mutable, :: indicates that new
         // is an associated function of type String
 
-        io::stdin().read_line(&mut guess)
+        let read = io::stdin().read_line(&mut guess)
             .expect("Failed to read line!");
 
+        // read_line returns how many bytes it read, 0 means stdin was closed.
+        if read == 0 {
+            out.end_of_input();
+            return out.finish();
+        }
+
         //  returns an instance of std::io::stdin &mut guess is our argument passed to read_line
         // '&' indicates the argument passed, is a reference, using references allows us to be efficient
         // avoiding copying data, and promotes re-use, mut keeps it mutable.
@@ -42,7 +78,7 @@ fn main() {
         // with a warning from the compiler stating that a result value is not being used,
         // indicating that program isnt handling a possible error
 
-        println!("Your guess was: {}", guess); // {} is for formatting, a la python
+        outln!(out, "Your guess was: {}", guess); // {} is for formatting, a la python
 
         // match is an expression, and is made up of arms, arms consists of a pattern and a piece of
         // code that should be run if the value given to the match expression fits that arm's pattern.
@@ -51,34 +87,52 @@ fn main() {
         // such that both secret_num and guess can be evaluated by ::cmp::Ordering, as Ordering needs
         // it's operands to be share a common numeric type.
 
-        let guess: u32 = match guess.trim().parse() {
+        let guess: u32 = match expr::eval_as(guess.trim()) {
             Ok(num) => num,
-            Err(_) => {
-                println!("An Integer was expected, please try again!");
+            Err(e) => {
+                out.invalid_input(guess.trim(), &e, "An Integer was expected, please try again!");
                 continue;
             }
         };
         // shadowing to reuse the guess variable, trim is method on our String instance, guess
         // and eliminates whitespaces, as /n is appended during read_line, from the ENTER key.
         // parse method then parses the string into some numeric type, here it's u32, and has an
-        // associated Result type, in case a non-numeric input is given.
+        // associated Result type, in case a non-numeric input is given. instead of parse, we use
+        // expr::eval_as, which works the same way, but also accepts arithmetic like "(3+4)*5".
 
         // in the event when parse is not able to turn the string into a number, it will cause
-        // match to go to second arm's pattern, Err(_) where, "_" is a catchall, which means Err
-        // will always match, no matter what, should the Ok arm fail to match due to parse failing
-        // to convert string into a number.
-
-
-        match guess.cmp(&secret_num) {
-            Ordering::Less => println!("Too Small!"),
-            Ordering::Greater => println!("Too Big!"),
+        // match to go to second arm's pattern, Err(e) where, e is bound to the error, which means
+        // Err will always match, no matter what, should the Ok arm fail to match due to parse
+        // failing to convert string into a number, the error knows where in the input it went
+        // wrong, and render shows that with a caret.
+
+
+        guesses += 1;
+        let outcome = guess.cmp(&secret_num);
+        out.event("guess", vec![
+            ("value", Value::UInt(guess as u128)),
+            ("outcome", Value::Str(match outcome {
+                Ordering::Less => "too_small",
+                Ordering::Greater => "too_big",
+                Ordering::Equal => "win",
+            }.to_string())),
+        ]);
+
+        match outcome {
+            Ordering::Less => outln!(out, "Too Small!"),
+            Ordering::Greater => outln!(out, "Too Big!"),
             Ordering::Equal => {
-                println!("You Win!");
+                outln!(out, "You Win!");
                 break;
             }
         }
     }
-    println!("The secret number is: {}", secret_num);
+    outln!(out, "The secret number is: {}", secret_num);
+    out.event("finished", vec![
+        ("secret", Value::UInt(secret_num as u128)),
+        ("guesses", Value::UInt(guesses as u128)),
+    ]);
+    out.finish();
 }
 
 // for the dependency part where we import the rand lib
@@ -0,0 +1,202 @@
+// watching the heap
+
+// the ownership lesson says a move doesn't touch the heap, and a clone does,
+// this is how it shows it. Counting is a global allocator that hands every
+// request on to the system allocator, and, while something is being traced,
+// writes down what was asked for, so trace(|| s1.clone()) comes back with
+// the clone, and a list of every alloc, realloc and dealloc it did.
+
+// a binary has to install it for any of this to work, with
+//
+//     #[global_allocator]
+//     static COUNTING: rusty::heap::Counting = rusty::heap::Counting;
+//
+// the rust_commons binaries that run the ownership lesson do, without it,
+// installed() is false, and every trace comes back empty.
+
+// the log lives in a thread local, so the traces of different threads, like
+// the tests running side by side, never get mixed up, and it's a fixed size
+// array, as the allocator can't allocate while it's writing things down.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+use std::panic::Location;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+// how many events a single trace keeps, anything past that is only counted.
+const CAPACITY: usize = 256;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Event {
+    Alloc(usize),
+    // from the old size, to the new one.
+    Realloc(usize, usize),
+    Dealloc(usize),
+}
+
+impl Event {
+    pub fn kind(&self) -> &'static str {
+        match *self {
+            Event::Alloc(_) => "alloc",
+            Event::Realloc(_, _) => "realloc",
+            Event::Dealloc(_) => "dealloc",
+        }
+    }
+
+    // the size of the block afterwards, or for a dealloc, the one freed.
+    pub fn size(&self) -> usize {
+        match *self {
+            Event::Alloc(size) | Event::Realloc(_, size) | Event::Dealloc(size) => size,
+        }
+    }
+}
+
+// the events of a trace are written from `len` on, `len` keeps going up past
+// the end of the array, so the ones that didn't fit are still counted.
+struct Log {
+    // how many traces are running, nothing is written down when it's 0.
+    depth: Cell<usize>,
+    len: Cell<usize>,
+    events: [Cell<Event>; CAPACITY],
+}
+
+impl Log {
+    fn record(&self, event: Event) {
+        if self.depth.get() == 0 {
+            return;
+        }
+        let len = self.len.get();
+        if let Some(slot) = self.events.get(len) {
+            slot.set(event);
+        }
+        self.len.set(len + 1);
+    }
+}
+
+thread_local! {
+    static LOG: Log = const {
+        Log {
+            depth: Cell::new(0),
+            len: Cell::new(0),
+            events: [const { Cell::new(Event::Alloc(0)) }; CAPACITY],
+        }
+    };
+}
+
+static INSTALLED: AtomicBool = AtomicBool::new(false);
+
+fn record(event: Event) {
+    INSTALLED.store(true, Ordering::Relaxed);
+    // try_with, as a thread that's shutting down may still free things
+    // after its thread locals are gone.
+    let _ = LOG.try_with(|log| log.record(event));
+}
+
+pub struct Counting;
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        record(Event::Alloc(layout.size()));
+        System.alloc(layout)
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        record(Event::Alloc(layout.size()));
+        System.alloc_zeroed(layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        record(Event::Realloc(layout.size(), new_size));
+        System.realloc(ptr, layout, new_size)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        record(Event::Dealloc(layout.size()));
+        System.dealloc(ptr, layout)
+    }
+}
+
+// whether Counting is the global allocator, if it isn't, nothing gets traced.
+pub fn installed() -> bool {
+    INSTALLED.load(Ordering::Relaxed)
+}
+
+#[derive(Debug, Clone)]
+pub struct Trace {
+    // where trace was called from, or whoever called that with #[track_caller].
+    pub site: &'static Location<'static>,
+    pub events: Vec<Event>,
+    // the events that didn't fit in the log.
+    pub lost: usize,
+}
+
+impl Trace {
+    fn count(&self, kind: &str) -> usize {
+        self.events.iter().filter(|event| event.kind() == kind).count()
+    }
+
+    pub fn allocs(&self) -> usize {
+        self.count("alloc")
+    }
+
+    pub fn reallocs(&self) -> usize {
+        self.count("realloc")
+    }
+
+    pub fn deallocs(&self) -> usize {
+        self.count("dealloc")
+    }
+
+    // how many bytes the allocs and reallocs asked for, all together.
+    pub fn bytes(&self) -> usize {
+        self.events.iter().map(|event| match *event {
+            Event::Dealloc(_) => 0,
+            _ => event.size(),
+        }).sum()
+    }
+
+    // "1 alloc, 1 realloc", or "nothing" when the heap wasn't touched.
+    pub fn summary(&self) -> String {
+        let counts = [(self.allocs(), "alloc"), (self.reallocs(), "realloc"), (self.deallocs(), "dealloc")];
+        let mut parts: Vec<String> = counts
+            .iter()
+            .filter(|&&(n, _)| n > 0)
+            .map(|&(n, kind)| format!("{} {}{}", n, kind, if n == 1 { "" } else { "s" }))
+            .collect();
+        if self.lost > 0 {
+            parts.push(format!("and {} more", self.lost));
+        }
+        if parts.is_empty() {
+            "nothing".to_string()
+        } else {
+            parts.join(", ")
+        }
+    }
+}
+
+// runs f, and hands back what it returned, with everything it did on the
+// heap. traces don't nest, a trace inside another one takes its events out
+// of the outer one's log.
+#[track_caller]
+pub fn trace<T, F: FnOnce() -> T>(f: F) -> (T, Trace) {
+    let site = Location::caller();
+    let start = LOG.with(|log| {
+        log.depth.set(log.depth.get() + 1);
+        log.len.get()
+    });
+
+    let value = f();
+
+    let (events, lost) = LOG.with(|log| {
+        // collecting the events allocates too, that's not part of the trace.
+        let depth = log.depth.replace(0);
+        let len = log.len.get();
+        let kept = start.min(CAPACITY)..len.min(CAPACITY);
+        let events: Vec<Event> = log.events[kept.clone()].iter().map(Cell::get).collect();
+        log.len.set(start);
+        log.depth.set(depth - 1);
+        (events, len - start - kept.len())
+    });
+
+    (value, Trace { site, events, lost })
+}
@@ -1,3 +1,11 @@
+#[macro_use]
+extern crate rusty;
+
+use rusty::output::Output;
+
 fn main() {
-    println!("Hello, world!");
+    let mut out = Output::from_env("hello_world");
+    outln!(out, "Hello, world!");
+    out.value("greeting", &"Hello, world!");
+    out.finish();
 }
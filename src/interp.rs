@@ -0,0 +1,1952 @@
+// a little rust
+
+// the rusty_repl binary reads the kind of code the lessons are made of,
+// let statements, shadowing, arithmetic on numbers with type suffixes,
+// tuples, arrays and blocks, and shows the value of each, along with its
+// type. rather than asking rustc, which would take seconds, every line is
+// run by the small interpreter in here, which understands just that part
+// of rust, and follows the same rules the lessons describe.
+
+// a line goes through the same steps as in expr.rs, a tokenizer, a parser
+// building a tree, and an evaluator walking it, with a type checker in
+// between, which rejects what rustc would reject, with the same error
+// codes, before anything is run.
+
+// the checker also works out the types of number literals, a literal with
+// no suffix, like 5, is an "{integer}" until something says what it is, an
+// annotation, the other side of a +, or an array it's in, and if nothing
+// does, it's an i32, like in rust, {float}s become f64s. unlike rustc, the
+// checker only looks at one line at a time, so a variable's type is settled
+// by the end of the line it's declared on.
+
+// arithmetic is checked, like in a debug build, an overflow panics, which
+// stops the line, and leaves the variables the way they were before it.
+
+use std::cmp::Ordering;
+use std::fmt;
+
+use expr::{self, Span};
+use literal::{self, Number};
+
+// TYPES
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IntTy {
+    I8,
+    I16,
+    I32,
+    I64,
+    I128,
+    Isize,
+    U8,
+    U16,
+    U32,
+    U64,
+    U128,
+    Usize,
+}
+
+const INT_TYS: [IntTy; 12] = [
+    IntTy::I8, IntTy::I16, IntTy::I32, IntTy::I64, IntTy::I128, IntTy::Isize,
+    IntTy::U8, IntTy::U16, IntTy::U32, IntTy::U64, IntTy::U128, IntTy::Usize,
+];
+
+impl IntTy {
+    pub fn name(self) -> &'static str {
+        match self {
+            IntTy::I8 => "i8",
+            IntTy::I16 => "i16",
+            IntTy::I32 => "i32",
+            IntTy::I64 => "i64",
+            IntTy::I128 => "i128",
+            IntTy::Isize => "isize",
+            IntTy::U8 => "u8",
+            IntTy::U16 => "u16",
+            IntTy::U32 => "u32",
+            IntTy::U64 => "u64",
+            IntTy::U128 => "u128",
+            IntTy::Usize => "usize",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<IntTy> {
+        INT_TYS.iter().cloned().find(|t| t.name() == name)
+    }
+
+    // isize and usize are as wide as a pointer, 64 bits on the machines
+    // the lessons are run on.
+    fn bits(self) -> u32 {
+        match self {
+            IntTy::I8 | IntTy::U8 => 8,
+            IntTy::I16 | IntTy::U16 => 16,
+            IntTy::I32 | IntTy::U32 => 32,
+            IntTy::I64 | IntTy::U64 | IntTy::Isize | IntTy::Usize => 64,
+            IntTy::I128 | IntTy::U128 => 128,
+        }
+    }
+
+    fn signed(self) -> bool {
+        matches!(self, IntTy::I8 | IntTy::I16 | IntTy::I32 | IntTy::I64 | IntTy::I128 | IntTy::Isize)
+    }
+
+    fn min(self) -> i128 {
+        match (self.signed(), self.bits()) {
+            (false, _) => 0,
+            (true, 128) => i128::MIN,
+            (true, bits) => -(1 << (bits - 1)),
+        }
+    }
+
+    fn max(self) -> u128 {
+        match (self.signed(), self.bits()) {
+            (true, bits) => (1 << (bits - 1)) - 1,
+            (false, 128) => u128::MAX,
+            (false, bits) => (1 << bits) - 1,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FloatTy {
+    F32,
+    F64,
+}
+
+impl FloatTy {
+    pub fn name(self) -> &'static str {
+        match self {
+            FloatTy::F32 => "f32",
+            FloatTy::F64 => "f64",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<FloatTy> {
+        match name {
+            "f32" => Some(FloatTy::F32),
+            "f64" => Some(FloatTy::F64),
+            _ => None,
+        }
+    }
+
+    // an f32 is kept in an f64, rounded to what an f32 can hold.
+    fn round(self, value: f64) -> f64 {
+        match self {
+            FloatTy::F32 => value as f32 as f64,
+            FloatTy::F64 => value,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Ty {
+    Int(IntTy),
+    Float(FloatTy),
+    Bool,
+    Char,
+    // string literals, which are &'static str.
+    Str,
+    // the unit type, (), is the tuple with no elements.
+    Tuple(Vec<Ty>),
+    Array(Box<Ty>, usize),
+    // a number literal whose type isn't known yet.
+    IntLit,
+    FloatLit,
+}
+
+impl Ty {
+    fn unit() -> Ty {
+        Ty::Tuple(Vec::new())
+    }
+
+    fn is_numeric(&self) -> bool {
+        matches!(*self, Ty::Int(_) | Ty::Float(_) | Ty::IntLit | Ty::FloatLit)
+    }
+
+    fn is_flexible(&self) -> bool {
+        match *self {
+            Ty::IntLit | Ty::FloatLit => true,
+            Ty::Tuple(ref tys) => tys.iter().any(Ty::is_flexible),
+            Ty::Array(ref ty, _) => ty.is_flexible(),
+            _ => false,
+        }
+    }
+
+    // what a literal is when nothing says otherwise.
+    fn defaulted(&self) -> Ty {
+        match *self {
+            Ty::IntLit => Ty::Int(IntTy::I32),
+            Ty::FloatLit => Ty::Float(FloatTy::F64),
+            Ty::Tuple(ref tys) => Ty::Tuple(tys.iter().map(Ty::defaulted).collect()),
+            Ty::Array(ref ty, n) => Ty::Array(Box::new(ty.defaulted()), n),
+            ref ty => ty.clone(),
+        }
+    }
+}
+
+impl fmt::Display for Ty {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Ty::Int(t) => write!(f, "{}", t.name()),
+            Ty::Float(t) => write!(f, "{}", t.name()),
+            Ty::Bool => write!(f, "bool"),
+            Ty::Char => write!(f, "char"),
+            Ty::Str => write!(f, "&str"),
+            Ty::Tuple(ref tys) if tys.len() == 1 => write!(f, "({},)", tys[0]),
+            Ty::Tuple(ref tys) => {
+                let tys: Vec<String> = tys.iter().map(|ty| ty.to_string()).collect();
+                write!(f, "({})", tys.join(", "))
+            }
+            Ty::Array(ref ty, n) => write!(f, "[{}; {}]", ty, n),
+            Ty::IntLit => write!(f, "{{integer}}"),
+            Ty::FloatLit => write!(f, "{{float}}"),
+        }
+    }
+}
+
+// the type both a and b can be, if there is one, an {integer} can be any
+// integer type, and a {float} any float type.
+fn unify(a: &Ty, b: &Ty) -> Option<Ty> {
+    match (a, b) {
+        (&Ty::IntLit, &Ty::Int(t)) | (&Ty::Int(t), &Ty::IntLit) => Some(Ty::Int(t)),
+        (&Ty::FloatLit, &Ty::Float(t)) | (&Ty::Float(t), &Ty::FloatLit) => Some(Ty::Float(t)),
+        (Ty::Tuple(xs), Ty::Tuple(ys)) if xs.len() == ys.len() => xs
+            .iter()
+            .zip(ys)
+            .map(|(x, y)| unify(x, y))
+            .collect::<Option<Vec<Ty>>>()
+            .map(Ty::Tuple),
+        (&Ty::Array(ref x, n), &Ty::Array(ref y, m)) if n == m => {
+            unify(x, y).map(|ty| Ty::Array(Box::new(ty), n))
+        }
+        (a, b) if a == b => Some(a.clone()),
+        _ => None,
+    }
+}
+
+// VALUES
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    // integers of every type are kept in an i128, unsigned ones as their
+    // bits, so a u128 above i128::MAX looks negative in here.
+    Int(IntTy, i128),
+    Float(FloatTy, f64),
+    Bool(bool),
+    Char(char),
+    Str(String),
+    Tuple(Vec<Value>),
+    Array(Vec<Value>),
+}
+
+fn join(values: &[Value]) -> String {
+    let values: Vec<String> = values.iter().map(|value| value.to_string()).collect();
+    values.join(", ")
+}
+
+// the way {:?} prints them.
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Value::Int(t, n) if t.signed() => write!(f, "{}", n),
+            Value::Int(_, n) => write!(f, "{}", n as u128),
+            Value::Float(FloatTy::F32, x) => write!(f, "{:?}", x as f32),
+            Value::Float(FloatTy::F64, x) => write!(f, "{:?}", x),
+            Value::Bool(b) => write!(f, "{}", b),
+            Value::Char(c) => write!(f, "{:?}", c),
+            Value::Str(ref s) => write!(f, "{:?}", s),
+            Value::Tuple(ref values) if values.len() == 1 => write!(f, "({},)", values[0]),
+            Value::Tuple(ref values) => write!(f, "({})", join(values)),
+            Value::Array(ref values) => write!(f, "[{}]", join(values)),
+        }
+    }
+}
+
+// ERRORS
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorKind {
+    // the input ended in the middle of something, like an unclosed `{`,
+    // there may be more to come on the next line.
+    Incomplete,
+    // rejected before running, where rustc would have rejected it too.
+    Compile,
+    // stopped while running, like an overflow.
+    Panic,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Error {
+    pub kind: ErrorKind,
+    // rustc's error code, like "E0308", for the errors that have one.
+    pub code: Option<&'static str>,
+    pub message: String,
+    pub span: Span,
+}
+
+impl Error {
+    fn compile(code: &'static str, span: Span, message: String) -> Error {
+        Error { kind: ErrorKind::Compile, code: Some(code), message, span }
+    }
+
+    fn syntax(span: Span, message: String) -> Error {
+        Error { kind: ErrorKind::Compile, code: None, message, span }
+    }
+
+    fn panic(span: Span, message: &str) -> Error {
+        Error { kind: ErrorKind::Panic, code: None, message: message.to_string(), span }
+    }
+
+    // "error[E0308]: mismatched types ..", or "panicked: attempt to add with
+    // overflow", the way rustc, or a panicking program, starts it.
+    pub fn headline(&self) -> String {
+        match (self.kind, self.code) {
+            (ErrorKind::Panic, _) => format!("panicked: {}", self.message),
+            (_, Some(code)) => format!("error[{}]: {}", code, self.message),
+            (_, None) => format!("error: {}", self.message),
+        }
+    }
+
+    // the line of the input the error is on, 1 based.
+    pub fn line(&self, input: &str) -> usize {
+        input[..self.span.start.min(input.len())].matches('\n').count() + 1
+    }
+
+    // the line the error is on, with a caret pointing at it, see expr.rs.
+    pub fn render(&self, input: &str) -> String {
+        let start = input[..self.span.start.min(input.len())].rfind('\n').map_or(0, |i| i + 1);
+        let end = input[start..].find('\n').map_or(input.len(), |i| start + i);
+        let span = Span::new(self.span.start - start, self.span.end.min(end).max(self.span.start) - start);
+        expr::Error { span, message: self.headline() }.render(&input[start..end])
+    }
+}
+
+// TOKENIZER
+
+#[derive(Debug, Clone, PartialEq)]
+enum Tok {
+    // a number literal, and its suffix, if it has one.
+    Int(u128, Option<String>),
+    Float(f64, Option<String>),
+    Str(String),
+    Char(char),
+    // names and keywords.
+    Ident(String),
+    Punct(&'static str),
+}
+
+impl Tok {
+    fn describe(&self) -> String {
+        match *self {
+            Tok::Int(n, ref suffix) => format!("`{}{}`", n, suffix.as_deref().unwrap_or("")),
+            Tok::Float(x, ref suffix) => format!("`{:?}{}`", x, suffix.as_deref().unwrap_or("")),
+            Tok::Str(ref s) => format!("`{:?}`", s),
+            Tok::Char(c) => format!("`{:?}`", c),
+            Tok::Ident(ref name) => format!("`{}`", name),
+            Tok::Punct(p) => format!("`{}`", p),
+        }
+    }
+}
+
+const KEYWORDS: &[&str] = &["let", "mut", "if", "else", "as", "true", "false", "fn", "loop", "while", "for", "match"];
+
+// longest first, so "==" isn't read as two "="s.
+const PUNCTS: &[&str] = &[
+    "==", "!=", "<=", ">=", "&&", "||", "+=", "-=", "*=", "/=", "%=", "::",
+    "+", "-", "*", "/", "%", "(", ")", "[", "]", "{", "}", ",", ";", ":", "=", "<", ">", "!", ".", "&",
+];
+
+fn tokenize(input: &str) -> Result<Vec<(Tok, Span)>, Error> {
+    let mut tokens: Vec<(Tok, Span)> = Vec::new();
+    let mut i = 0;
+
+    while let Some(c) = input[i..].chars().next() {
+        if c.is_whitespace() {
+            i += c.len_utf8();
+            continue;
+        }
+        if input[i..].starts_with("//") {
+            i = input[i..].find('\n').map_or(input.len(), |end| i + end);
+            continue;
+        }
+
+        let start = i;
+        let tok = if c.is_ascii_digit() || input[i..].starts_with("b'") {
+            // after a ".", as in t.0.1, a number is a tuple index, and
+            // never has a fraction.
+            let after_dot = tokens.last().map(|token| &token.0) == Some(&Tok::Punct("."));
+            let (tok, end) = number(input, i, after_dot)?;
+            i = end;
+            tok
+        } else if c.is_alphabetic() || c == '_' {
+            let end = input[i..]
+                .find(|c: char| !c.is_alphanumeric() && c != '_')
+                .map_or(input.len(), |end| i + end);
+            let name = input[i..end].to_string();
+            i = end;
+            Tok::Ident(name)
+        } else if c == '"' {
+            let (text, end) = quoted(input, i, '"')?;
+            i = end;
+            Tok::Str(text)
+        } else if c == '\'' {
+            let (text, end) = quoted(input, i, '\'')?;
+            let mut chars = text.chars();
+            match (chars.next(), chars.next()) {
+                (Some(c), None) => {
+                    i = end;
+                    Tok::Char(c)
+                }
+                _ => return Err(Error::syntax(
+                    Span::new(i, end),
+                    "a character literal holds exactly one character".to_string(),
+                )),
+            }
+        } else {
+            match PUNCTS.iter().find(|p| input[i..].starts_with(**p)) {
+                Some(p) => {
+                    i += p.len();
+                    Tok::Punct(p)
+                }
+                None => return Err(Error::syntax(
+                    Span::new(i, i + c.len_utf8()),
+                    format!("unknown start of token `{}`", c),
+                )),
+            }
+        };
+        tokens.push((tok, Span::new(start, i)));
+    }
+
+    Ok(tokens)
+}
+
+// reads a number literal starting at `start`, with its suffix, like 5,
+// 1_000u64, 0xff, b'A', 2.5, 1e3 or 2.0f32, see literal.rs. after a ".",
+// as in t.0.1, it's a tuple index, which is only ever digits.
+fn number(input: &str, start: usize, after_dot: bool) -> Result<(Tok, usize), Error> {
+    if after_dot {
+        let end = input[start..].find(|c: char| !c.is_ascii_digit()).map_or(input.len(), |i| start + i);
+        let span = Span::new(start, end);
+        let index = input[start..end].parse().map_err(|_| Error::syntax(span, "invalid tuple index".to_string()))?;
+        return Ok((Tok::Int(index, None), end));
+    }
+
+    let (literal, len) = literal::lex(&input[start..]).map_err(|e| {
+        Error::syntax(Span::new(start + e.span.start, start + e.span.end), e.message)
+    })?;
+    let suffix = literal.suffix.map(|suffix| suffix.to_string());
+    let tok = match literal.number {
+        Number::Int(n) => Tok::Int(n, suffix),
+        Number::Float(x) => Tok::Float(x, suffix),
+    };
+    Ok((tok, start + len))
+}
+
+// reads a string, or character, literal, with its escapes.
+fn quoted(input: &str, start: usize, quote: char) -> Result<(String, usize), Error> {
+    let mut text = String::new();
+    let mut chars = input[start + 1..].char_indices().map(|(i, c)| (start + 1 + i, c));
+
+    while let Some((i, c)) = chars.next() {
+        if c == quote {
+            return Ok((text, i + 1));
+        }
+        if c != '\\' {
+            text.push(c);
+            continue;
+        }
+        let escaped = match chars.next() {
+            Some((_, 'n')) => '\n',
+            Some((_, 't')) => '\t',
+            Some((_, 'r')) => '\r',
+            Some((_, '0')) => '\0',
+            Some((_, '\\')) => '\\',
+            Some((_, '\'')) => '\'',
+            Some((_, '"')) => '"',
+            Some((at, other)) => return Err(Error::syntax(
+                Span::new(i, at + other.len_utf8()),
+                format!("unknown character escape `\\{}`", other),
+            )),
+            None => break,
+        };
+        text.push(escaped);
+    }
+
+    let what = if quote == '"' { "string" } else { "character" };
+    Err(Error {
+        kind: ErrorKind::Incomplete,
+        code: None,
+        message: format!("unterminated {} literal", what),
+        span: Span::new(start, input.len()),
+    })
+}
+
+// PARSER
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum BinOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Rem,
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    And,
+    Or,
+}
+
+impl BinOp {
+    fn is_arithmetic(self) -> bool {
+        matches!(self, BinOp::Add | BinOp::Sub | BinOp::Mul | BinOp::Div | BinOp::Rem)
+    }
+
+    fn is_comparison(self) -> bool {
+        matches!(self, BinOp::Eq | BinOp::Ne | BinOp::Lt | BinOp::Le | BinOp::Gt | BinOp::Ge)
+    }
+
+    fn symbol(self) -> &'static str {
+        match self {
+            BinOp::Add => "+",
+            BinOp::Sub => "-",
+            BinOp::Mul => "*",
+            BinOp::Div => "/",
+            BinOp::Rem => "%",
+            BinOp::Eq => "==",
+            BinOp::Ne => "!=",
+            BinOp::Lt => "<",
+            BinOp::Le => "<=",
+            BinOp::Gt => ">",
+            BinOp::Ge => ">=",
+            BinOp::And => "&&",
+            BinOp::Or => "||",
+        }
+    }
+
+    // what rustc says when the two sides don't go together.
+    fn mismatch(self, lhs: &Ty, rhs: &Ty) -> String {
+        match self {
+            BinOp::Add => format!("cannot add `{}` to `{}`", rhs, lhs),
+            BinOp::Sub => format!("cannot subtract `{}` from `{}`", rhs, lhs),
+            BinOp::Mul => format!("cannot multiply `{}` by `{}`", lhs, rhs),
+            BinOp::Div => format!("cannot divide `{}` by `{}`", lhs, rhs),
+            _ => format!("cannot calculate the remainder of `{}` divided by `{}`", lhs, rhs),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Expr {
+    kind: Kind,
+    span: Span,
+    // filled in by the checker.
+    ty: Ty,
+}
+
+#[derive(Debug, Clone)]
+enum Kind {
+    // a number literal, and the type its suffix gives it.
+    Int(u128, Option<Ty>),
+    Float(f64, Option<Ty>),
+    Bool(bool),
+    Char(char),
+    Str(String),
+    Var(String),
+    Neg(Box<Expr>),
+    Not(Box<Expr>),
+    Binary(BinOp, Box<Expr>, Box<Expr>),
+    Cast(Box<Expr>, Ty),
+    Tuple(Vec<Expr>),
+    Array(Vec<Expr>),
+    // [value; count]
+    Repeat(Box<Expr>, usize),
+    Index(Box<Expr>, Box<Expr>),
+    Field(Box<Expr>, usize),
+    Block(Vec<Stmt>, Option<Box<Expr>>),
+    If(Box<Expr>, Box<Expr>, Option<Box<Expr>>),
+}
+
+impl Expr {
+    fn new(kind: Kind, span: Span) -> Expr {
+        Expr { kind, span, ty: Ty::unit() }
+    }
+
+    // blocks and ifs can be statements without a ";" after them.
+    fn is_block_like(&self) -> bool {
+        matches!(self.kind, Kind::Block(..) | Kind::If(..))
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Stmt {
+    Let(Pat, Option<Ty>, Expr),
+    // x = value, or x += value.
+    Assign(String, Span, Option<BinOp>, Expr),
+    Expr(Expr),
+}
+
+// the statements of a block, or a line, and the expression at the end, if
+// there is one.
+type Body = (Vec<Stmt>, Option<Box<Expr>>);
+
+#[derive(Debug, Clone)]
+enum Pat {
+    // a name, and whether it's mut.
+    Bind(String, bool),
+    Tuple(Vec<Pat>, Span),
+    Wild,
+}
+
+// the grammar, from loosest to tightest binding, like rust's:
+//
+//   expr    := or
+//   or      := and ('||' and)*
+//   and     := cmp ('&&' cmp)*
+//   cmp     := sum (('==' | '!=' | '<' | '<=' | '>' | '>=') sum)?
+//   sum     := product (('+' | '-') product)*
+//   product := cast (('*' | '/' | '%') cast)*
+//   cast    := unary ('as' type)*
+//   unary   := ('-' | '!') unary | postfix
+//   postfix := atom ('.' index | '[' expr ']')*
+//   atom    := literal | name | tuple | array | block | if
+//
+// a line, like a block, is a list of statements, optionally followed by an
+// expression, whose value is the value of the line.
+
+// the parser calls itself for everything that nests, brackets, blocks, ifs,
+// signs and types, and every operator, `as`, index and field puts what came
+// before it one level further down the tree, which the checker and the
+// evaluator walk by calling themselves too. a line only goes this deep, so
+// none of them run out of stack, and take the whole repl down with them.
+const MAX_DEPTH: usize = 64;
+
+struct Parser {
+    tokens: Vec<(Tok, Span)>,
+    pos: usize,
+    end: usize,
+    // how many levels deep we are, see MAX_DEPTH.
+    depth: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Tok> {
+        self.tokens.get(self.pos).map(|token| &token.0)
+    }
+
+    fn peek_at(&self, offset: usize) -> Option<&Tok> {
+        self.tokens.get(self.pos + offset).map(|token| &token.0)
+    }
+
+    fn is(&self, punct: &str) -> bool {
+        match self.peek() {
+            Some(&Tok::Punct(p)) => p == punct,
+            _ => false,
+        }
+    }
+
+    fn is_keyword(&self, keyword: &str) -> bool {
+        match self.peek() {
+            Some(Tok::Ident(name)) => name == keyword,
+            _ => false,
+        }
+    }
+
+    fn here(&self) -> Span {
+        match self.tokens.get(self.pos) {
+            Some(&(_, span)) => span,
+            None => Span::new(self.end, self.end),
+        }
+    }
+
+    fn next(&mut self) -> Option<(Tok, Span)> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    // one level deeper, for the token at span, the caller goes back up by
+    // setting depth back to what it was.
+    fn enter(&mut self, span: Span) -> Result<(), Error> {
+        if self.depth == MAX_DEPTH {
+            return Err(Error::syntax(span, format!("too deeply nested, at most {} levels are allowed here", MAX_DEPTH)));
+        }
+        self.depth += 1;
+        Ok(())
+    }
+
+    // what we found instead of what we were expecting, running out of input
+    // means there may be more to come.
+    fn unexpected(&self, expected: &str) -> Error {
+        match self.tokens.get(self.pos) {
+            Some(&(ref tok, span)) => Error::syntax(span, format!("expected {}, found {}", expected, tok.describe())),
+            None => Error {
+                kind: ErrorKind::Incomplete,
+                code: None,
+                message: format!("expected {}, found the end of the input", expected),
+                span: self.here(),
+            },
+        }
+    }
+
+    fn expect(&mut self, punct: &str) -> Result<Span, Error> {
+        if self.is(punct) {
+            Ok(self.next().unwrap().1)
+        } else {
+            Err(self.unexpected(&format!("`{}`", punct)))
+        }
+    }
+
+    fn name(&mut self) -> Result<(String, Span), Error> {
+        match self.peek() {
+            Some(Tok::Ident(name)) if KEYWORDS.contains(&name.as_str()) => {
+                let message = format!("expected a name, found keyword `{}`", name);
+                Err(Error::syntax(self.here(), message))
+            }
+            Some(&Tok::Ident(_)) => match self.next() {
+                Some((Tok::Ident(name), span)) => Ok((name, span)),
+                _ => unreachable!(),
+            },
+            _ => Err(self.unexpected("a name")),
+        }
+    }
+
+    // statements, up to the closing "}", or with none, the end of the input,
+    // where, unlike in a block, a last let statement doesn't need its ";".
+    fn body(&mut self, closing: Option<Span>) -> Result<Body, Error> {
+        let mut stmts = Vec::new();
+        loop {
+            match (self.peek(), closing) {
+                (None, None) => return Ok((stmts, None)),
+                (None, Some(open)) => return Err(Error {
+                    kind: ErrorKind::Incomplete,
+                    code: None,
+                    message: "this `{` is never closed".to_string(),
+                    span: open,
+                }),
+                (Some(&Tok::Punct("}")), Some(_)) => return Ok((stmts, None)),
+                (Some(&Tok::Punct(";")), _) => {
+                    self.next();
+                    continue;
+                }
+                _ => {}
+            }
+
+            let ends = |parser: &Parser| parser.peek().is_none() && closing.is_none();
+            if self.is_keyword("let") {
+                let stmt = self.let_stmt()?;
+                if !ends(self) {
+                    self.expect(";")?;
+                }
+                stmts.push(stmt);
+                continue;
+            }
+            if let (Some(&Tok::Ident(_)), Some(&Tok::Punct(op))) = (self.peek(), self.peek_at(1)) {
+                if ["=", "+=", "-=", "*=", "/=", "%="].contains(&op) {
+                    let stmt = self.assign()?;
+                    if !ends(self) {
+                        self.expect(";")?;
+                    }
+                    stmts.push(stmt);
+                    continue;
+                }
+            }
+
+            let expr = self.expr()?;
+            if self.is(";") {
+                self.next();
+                stmts.push(Stmt::Expr(expr));
+            } else if ends(self) || (closing.is_some() && self.is("}")) {
+                return Ok((stmts, Some(Box::new(expr))));
+            } else if expr.is_block_like() {
+                stmts.push(Stmt::Expr(expr));
+            } else {
+                return Err(self.unexpected("`;`"));
+            }
+        }
+    }
+
+    fn let_stmt(&mut self) -> Result<Stmt, Error> {
+        self.next();
+        let pat = self.pattern()?;
+        let ty = if self.is(":") {
+            self.next();
+            Some(self.ty()?)
+        } else {
+            None
+        };
+        if !self.is("=") {
+            return Err(self.unexpected("`=`, a let needs a value here"));
+        }
+        self.next();
+        let value = self.expr()?;
+        Ok(Stmt::Let(pat, ty, value))
+    }
+
+    fn pattern(&mut self) -> Result<Pat, Error> {
+        if self.is("(") {
+            let (_, open) = self.next().unwrap();
+            self.enter(open)?;
+            let mut pats = Vec::new();
+            while !self.is(")") {
+                pats.push(self.pattern()?);
+                if !self.is(")") {
+                    self.expect(",")?;
+                }
+            }
+            let close = self.expect(")")?;
+            self.depth -= 1;
+            return Ok(Pat::Tuple(pats, open.to(close)));
+        }
+        let mutable = self.is_keyword("mut");
+        if mutable {
+            self.next();
+        }
+        let (name, _) = self.name()?;
+        if name == "_" {
+            return Ok(Pat::Wild);
+        }
+        Ok(Pat::Bind(name, mutable))
+    }
+
+    fn assign(&mut self) -> Result<Stmt, Error> {
+        let (name, span) = self.name()?;
+        let op = match self.next() {
+            Some((Tok::Punct("+="), _)) => Some(BinOp::Add),
+            Some((Tok::Punct("-="), _)) => Some(BinOp::Sub),
+            Some((Tok::Punct("*="), _)) => Some(BinOp::Mul),
+            Some((Tok::Punct("/="), _)) => Some(BinOp::Div),
+            Some((Tok::Punct("%="), _)) => Some(BinOp::Rem),
+            _ => None,
+        };
+        let value = self.expr()?;
+        Ok(Stmt::Assign(name, span, op, value))
+    }
+
+    fn ty(&mut self) -> Result<Ty, Error> {
+        let span = self.here();
+        let depth = self.depth;
+        self.enter(span)?;
+        let ty = self.nested_ty(span);
+        self.depth = depth;
+        ty
+    }
+
+    fn nested_ty(&mut self, span: Span) -> Result<Ty, Error> {
+        match self.next() {
+            Some((Tok::Ident(name), span)) => {
+                if let Some(t) = IntTy::from_name(&name) {
+                    return Ok(Ty::Int(t));
+                }
+                if let Some(t) = FloatTy::from_name(&name) {
+                    return Ok(Ty::Float(t));
+                }
+                match name.as_str() {
+                    "bool" => Ok(Ty::Bool),
+                    "char" => Ok(Ty::Char),
+                    "str" => Err(Error::syntax(span, "a str is only ever used through a reference, as a &str".to_string())),
+                    "String" => Err(Error::syntax(span, "String isn't supported here, string literals are &strs".to_string())),
+                    _ => Err(Error::compile("E0412", span, format!("cannot find type `{}` in this scope", name))),
+                }
+            }
+            Some((Tok::Punct("&"), _)) => match self.next() {
+                Some((Tok::Ident(ref name), _)) if name == "str" => Ok(Ty::Str),
+                _ => Err(Error::syntax(span, "only &str references are supported here".to_string())),
+            },
+            Some((Tok::Punct("("), _)) => {
+                let mut tys = Vec::new();
+                let mut trailing = false;
+                while !self.is(")") {
+                    tys.push(self.ty()?);
+                    trailing = self.is(",");
+                    if !self.is(")") {
+                        self.expect(",")?;
+                    }
+                }
+                self.expect(")")?;
+                // (i32) is just an i32, a tuple with one element is (i32,).
+                if tys.len() == 1 && !trailing {
+                    return Ok(tys.pop().unwrap());
+                }
+                Ok(Ty::Tuple(tys))
+            }
+            Some((Tok::Punct("["), _)) => {
+                let ty = self.ty()?;
+                if !self.is(";") {
+                    return Err(self.unexpected("`;` and a length, slices aren't supported here"));
+                }
+                self.next();
+                let n = self.length()?;
+                self.expect("]")?;
+                Ok(Ty::Array(Box::new(ty), n))
+            }
+            Some(_) => {
+                self.pos -= 1;
+                Err(self.unexpected("a type"))
+            }
+            None => Err(self.unexpected("a type")),
+        }
+    }
+
+    // the length of an array, which has to be known before running.
+    fn length(&mut self) -> Result<usize, Error> {
+        match self.next() {
+            Some((Tok::Int(n, ref suffix), span)) => match *suffix {
+                Some(ref suffix) if suffix != "usize" => Err(Error::compile(
+                    "E0308",
+                    span,
+                    format!("mismatched types, expected `usize`, found `{}`", suffix),
+                )),
+                _ if n > 1 << 16 => Err(Error::syntax(span, "that's too long for an array here".to_string())),
+                _ => Ok(n as usize),
+            },
+            _ => {
+                self.pos -= 1;
+                Err(self.unexpected("the length, a number"))
+            }
+        }
+    }
+
+    fn expr(&mut self) -> Result<Expr, Error> {
+        self.binary(0)
+    }
+
+    // the binary operators, by how loosely they bind, one level at a time,
+    // comparisons can't be chained, a < b < c doesn't mean anything in rust.
+    fn binary(&mut self, level: usize) -> Result<Expr, Error> {
+        const LEVELS: &[&[(&str, BinOp)]] = &[
+            &[("||", BinOp::Or)],
+            &[("&&", BinOp::And)],
+            &[("==", BinOp::Eq), ("!=", BinOp::Ne), ("<=", BinOp::Le), (">=", BinOp::Ge), ("<", BinOp::Lt), (">", BinOp::Gt)],
+            &[("+", BinOp::Add), ("-", BinOp::Sub)],
+            &[("*", BinOp::Mul), ("/", BinOp::Div), ("%", BinOp::Rem)],
+        ];
+        if level == LEVELS.len() {
+            return self.cast();
+        }
+
+        let mut lhs = self.binary(level + 1)?;
+        let depth = self.depth;
+        loop {
+            let op = match LEVELS[level].iter().find(|&&(punct, _)| self.is(punct)) {
+                Some(&(_, op)) => op,
+                None => {
+                    self.depth = depth;
+                    return Ok(lhs);
+                }
+            };
+            let (_, op_span) = self.next().unwrap();
+            self.enter(op_span)?;
+            let rhs = self.binary(level + 1)?;
+            if let Kind::Binary(previous, ..) = lhs.kind {
+                if op.is_comparison() && previous.is_comparison() {
+                    return Err(Error::syntax(op_span, "comparison operators cannot be chained".to_string()));
+                }
+            }
+            let span = lhs.span.to(rhs.span);
+            lhs = Expr::new(Kind::Binary(op, Box::new(lhs), Box::new(rhs)), span);
+        }
+    }
+
+    fn cast(&mut self) -> Result<Expr, Error> {
+        let mut expr = self.unary()?;
+        let depth = self.depth;
+        while self.is_keyword("as") {
+            let (_, as_span) = self.next().unwrap();
+            self.enter(as_span)?;
+            let end = self.here();
+            let ty = self.ty()?;
+            let span = expr.span.to(end);
+            expr = Expr::new(Kind::Cast(Box::new(expr), ty), span);
+        }
+        self.depth = depth;
+        Ok(expr)
+    }
+
+    fn unary(&mut self) -> Result<Expr, Error> {
+        let neg = self.is("-");
+        if neg || self.is("!") {
+            let (_, span) = self.next().unwrap();
+            self.enter(span)?;
+            let operand = self.unary()?;
+            self.depth -= 1;
+            let span = span.to(operand.span);
+            let kind = if neg { Kind::Neg(Box::new(operand)) } else { Kind::Not(Box::new(operand)) };
+            return Ok(Expr::new(kind, span));
+        }
+        self.postfix()
+    }
+
+    fn postfix(&mut self) -> Result<Expr, Error> {
+        let mut expr = self.atom()?;
+        let depth = self.depth;
+        loop {
+            if self.is(".") {
+                let (_, dot) = self.next().unwrap();
+                self.enter(dot)?;
+                let (index, span) = match self.next() {
+                    Some((Tok::Int(n, None), span)) => (n as usize, span),
+                    _ => {
+                        self.pos -= 1;
+                        return Err(self.unexpected("a tuple index, like `.0`, methods aren't supported here"));
+                    }
+                };
+                let span = expr.span.to(span);
+                expr = Expr::new(Kind::Field(Box::new(expr), index), span);
+            } else if self.is("[") {
+                let (_, open) = self.next().unwrap();
+                self.enter(open)?;
+                let index = self.expr()?;
+                let close = self.expect("]")?;
+                let span = expr.span.to(close);
+                expr = Expr::new(Kind::Index(Box::new(expr), Box::new(index)), span);
+            } else {
+                self.depth = depth;
+                return Ok(expr);
+            }
+        }
+    }
+
+    fn atom(&mut self) -> Result<Expr, Error> {
+        let depth = self.depth;
+        self.enter(self.here())?;
+        let atom = self.nested_atom();
+        self.depth = depth;
+        atom
+    }
+
+    fn nested_atom(&mut self) -> Result<Expr, Error> {
+        if self.is_keyword("if") {
+            return self.if_expr();
+        }
+        if self.is("{") {
+            return self.block();
+        }
+        let (tok, span) = match self.next() {
+            Some(token) => token,
+            None => return Err(self.unexpected("an expression")),
+        };
+        let kind = match tok {
+            Tok::Int(n, suffix) => Kind::Int(n, suffix.map(|s| match FloatTy::from_name(&s) {
+                Some(t) => Ty::Float(t),
+                None => Ty::Int(IntTy::from_name(&s).unwrap()),
+            })),
+            Tok::Float(x, suffix) => Kind::Float(x, suffix.map(|s| Ty::Float(FloatTy::from_name(&s).unwrap()))),
+            Tok::Str(s) => Kind::Str(s),
+            Tok::Char(c) => Kind::Char(c),
+            Tok::Ident(ref name) if name == "true" => Kind::Bool(true),
+            Tok::Ident(ref name) if name == "false" => Kind::Bool(false),
+            Tok::Ident(_) => {
+                self.pos -= 1;
+                let (name, span) = self.name()?;
+                let unsupported = if self.is("::") {
+                    Some(format!("paths, like `{}::..`, aren't supported here", name))
+                } else if self.is("(") || self.is("!") {
+                    Some(format!("calling `{}` isn't supported here", name))
+                } else {
+                    None
+                };
+                if let Some(unsupported) = unsupported {
+                    let message = format!("{}, only values, operators, tuples, arrays and blocks", unsupported);
+                    return Err(Error::syntax(span, message));
+                }
+                Kind::Var(name)
+            }
+            Tok::Punct("(") => return self.tuple(span),
+            Tok::Punct("[") => return self.array(span),
+            _ => {
+                self.pos -= 1;
+                return Err(self.unexpected("an expression"));
+            }
+        };
+        Ok(Expr::new(kind, span))
+    }
+
+    // (), (x), (x,), (x, y) ..
+    fn tuple(&mut self, open: Span) -> Result<Expr, Error> {
+        let mut exprs = Vec::new();
+        let mut trailing = false;
+        while !self.is(")") {
+            exprs.push(self.expr()?);
+            trailing = self.is(",");
+            if !self.is(")") {
+                self.expect(",")?;
+            }
+        }
+        let close = self.expect(")")?;
+        if exprs.len() == 1 && !trailing {
+            let mut inner = exprs.pop().unwrap();
+            inner.span = open.to(close);
+            return Ok(inner);
+        }
+        Ok(Expr::new(Kind::Tuple(exprs), open.to(close)))
+    }
+
+    // [], [x; n], [x, y, ..]
+    fn array(&mut self, open: Span) -> Result<Expr, Error> {
+        let mut exprs = Vec::new();
+        while !self.is("]") {
+            exprs.push(self.expr()?);
+            if exprs.len() == 1 && self.is(";") {
+                self.next();
+                let n = self.length()?;
+                let close = self.expect("]")?;
+                return Ok(Expr::new(Kind::Repeat(Box::new(exprs.pop().unwrap()), n), open.to(close)));
+            }
+            if !self.is("]") {
+                self.expect(",")?;
+            }
+        }
+        let close = self.expect("]")?;
+        Ok(Expr::new(Kind::Array(exprs), open.to(close)))
+    }
+
+    fn block(&mut self) -> Result<Expr, Error> {
+        let open = self.expect("{")?;
+        let (stmts, tail) = self.body(Some(open))?;
+        let close = self.expect("}")?;
+        Ok(Expr::new(Kind::Block(stmts, tail), open.to(close)))
+    }
+
+    fn if_expr(&mut self) -> Result<Expr, Error> {
+        let (_, start) = self.next().unwrap();
+        let cond = self.expr()?;
+        if !self.is("{") {
+            return Err(self.unexpected("`{` after the condition"));
+        }
+        let then = self.block()?;
+        let mut span = start.to(then.span);
+        let otherwise = if self.is_keyword("else") {
+            self.next();
+            // an else if is an if inside the else, one level further down.
+            let otherwise = if self.is_keyword("if") {
+                let depth = self.depth;
+                self.enter(self.here())?;
+                let otherwise = self.if_expr()?;
+                self.depth = depth;
+                otherwise
+            } else {
+                self.block()?
+            };
+            span = start.to(otherwise.span);
+            Some(Box::new(otherwise))
+        } else {
+            None
+        };
+        Ok(Expr::new(Kind::If(Box::new(cond), Box::new(then), otherwise), span))
+    }
+}
+
+fn parse(input: &str) -> Result<Body, Error> {
+    let mut parser = Parser { tokens: tokenize(input)?, pos: 0, end: input.len(), depth: 0 };
+    let (stmts, tail) = parser.body(None)?;
+    if let Some((tok, span)) = parser.next() {
+        return Err(Error::syntax(span, format!("unexpected {}", tok.describe())));
+    }
+    Ok((stmts, tail))
+}
+
+// TYPE CHECKER
+
+// gives an {integer} or {float}, and whatever it's made of, the type it
+// turned out to be, a+1 is an i32 if a is, and so is the 1.
+fn settle(expr: &mut Expr, ty: &Ty) {
+    if !expr.ty.is_flexible() {
+        return;
+    }
+    let ty = match unify(&expr.ty, ty) {
+        Some(ty) => ty,
+        None => return,
+    };
+    expr.ty = ty.clone();
+
+    match expr.kind {
+        Kind::Neg(ref mut operand) | Kind::Not(ref mut operand) => settle(operand, &ty),
+        Kind::Binary(op, ref mut lhs, ref mut rhs)
+            if op.is_arithmetic() => {
+                settle(lhs, &ty);
+                settle(rhs, &ty);
+            }
+        Kind::Block(_, Some(ref mut tail)) => settle(tail, &ty),
+        Kind::If(_, ref mut then, ref mut otherwise) => {
+            settle(then, &ty);
+            if let Some(ref mut otherwise) = *otherwise {
+                settle(otherwise, &ty);
+            }
+        }
+        Kind::Tuple(ref mut exprs) => {
+            if let Ty::Tuple(ref tys) = ty {
+                for (expr, ty) in exprs.iter_mut().zip(tys) {
+                    settle(expr, ty);
+                }
+            }
+        }
+        Kind::Array(ref mut exprs) => {
+            if let Ty::Array(ref elem, _) = ty {
+                for expr in exprs.iter_mut() {
+                    settle(expr, elem);
+                }
+            }
+        }
+        Kind::Repeat(ref mut expr, _) => {
+            if let Ty::Array(ref elem, _) = ty {
+                settle(expr, elem);
+            }
+        }
+        Kind::Index(ref mut base, _) => {
+            if let Ty::Array(_, n) = base.ty {
+                settle(base, &Ty::Array(Box::new(ty.clone()), n));
+            }
+        }
+        Kind::Field(ref mut base, i) => {
+            if let Ty::Tuple(mut tys) = base.ty.clone() {
+                tys[i] = ty.clone();
+                settle(base, &Ty::Tuple(tys));
+            }
+        }
+        _ => {}
+    }
+}
+
+// rustc refuses a literal too big for its type, like 256u8, a negative
+// literal can be one larger, -128i8 is fine. a float literal too big for
+// its type, like 1e400, would be infinity, which rustc refuses too.
+fn check_literals(expr: &Expr, negated: bool) -> Result<(), Error> {
+    let children: Vec<&Expr> = match expr.kind {
+        Kind::Float(x, _) => {
+            let t = match expr.ty {
+                Ty::Float(t) => t,
+                _ => FloatTy::F64,
+            };
+            if t.round(x).is_infinite() {
+                return Err(Error::syntax(expr.span, format!(
+                    "literal out of range for `{}`, it would be `{}::INFINITY`",
+                    t.name(), t.name()
+                )));
+            }
+            vec![]
+        }
+        Kind::Int(n, _) => {
+            if let Ty::Int(t) = expr.ty {
+                let max = if negated && t.signed() { t.max() + 1 } else { t.max() };
+                if n > max {
+                    return Err(Error::syntax(expr.span, format!(
+                        "literal out of range for `{}`, whose range is `{}..={}`",
+                        t.name(), t.min(), t.max()
+                    )));
+                }
+            }
+            vec![]
+        }
+        Kind::Neg(ref operand) => return check_literals(operand, true),
+        Kind::Not(ref operand) | Kind::Cast(ref operand, _) | Kind::Repeat(ref operand, _) | Kind::Field(ref operand, _) => vec![operand],
+        Kind::Binary(_, ref lhs, ref rhs) | Kind::Index(ref lhs, ref rhs) => vec![lhs, rhs],
+        Kind::Tuple(ref exprs) | Kind::Array(ref exprs) => exprs.iter().collect(),
+        Kind::Block(ref stmts, ref tail) => {
+            for stmt in stmts {
+                check_stmt_literals(stmt)?;
+            }
+            tail.iter().map(|tail| &**tail).collect()
+        }
+        Kind::If(ref cond, ref then, ref otherwise) => {
+            let mut children = vec![&**cond, &**then];
+            children.extend(otherwise.iter().map(|otherwise| &**otherwise));
+            children
+        }
+        _ => vec![],
+    };
+    children.into_iter().try_for_each(|child| check_literals(child, false))
+}
+
+fn check_stmt_literals(stmt: &Stmt) -> Result<(), Error> {
+    match *stmt {
+        Stmt::Let(_, _, ref expr) | Stmt::Assign(_, _, _, ref expr) | Stmt::Expr(ref expr) => check_literals(expr, false),
+    }
+}
+
+fn mismatched(span: Span, expected: &Ty, found: &Ty) -> Error {
+    Error::compile("E0308", span, format!("mismatched types, expected `{}`, found `{}`", expected, found))
+}
+
+struct Checker {
+    // every variable in scope, the last one with a name is the one it
+    // refers to, the ones before it are shadowed.
+    scope: Vec<(String, Ty, bool)>,
+}
+
+impl Checker {
+    fn lookup(&self, name: &str, span: Span) -> Result<(Ty, bool), Error> {
+        match self.scope.iter().rev().find(|var| var.0 == name) {
+            Some(&(_, ref ty, mutable)) => Ok((ty.clone(), mutable)),
+            None => Err(Error::compile("E0425", span, format!("cannot find value `{}` in this scope", name))),
+        }
+    }
+
+    // the type of the expression, which, if it can, becomes the expected
+    // type, if there is one, whether it is that type is up to the caller.
+    fn expr(&mut self, expr: &mut Expr, expected: Option<&Ty>) -> Result<Ty, Error> {
+        let ty = self.infer(expr, expected)?;
+        expr.ty = ty.clone();
+        Ok(ty)
+    }
+
+    // the type of an expression that has to be of the expected type.
+    fn expect(&mut self, expr: &mut Expr, expected: &Ty) -> Result<Ty, Error> {
+        let ty = self.expr(expr, Some(expected))?;
+        match unify(&ty, expected) {
+            Some(ty) => {
+                settle(expr, &ty);
+                Ok(ty)
+            }
+            None => Err(mismatched(expr.span, expected, &ty)),
+        }
+    }
+
+    // the type of an expression nothing else says anything about, so any
+    // literal in it that's still an {integer} is an i32.
+    fn finish(&mut self, expr: &mut Expr) -> Result<Ty, Error> {
+        let ty = self.expr(expr, None)?.defaulted();
+        settle(expr, &ty);
+        Ok(ty)
+    }
+
+    fn infer(&mut self, expr: &mut Expr, expected: Option<&Ty>) -> Result<Ty, Error> {
+        let span = expr.span;
+        match expr.kind {
+            Kind::Int(_, Some(ref ty)) | Kind::Float(_, Some(ref ty)) => Ok(ty.clone()),
+            Kind::Int(_, None) => Ok(match expected {
+                Some(&Ty::Int(t)) => Ty::Int(t),
+                _ => Ty::IntLit,
+            }),
+            Kind::Float(_, None) => Ok(match expected {
+                Some(&Ty::Float(t)) => Ty::Float(t),
+                _ => Ty::FloatLit,
+            }),
+            Kind::Bool(_) => Ok(Ty::Bool),
+            Kind::Char(_) => Ok(Ty::Char),
+            Kind::Str(_) => Ok(Ty::Str),
+            Kind::Var(ref name) => self.lookup(name, span).map(|(ty, _)| ty),
+            Kind::Neg(ref mut operand) => {
+                let ty = self.expr(operand, expected.filter(|ty| ty.is_numeric()))?;
+                match ty {
+                    Ty::Int(t) if !t.signed() => Err(Error::compile(
+                        "E0600",
+                        span,
+                        format!("cannot apply unary operator `-` to type `{}`, unsigned values cannot be negated", ty),
+                    )),
+                    ref ty if ty.is_numeric() => Ok(ty.clone()),
+                    ty => Err(Error::compile("E0600", span, format!("cannot apply unary operator `-` to type `{}`", ty))),
+                }
+            }
+            Kind::Not(ref mut operand) => {
+                let ty = self.expr(operand, expected)?;
+                match ty {
+                    Ty::Bool | Ty::Int(_) | Ty::IntLit => Ok(ty),
+                    ty => Err(Error::compile("E0600", span, format!("cannot apply unary operator `!` to type `{}`", ty))),
+                }
+            }
+            Kind::Binary(op, ref mut lhs, ref mut rhs) => self.binary(op, lhs, rhs, expected),
+            Kind::Cast(ref mut operand, ref target) => {
+                let ty = self.finish(operand)?;
+                cast_allowed(&ty, target).map_err(|(code, message)| Error::compile(code, span, message))?;
+                Ok(target.clone())
+            }
+            Kind::Tuple(ref mut exprs) => {
+                let hints = match expected {
+                    Some(Ty::Tuple(tys)) if tys.len() == exprs.len() => tys.iter().map(Some).collect(),
+                    _ => vec![None; exprs.len()],
+                };
+                let mut tys = Vec::new();
+                for (expr, hint) in exprs.iter_mut().zip(hints) {
+                    tys.push(self.expr(expr, hint)?);
+                }
+                Ok(Ty::Tuple(tys))
+            }
+            Kind::Array(ref mut exprs) => {
+                let hint = match expected {
+                    Some(Ty::Array(elem, _)) => Some((**elem).clone()),
+                    _ => None,
+                };
+                if exprs.is_empty() {
+                    return match hint {
+                        Some(elem) => Ok(Ty::Array(Box::new(elem), 0)),
+                        None => Err(Error::compile("E0282", span, "type annotations needed for `[_; 0]`".to_string())),
+                    };
+                }
+                // every element has to be of the same type.
+                let mut elem = self.expr(&mut exprs[0], hint.as_ref())?;
+                for expr in exprs[1..].iter_mut() {
+                    let ty = self.expr(expr, Some(&elem))?;
+                    elem = unify(&elem, &ty).ok_or_else(|| mismatched(expr.span, &elem, &ty))?;
+                }
+                for expr in exprs.iter_mut() {
+                    settle(expr, &elem);
+                }
+                Ok(Ty::Array(Box::new(elem), exprs.len()))
+            }
+            Kind::Repeat(ref mut value, n) => {
+                let hint = match expected {
+                    Some(Ty::Array(elem, _)) => Some((**elem).clone()),
+                    _ => None,
+                };
+                let elem = self.expr(value, hint.as_ref())?;
+                Ok(Ty::Array(Box::new(elem), n))
+            }
+            Kind::Index(ref mut base, ref mut index) => {
+                let elem = match self.expr(base, None)? {
+                    Ty::Array(elem, _) => *elem,
+                    ty => return Err(Error::compile("E0608", span, format!("cannot index into a value of type `{}`", ty))),
+                };
+                let usize = Ty::Int(IntTy::Usize);
+                let ty = self.expr(index, Some(&usize))?;
+                if unify(&ty, &usize).is_none() {
+                    return Err(Error::compile(
+                        "E0277",
+                        index.span,
+                        format!("the type `[{}]` cannot be indexed by `{}`", elem, ty),
+                    ));
+                }
+                settle(index, &usize);
+                Ok(elem)
+            }
+            Kind::Field(ref mut base, i) => match self.expr(base, None)? {
+                Ty::Tuple(ref tys) if i < tys.len() => Ok(tys[i].clone()),
+                ty => Err(Error::compile("E0609", span, format!("no field `{}` on type `{}`", i, ty))),
+            },
+            Kind::Block(ref mut stmts, ref mut tail) => {
+                // the variables declared in a block are gone after it.
+                let mark = self.scope.len();
+                for stmt in stmts.iter_mut() {
+                    self.stmt(stmt)?;
+                }
+                let ty = match *tail {
+                    Some(ref mut tail) => self.expr(tail, expected)?,
+                    None => Ty::unit(),
+                };
+                self.scope.truncate(mark);
+                Ok(ty)
+            }
+            Kind::If(ref mut cond, ref mut then, ref mut otherwise) => {
+                self.expect(cond, &Ty::Bool)?;
+                let then_ty = self.expr(then, expected)?;
+                let otherwise = match *otherwise {
+                    Some(ref mut otherwise) => otherwise,
+                    None if then_ty == Ty::unit() => return Ok(then_ty),
+                    None => return Err(Error::compile(
+                        "E0317",
+                        span,
+                        format!("`if` may be missing an `else` clause, expected `()`, found `{}`", then_ty),
+                    )),
+                };
+                let otherwise_ty = self.expr(otherwise, Some(&then_ty))?;
+                let ty = unify(&then_ty, &otherwise_ty).ok_or_else(|| Error::compile(
+                    "E0308",
+                    otherwise.span,
+                    format!("`if` and `else` have incompatible types, expected `{}`, found `{}`", then_ty, otherwise_ty),
+                ))?;
+                settle(then, &ty);
+                settle(otherwise, &ty);
+                Ok(ty)
+            }
+        }
+    }
+
+    fn binary(&mut self, op: BinOp, lhs: &mut Expr, rhs: &mut Expr, expected: Option<&Ty>) -> Result<Ty, Error> {
+        if op == BinOp::And || op == BinOp::Or {
+            self.expect(lhs, &Ty::Bool)?;
+            self.expect(rhs, &Ty::Bool)?;
+            return Ok(Ty::Bool);
+        }
+
+        // the left side may take its type from what's expected, and the
+        // right side from the left side, so in x + 1, the 1 is whatever x is.
+        let hint = if op.is_arithmetic() { expected.filter(|ty| ty.is_numeric()) } else { None };
+        let lhs_ty = self.expr(lhs, hint)?;
+        let rhs_ty = self.expr(rhs, Some(&lhs_ty))?;
+        let span = lhs.span.to(rhs.span);
+
+        let ty = match unify(&lhs_ty, &rhs_ty) {
+            Some(ty) => ty,
+            None if op.is_comparison() => return Err(mismatched(rhs.span, &lhs_ty, &rhs_ty)),
+            None if lhs_ty.is_numeric() && rhs_ty.is_numeric() => {
+                return Err(Error::compile("E0277", span, op.mismatch(&lhs_ty, &rhs_ty)))
+            }
+            None => return Err(Error::compile("E0369", span, op.mismatch(&lhs_ty, &rhs_ty))),
+        };
+
+        if op.is_comparison() {
+            let ty = ty.defaulted();
+            settle(lhs, &ty);
+            settle(rhs, &ty);
+            return Ok(Ty::Bool);
+        }
+        if !ty.is_numeric() {
+            return Err(Error::compile("E0369", span, op.mismatch(&lhs_ty, &rhs_ty)));
+        }
+        settle(lhs, &ty);
+        settle(rhs, &ty);
+        Ok(ty)
+    }
+
+    fn stmt(&mut self, stmt: &mut Stmt) -> Result<(), Error> {
+        match *stmt {
+            Stmt::Let(ref pat, ref annotation, ref mut value) => {
+                let ty = match *annotation {
+                    Some(ref ty) => self.expect(value, ty)?,
+                    None => self.finish(value)?,
+                };
+                self.bind(pat, &ty)
+            }
+            Stmt::Assign(ref name, span, op, ref mut value) => {
+                let (ty, mutable) = self.lookup(name, span)?;
+                if !mutable {
+                    return Err(Error::compile(
+                        "E0384",
+                        span.to(value.span),
+                        format!("cannot assign twice to immutable variable `{}`, it needs a `let mut`", name),
+                    ));
+                }
+                if let Some(op) = op {
+                    if !ty.is_numeric() {
+                        return Err(Error::compile(
+                            "E0368",
+                            span.to(value.span),
+                            format!("binary assignment operation `{}=` cannot be applied to type `{}`", op.symbol(), ty),
+                        ));
+                    }
+                }
+                self.expect(value, &ty).map(|_| ())
+            }
+            Stmt::Expr(ref mut expr) => self.finish(expr).map(|_| ()),
+        }
+    }
+
+    fn bind(&mut self, pat: &Pat, ty: &Ty) -> Result<(), Error> {
+        match *pat {
+            Pat::Bind(ref name, mutable) => self.scope.push((name.clone(), ty.clone(), mutable)),
+            Pat::Wild => {}
+            Pat::Tuple(ref pats, pat_span) => match *ty {
+                Ty::Tuple(ref tys) if tys.len() == pats.len() => {
+                    for (pat, ty) in pats.iter().zip(tys) {
+                        self.bind(pat, ty)?;
+                    }
+                }
+                _ => return Err(Error::compile(
+                    "E0308",
+                    pat_span,
+                    format!("mismatched types, expected `{}`, found a tuple with {} elements", ty, pats.len()),
+                )),
+            },
+        }
+        Ok(())
+    }
+}
+
+// whether `ty as target` is allowed, and if not, rustc's code and message.
+fn cast_allowed(ty: &Ty, target: &Ty) -> Result<(), (&'static str, String)> {
+    let primitive = |ty: &Ty| ty.is_numeric() || *ty == Ty::Bool || *ty == Ty::Char;
+    match (ty, target) {
+        (a, b) if a.is_numeric() && b.is_numeric() => Ok(()),
+        (&Ty::Bool, &Ty::Int(_)) | (&Ty::Char, &Ty::Int(_)) => Ok(()),
+        (&Ty::Int(IntTy::U8), &Ty::Char) => Ok(()),
+        (a, b) if a == b && (primitive(a) || *a == Ty::Str) => Ok(()),
+        (a, &Ty::Char) if a.is_numeric() => Err(("E0604", format!("only `u8` can be cast as `char`, not `{}`", a))),
+        (a, &Ty::Bool) if primitive(a) => Err(("E0054", format!("cannot cast `{}` as `bool`", a))),
+        (a, b) if primitive(a) && primitive(b) => Err(("E0606", format!("casting `{}` as `{}` is invalid", a, b))),
+        (a, b) => Err(("E0605", format!("non-primitive cast: `{}` as `{}`", a, b))),
+    }
+}
+
+// EVALUATOR
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Binding {
+    pub name: String,
+    pub ty: Ty,
+    pub mutable: bool,
+    pub value: Value,
+}
+
+// the bits of a value as a given integer type, like `as` does it.
+fn truncate(t: IntTy, n: i128) -> i128 {
+    let bits = t.bits();
+    if bits == 128 {
+        return n;
+    }
+    let low = (n as u128) & ((1 << bits) - 1);
+    if t.signed() && low >> (bits - 1) == 1 {
+        (low | !((1 << bits) - 1)) as i128
+    } else {
+        low as i128
+    }
+}
+
+// an integer operation, checked like in a debug build, None on overflow.
+fn int_op(op: BinOp, t: IntTy, a: i128, b: i128) -> Option<i128> {
+    if t.signed() {
+        let n = match op {
+            BinOp::Add => a.checked_add(b),
+            BinOp::Sub => a.checked_sub(b),
+            BinOp::Mul => a.checked_mul(b),
+            BinOp::Div => a.checked_div(b),
+            _ => a.checked_rem(b),
+        }?;
+        if n < t.min() || n > t.max() as i128 {
+            return None;
+        }
+        Some(n)
+    } else {
+        let (a, b) = (a as u128, b as u128);
+        let n = match op {
+            BinOp::Add => a.checked_add(b),
+            BinOp::Sub => a.checked_sub(b),
+            BinOp::Mul => a.checked_mul(b),
+            BinOp::Div => a.checked_div(b),
+            _ => a.checked_rem(b),
+        }?;
+        if n > t.max() {
+            return None;
+        }
+        Some(n as i128)
+    }
+}
+
+fn compare(a: &Value, b: &Value) -> Option<Ordering> {
+    match (a, b) {
+        (&Value::Int(t, a), &Value::Int(_, b)) if !t.signed() => Some((a as u128).cmp(&(b as u128))),
+        (&Value::Int(_, a), &Value::Int(_, b)) => Some(a.cmp(&b)),
+        (&Value::Float(_, a), &Value::Float(_, b)) => a.partial_cmp(&b),
+        (&Value::Bool(a), &Value::Bool(b)) => Some(a.cmp(&b)),
+        (&Value::Char(a), &Value::Char(b)) => Some(a.cmp(&b)),
+        (Value::Str(a), Value::Str(b)) => Some(a.cmp(b)),
+        (&Value::Tuple(ref a), &Value::Tuple(ref b)) | (&Value::Array(ref a), &Value::Array(ref b)) => {
+            for (a, b) in a.iter().zip(b) {
+                match compare(a, b)? {
+                    Ordering::Equal => continue,
+                    other => return Some(other),
+                }
+            }
+            Some(a.len().cmp(&b.len()))
+        }
+        _ => None,
+    }
+}
+
+fn cast(value: Value, target: &Ty) -> Value {
+    match (value, target) {
+        (Value::Int(from, n), &Ty::Int(t)) => {
+            let n = if from.signed() { n } else { truncate(IntTy::U128, n) };
+            Value::Int(t, truncate(t, n))
+        }
+        (Value::Int(from, n), &Ty::Float(t)) => {
+            let x = if from.signed() { n as f64 } else { n as u128 as f64 };
+            Value::Float(t, t.round(x))
+        }
+        // floats saturate, and NaN becomes 0, the way `as` does it.
+        (Value::Float(_, x), &Ty::Int(t)) => {
+            let n = if x.is_nan() {
+                0
+            } else if t.signed() {
+                (x.max(t.min() as f64).min(t.max() as f64)) as i128
+            } else {
+                (x.max(0.0).min(t.max() as f64)) as u128 as i128
+            };
+            Value::Int(t, n)
+        }
+        (Value::Float(_, x), &Ty::Float(t)) => Value::Float(t, t.round(x)),
+        (Value::Bool(b), &Ty::Int(t)) => Value::Int(t, b as i128),
+        (Value::Char(c), &Ty::Int(t)) => Value::Int(t, truncate(t, c as i128)),
+        (Value::Int(_, n), &Ty::Char) => Value::Char(n as u8 as char),
+        (value, _) => value,
+    }
+}
+
+struct Machine {
+    stack: Vec<Binding>,
+}
+
+impl Machine {
+    fn lookup(&self, name: &str) -> &Binding {
+        self.stack.iter().rev().find(|binding| binding.name == name).expect("checked")
+    }
+
+    fn eval(&mut self, expr: &Expr) -> Result<Value, Error> {
+        let ty = expr.ty.defaulted();
+        let panic = |message: &str| Error::panic(expr.span, message);
+        Ok(match expr.kind {
+            Kind::Int(n, _) => match ty {
+                Ty::Int(t) => Value::Int(t, n as i128),
+                _ => Value::Float(FloatTy::F64, n as f64),
+            },
+            Kind::Float(x, _) => match ty {
+                Ty::Float(t) => Value::Float(t, t.round(x)),
+                _ => Value::Float(FloatTy::F64, x),
+            },
+            Kind::Bool(b) => Value::Bool(b),
+            Kind::Char(c) => Value::Char(c),
+            Kind::Str(ref s) => Value::Str(s.clone()),
+            Kind::Var(ref name) => self.lookup(name).value.clone(),
+            // -128i8 is a literal, negated, but 128i8 would be too large on
+            // its own, the literal is negated first.
+            Kind::Neg(ref operand) => match (&operand.kind, self.eval(operand)?) {
+                (&Kind::Int(n, _), Value::Int(t, _)) => Value::Int(t, (n as i128).wrapping_neg()),
+                (_, Value::Int(t, n)) => match n.checked_neg() {
+                    Some(n) if n >= t.min() && n <= t.max() as i128 => Value::Int(t, n),
+                    _ => return Err(panic("attempt to negate with overflow")),
+                },
+                (_, Value::Float(t, x)) => Value::Float(t, -x),
+                (_, value) => value,
+            },
+            Kind::Not(ref operand) => match self.eval(operand)? {
+                Value::Bool(b) => Value::Bool(!b),
+                Value::Int(t, n) => Value::Int(t, truncate(t, !n)),
+                value => value,
+            },
+            Kind::Binary(op, ref lhs, ref rhs) => self.binary(expr, op, lhs, rhs)?,
+            Kind::Cast(ref operand, ref target) => cast(self.eval(operand)?, target),
+            Kind::Tuple(ref exprs) => Value::Tuple(self.eval_all(exprs)?),
+            Kind::Array(ref exprs) => Value::Array(self.eval_all(exprs)?),
+            Kind::Repeat(ref value, n) => Value::Array(vec![self.eval(value)?; n]),
+            Kind::Index(ref base, ref index) => {
+                let values = match self.eval(base)? {
+                    Value::Array(values) => values,
+                    _ => unreachable!("checked"),
+                };
+                let i = match self.eval(index)? {
+                    Value::Int(_, i) => i as u128,
+                    _ => unreachable!("checked"),
+                };
+                match values.get(i as usize) {
+                    Some(value) if i < values.len() as u128 => value.clone(),
+                    _ => return Err(panic(&format!(
+                        "index out of bounds: the len is {} but the index is {}",
+                        values.len(), i
+                    ))),
+                }
+            }
+            Kind::Field(ref base, i) => match self.eval(base)? {
+                Value::Tuple(mut values) => values.swap_remove(i),
+                _ => unreachable!("checked"),
+            },
+            Kind::Block(ref stmts, ref tail) => {
+                let mark = self.stack.len();
+                for stmt in stmts {
+                    self.stmt(stmt)?;
+                }
+                let value = match *tail {
+                    Some(ref tail) => self.eval(tail)?,
+                    None => Value::Tuple(Vec::new()),
+                };
+                self.stack.truncate(mark);
+                value
+            }
+            Kind::If(ref cond, ref then, ref otherwise) => match self.eval(cond)? {
+                Value::Bool(true) => self.eval(then)?,
+                _ => match *otherwise {
+                    Some(ref otherwise) => self.eval(otherwise)?,
+                    None => Value::Tuple(Vec::new()),
+                },
+            },
+        })
+    }
+
+    fn eval_all(&mut self, exprs: &[Expr]) -> Result<Vec<Value>, Error> {
+        exprs.iter().map(|expr| self.eval(expr)).collect()
+    }
+
+    fn binary(&mut self, expr: &Expr, op: BinOp, lhs: &Expr, rhs: &Expr) -> Result<Value, Error> {
+        let a = self.eval(lhs)?;
+        // && and || only look at the right side if they have to.
+        match (op, &a) {
+            (BinOp::And, &Value::Bool(false)) => return Ok(Value::Bool(false)),
+            (BinOp::Or, &Value::Bool(true)) => return Ok(Value::Bool(true)),
+            (BinOp::And, _) | (BinOp::Or, _) => return self.eval(rhs),
+            _ => {}
+        }
+        let b = self.eval(rhs)?;
+        arithmetic(op, a, b).map_err(|message| Error::panic(expr.span, message))
+    }
+
+    fn stmt(&mut self, stmt: &Stmt) -> Result<(), Error> {
+        match *stmt {
+            Stmt::Let(ref pat, _, ref value) => {
+                let ty = value.ty.defaulted();
+                let value = self.eval(value)?;
+                self.bind(pat, ty, value);
+            }
+            Stmt::Assign(ref name, span, op, ref value) => {
+                let span = span.to(value.span);
+                let mut value = self.eval(value)?;
+                if let Some(op) = op {
+                    let old = self.lookup(name).value.clone();
+                    value = arithmetic(op, old, value).map_err(|message| Error::panic(span, message))?;
+                }
+                let binding = self.stack.iter_mut().rev().find(|binding| binding.name == *name).expect("checked");
+                binding.value = value;
+            }
+            Stmt::Expr(ref expr) => {
+                self.eval(expr)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn bind(&mut self, pat: &Pat, ty: Ty, value: Value) {
+        match (pat, ty, value) {
+            (&Pat::Bind(ref name, mutable), ty, value) => self.stack.push(Binding {
+                name: name.clone(),
+                ty,
+                mutable,
+                value,
+            }),
+            (Pat::Tuple(pats, _), Ty::Tuple(tys), Value::Tuple(values)) => {
+                for ((pat, ty), value) in pats.iter().zip(tys).zip(values) {
+                    self.bind(pat, ty, value);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+// a binary operation on two values of the same type, or the panic message.
+fn arithmetic(op: BinOp, a: Value, b: Value) -> Result<Value, &'static str> {
+    if op.is_comparison() {
+        let ordering = compare(&a, &b);
+        return Ok(Value::Bool(match op {
+            BinOp::Eq => ordering == Some(Ordering::Equal),
+            BinOp::Ne => ordering != Some(Ordering::Equal),
+            BinOp::Lt => ordering == Some(Ordering::Less),
+            BinOp::Le => ordering == Some(Ordering::Less) || ordering == Some(Ordering::Equal),
+            BinOp::Gt => ordering == Some(Ordering::Greater),
+            _ => ordering == Some(Ordering::Greater) || ordering == Some(Ordering::Equal),
+        }));
+    }
+    match (a, b) {
+        (Value::Int(t, a), Value::Int(_, b)) => {
+            if b == 0 && op == BinOp::Div {
+                return Err("attempt to divide by zero");
+            }
+            if b == 0 && op == BinOp::Rem {
+                return Err("attempt to calculate the remainder with a divisor of zero");
+            }
+            int_op(op, t, a, b).map(|n| Value::Int(t, n)).ok_or(match op {
+                BinOp::Add => "attempt to add with overflow",
+                BinOp::Sub => "attempt to subtract with overflow",
+                BinOp::Mul => "attempt to multiply with overflow",
+                BinOp::Div => "attempt to divide with overflow",
+                _ => "attempt to calculate the remainder with overflow",
+            })
+        }
+        (Value::Float(t, a), Value::Float(_, b)) => Ok(Value::Float(t, t.round(match op {
+            BinOp::Add => a + b,
+            BinOp::Sub => a - b,
+            BinOp::Mul => a * b,
+            BinOp::Div => a / b,
+            _ => a % b,
+        }))),
+        _ => unreachable!("checked"),
+    }
+}
+
+// SESSIONS
+
+// what a line did.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Outcome {
+    // a let made a variable, along with the type of the variable it
+    // shadows, if there was one with the same name.
+    Bound(Binding, Option<Ty>),
+    // a variable was given a new value.
+    Assigned(Binding),
+    // the value of the line's last expression.
+    Value(Ty, Value),
+}
+
+// the variables made so far, kept from one line to the next.
+#[derive(Debug, Clone, Default)]
+pub struct Session {
+    stack: Vec<Binding>,
+}
+
+impl Session {
+    pub fn new() -> Session {
+        Session::default()
+    }
+
+    // the variables in scope, without the shadowed ones.
+    pub fn variables(&self) -> Vec<&Binding> {
+        self.stack
+            .iter()
+            .enumerate()
+            .filter(|&(i, binding)| !self.stack[i + 1..].iter().any(|later| later.name == binding.name))
+            .map(|(_, binding)| binding)
+            .collect()
+    }
+
+    fn check(&self, input: &str) -> Result<(Body, Option<Ty>), Error> {
+        let (mut stmts, mut tail) = parse(input)?;
+        let mut checker = Checker {
+            scope: self.stack.iter().map(|b| (b.name.clone(), b.ty.clone(), b.mutable)).collect(),
+        };
+        for stmt in stmts.iter_mut() {
+            checker.stmt(stmt)?;
+            check_stmt_literals(stmt)?;
+        }
+        let ty = match tail {
+            Some(ref mut tail) => {
+                let ty = checker.finish(tail)?;
+                check_literals(tail, false)?;
+                Some(ty)
+            }
+            None => None,
+        };
+        Ok(((stmts, tail), ty))
+    }
+
+    // the type of an expression, without running it.
+    pub fn type_of(&self, input: &str) -> Result<Ty, Error> {
+        match self.check(input)? {
+            ((ref stmts, _), Some(ty)) if stmts.is_empty() => Ok(ty),
+            _ => Err(Error::syntax(Span::new(0, input.len()), "expected just an expression".to_string())),
+        }
+    }
+
+    // checks, then runs, a line, if it panics halfway, the variables it
+    // made, or changed, before that are forgotten.
+    pub fn run(&mut self, input: &str) -> Result<Vec<Outcome>, Error> {
+        let ((stmts, tail), ty) = self.check(input)?;
+        let mut machine = Machine { stack: self.stack.clone() };
+        let mut outcomes = Vec::new();
+
+        for stmt in stmts.iter() {
+            let mark = machine.stack.len();
+            machine.stmt(stmt)?;
+            match *stmt {
+                Stmt::Let(..) => {
+                    for (i, binding) in machine.stack.iter().enumerate().skip(mark) {
+                        let shadowed = machine.stack[..mark]
+                            .iter()
+                            .rev()
+                            .find(|earlier| earlier.name == binding.name)
+                            .map(|earlier| earlier.ty.clone());
+                        // (a, a) binds a twice, the second one wins.
+                        if machine.stack[i + 1..].iter().all(|later| later.name != binding.name) {
+                            outcomes.push(Outcome::Bound(binding.clone(), shadowed));
+                        }
+                    }
+                }
+                Stmt::Assign(ref name, ..) => outcomes.push(Outcome::Assigned(machine.lookup(name).clone())),
+                Stmt::Expr(_) => {}
+            }
+        }
+        if let (Some(tail), Some(ty)) = (tail, ty) {
+            outcomes.push(Outcome::Value(ty, machine.eval(&tail)?));
+        }
+
+        self.stack = machine.stack;
+        Ok(outcomes)
+    }
+}
@@ -0,0 +1,341 @@
+// memory layout, up close
+
+// every type takes up a number of bytes, its size, and has to start at an
+// address that's a multiple of its alignment, a u32 at a multiple of 4, an
+// f64 at a multiple of 8. a tuple, or a struct, puts its fields one after
+// the other, but every field has to be aligned, so there can be gaps, the
+// padding, and the whole thing is made a multiple of its largest alignment,
+// so that the next one in an array is aligned too.
+
+// unless it's asked not to, with #[repr(C)], rust is free to put the fields
+// in whatever order wastes the least, so (i32, f64, u8) doesn't keep the
+// order it's written in. nothing here is worked out from those rules, the
+// sizes and offsets are measured, with size_of, align_of and offset_of!,
+// and for a struct that's typed in, by building a program that measures it.
+
+use std::mem::{align_of, size_of};
+use std::num::NonZeroU32;
+
+use rustc::{self, Diagnostic};
+
+pub struct Field {
+    // the index of a tuple field, or the name of a struct field.
+    pub name: String,
+    pub ty: String,
+    pub offset: usize,
+    pub size: usize,
+    pub align: usize,
+}
+
+pub struct Layout {
+    pub ty: String,
+    pub size: usize,
+    pub align: usize,
+    // in the order they were declared in, empty when what's inside is
+    // private, like a String's.
+    pub fields: Vec<Field>,
+    // what's inside, when the fields can't be looked at.
+    pub insides: Option<&'static str>,
+}
+
+// what marks a field's bytes in the diagram, in the order it was declared.
+pub fn label(index: usize) -> char {
+    match index {
+        0..=9 => (b'0' + index as u8) as char,
+        10..=35 => (b'a' + (index - 10) as u8) as char,
+        _ => '#',
+    }
+}
+
+impl Layout {
+    // the fields, in the order they're in memory, with their labels.
+    pub fn in_memory(&self) -> Vec<(char, &Field)> {
+        let mut fields: Vec<(char, &Field)> = self.fields.iter().enumerate().map(|(i, field)| (label(i), field)).collect();
+        fields.sort_by_key(|&(_, field)| field.offset);
+        fields
+    }
+
+    // the gaps between the fields, and after the last one, as the offset
+    // each starts at, and how many bytes it is.
+    pub fn padding(&self) -> Vec<(usize, usize)> {
+        if self.insides.is_some() {
+            return Vec::new();
+        }
+        let mut used = vec![false; self.size];
+        for field in &self.fields {
+            for byte in &mut used[field.offset..field.offset + field.size] {
+                *byte = true;
+            }
+        }
+        let mut gaps: Vec<(usize, usize)> = Vec::new();
+        for (offset, _) in used.iter().enumerate().filter(|&(_, &used)| !used) {
+            match gaps.last_mut() {
+                Some(&mut (start, ref mut len)) if start + *len == offset => *len += 1,
+                _ => gaps.push((offset, 1)),
+            }
+        }
+        gaps
+    }
+
+    // a character for every byte, the label of the field it belongs to, a
+    // dot for padding, or # for what's inside a type we can't look into,
+    // in groups of 8.
+    pub fn diagram(&self) -> String {
+        let mut bytes = vec![if self.insides.is_some() { '#' } else { '.' }; self.size];
+        for (label, field) in self.in_memory() {
+            for byte in &mut bytes[field.offset..field.offset + field.size] {
+                *byte = label;
+            }
+        }
+        let groups: Vec<String> = bytes.chunks(8).map(|group| group.iter().collect()).collect();
+        groups.join(" ")
+    }
+}
+
+pub fn field<T>(name: &str, ty: &str, offset: usize) -> Field {
+    Field { name: name.to_string(), ty: ty.to_string(), offset, size: size_of::<T>(), align: align_of::<T>() }
+}
+
+// a tuple, measured field by field, the offsets come from offset_of!.
+macro_rules! tuple {
+    ($tuple:ty, $($index:tt: $field:ty),*) => {
+        Layout {
+            ty: stringify!($tuple).to_string(),
+            size: size_of::<$tuple>(),
+            align: align_of::<$tuple>(),
+            fields: vec![$(field::<$field>(stringify!($index), stringify!($field), std::mem::offset_of!($tuple, $index))),*],
+            insides: None,
+        }
+    };
+}
+
+// an array, its elements are always one after the other, without gaps.
+pub fn array<T>(ty: &str, element: &str, len: usize) -> Layout {
+    Layout {
+        ty: ty.to_string(),
+        size: size_of::<T>() * len,
+        align: align_of::<T>(),
+        fields: (0..len).map(|i| field::<T>(&format!("[{}]", i), element, i * size_of::<T>())).collect(),
+        insides: None,
+    }
+}
+
+pub fn opaque<T>(ty: &str, insides: &'static str) -> Layout {
+    Layout { ty: ty.to_string(), size: size_of::<T>(), align: align_of::<T>(), fields: Vec::new(), insides: Some(insides) }
+}
+
+// the types from the datatypes lesson, and a few others worth a look.
+pub fn known() -> Vec<Layout> {
+    vec![
+        tuple!((i32, f64, u8), 0: i32, 1: f64, 2: u8),
+        tuple!((u8, u16, u8), 0: u8, 1: u16, 2: u8),
+        tuple!((u8, u32, u16, u64), 0: u8, 1: u32, 2: u16, 3: u64),
+        tuple!((bool, char), 0: bool, 1: char),
+        array::<i32>("[i32; 4]", "i32", 4),
+        array::<u8>("[u8; 5]", "u8", 5),
+        array::<(u8, u16)>("[(u8, u16); 3]", "(u8, u16)", 3),
+        opaque::<()>("()", "nothing at all"),
+        opaque::<String>("String", "a pointer to the bytes on the heap, their capacity, and their length"),
+        opaque::<&str>("&str", "a pointer to the bytes, and their length, a fat pointer"),
+        opaque::<Vec<i32>>("Vec<i32>", "a pointer to the elements on the heap, their capacity, and their length"),
+        opaque::<Box<i32>>("Box<i32>", "a pointer to the i32 on the heap"),
+        opaque::<&[i32]>("&[i32]", "a pointer to the elements, and how many there are, a fat pointer"),
+    ]
+}
+
+// finds one of the known types, however it's spaced.
+pub fn find(ty: &str) -> Option<Layout> {
+    let squashed = |ty: &str| ty.split_whitespace().collect::<String>();
+    known().into_iter().find(|layout| squashed(&layout.ty) == squashed(ty))
+}
+
+// NICHES
+
+// an Option<T> has to say whether it's Some or None, usually that takes a
+// tag, and the padding to keep T aligned after it, but when T has values
+// it can never hold, like a reference that's never null, None is stored as
+// one of those, the niche, and the Option is no bigger than T.
+pub struct Niche {
+    pub ty: &'static str,
+    pub size: usize,
+    pub option_size: usize,
+    // which values T can't have, or why there aren't any.
+    pub why: &'static str,
+}
+
+fn niche<T>(ty: &'static str, why: &'static str) -> Niche {
+    Niche { ty, size: size_of::<T>(), option_size: size_of::<Option<T>>(), why }
+}
+
+pub fn niches() -> Vec<Niche> {
+    vec![
+        niche::<&i32>("&i32", "a reference is never null"),
+        niche::<Box<i32>>("Box<i32>", "a box is never null"),
+        niche::<String>("String", "its pointer is never null"),
+        niche::<bool>("bool", "only 0 and 1 are a bool"),
+        niche::<char>("char", "a char only goes up to 0x10ffff"),
+        niche::<NonZeroU32>("NonZeroU32", "it's never 0"),
+        niche::<u32>("u32", "every value is a u32"),
+        niche::<f64>("f64", "every bit pattern is an f64"),
+    ]
+}
+
+// STRUCTS
+
+// a struct that's been typed in, like "struct Point { x: u8, y: u32 }".
+pub struct Definition {
+    // all of it, attributes like #[repr(C)] included.
+    pub source: String,
+    pub name: String,
+    // names, and types, a tuple struct's fields are named 0, 1, 2 ..
+    pub fields: Vec<(String, String)>,
+}
+
+// splits at the commas that aren't inside brackets, (u8, u16) is one type.
+// the definition ends up in a program that's run, so it has to be fields,
+// and nothing else, no braces, that could end the struct early.
+fn split_fields(text: &str) -> Result<Vec<&str>, String> {
+    let unbalanced = || "the brackets in the fields don't match up".to_string();
+    let mut fields = Vec::new();
+    let (mut depth, mut start, mut previous) = (0, 0, ' ');
+    for (i, c) in text.char_indices() {
+        match c {
+            '{' | '}' => return Err("the fields can't have braces in them".to_string()),
+            '(' | '[' | '<' => depth += 1,
+            // the arrow of fn(u8) -> u8 isn't a bracket.
+            '>' if previous == '-' => {}
+            ')' | ']' | '>' if depth == 0 => return Err(unbalanced()),
+            ')' | ']' | '>' => depth -= 1,
+            ';' if depth == 0 => return Err("fields are separated by commas, not semicolons".to_string()),
+            ',' if depth == 0 => {
+                fields.push(text[start..i].trim());
+                start = i + 1;
+            }
+            _ => {}
+        }
+        previous = c;
+    }
+    if depth != 0 {
+        return Err(unbalanced());
+    }
+    fields.push(text[start..].trim());
+    fields.retain(|field| !field.is_empty());
+    Ok(fields)
+}
+
+fn is_ident(name: &str) -> bool {
+    name.chars().next().is_some_and(|c| c.is_alphabetic() || c == '_')
+        && name.chars().all(|c| c.is_alphanumeric() || c == '_')
+}
+
+pub fn parse(source: &str) -> Result<Definition, String> {
+    let source = source.trim();
+
+    // #[repr(C)], and the other reprs, are left for rustc to read, no
+    // other attribute changes a layout.
+    let mut rest = source;
+    while rest.starts_with("#[") {
+        let end = rest.find(']').ok_or("an attribute needs its closing ]")?;
+        if !rest.starts_with("#[repr(") {
+            return Err(format!("`{}` doesn't change the layout, only #[repr(..)] does", &rest[..end + 1]));
+        }
+        rest = rest[end + 1..].trim_start();
+    }
+    let rest = rest.strip_prefix("pub ").unwrap_or(rest).trim_start();
+    let rest = rest.strip_prefix("struct ").ok_or("a struct starts with \"struct\", like struct Point { x: u8, y: u32 }")?;
+
+    let name_end = rest.find(|c: char| !(c.is_alphanumeric() || c == '_')).unwrap_or(rest.len());
+    let (name, body) = (&rest[..name_end], rest[name_end..].trim());
+    if !is_ident(name) {
+        return Err("the struct needs a name, like struct Point { x: u8, y: u32 }".to_string());
+    }
+    if body.starts_with('<') {
+        return Err("a generic struct has no layout until its parameters are filled in, try it without them".to_string());
+    }
+
+    let fields = if body == ";" || body.is_empty() {
+        Vec::new()
+    } else if let Some(inside) = body.strip_prefix('{').and_then(|body| body.strip_suffix('}')) {
+        let mut fields = Vec::new();
+        for field in split_fields(inside)? {
+            let field = field.strip_prefix("pub ").unwrap_or(field);
+            let colon = field.find(':').ok_or_else(|| format!("`{}` needs a type, like `{}: u8`", field, field))?;
+            let (name, ty) = (field[..colon].trim(), field[colon + 1..].trim());
+            if !is_ident(name) || ty.is_empty() {
+                return Err(format!("`{}` isn't a field, a field looks like `x: u8`", field));
+            }
+            fields.push((name.to_string(), ty.to_string()));
+        }
+        fields
+    } else if let Some(inside) = body.strip_suffix(';').unwrap_or(body).trim_end().strip_prefix('(').and_then(|body| body.strip_suffix(')')) {
+        split_fields(inside)?.into_iter().enumerate().map(|(i, ty)| (i.to_string(), ty.strip_prefix("pub ").unwrap_or(ty).to_string())).collect()
+    } else {
+        return Err("the fields go in braces, like struct Point { x: u8, y: u32 }, or parentheses, like struct Pair(u8, u32);".to_string());
+    };
+
+    // a tuple struct, or a unit struct, needs its ; for rustc.
+    let source = if source.ends_with('}') || source.ends_with(';') { source.to_string() } else { format!("{};", source) };
+    Ok(Definition { source, name: name.to_string(), fields })
+}
+
+// the program that measures the struct, it prints its size and alignment,
+// then the offset, size and alignment of each field, a line each.
+pub fn program(definition: &Definition) -> String {
+    let mut main = format!("    println!(\"{{}} {{}}\", size_of::<{0}>(), align_of::<{0}>());\n", definition.name);
+    for (name, ty) in &definition.fields {
+        main.push_str(&format!(
+            "    println!(\"{{}} {{}} {{}}\", offset_of!({}, {}), size_of::<{2}>(), align_of::<{2}>());\n",
+            definition.name, name, ty
+        ));
+    }
+    format!(
+        "#![allow(dead_code)]\nuse std::mem::{{align_of, offset_of, size_of}};\n\n{}\n\nfn main() {{\n{}}}\n",
+        definition.source, main
+    )
+}
+
+// what went wrong measuring a struct.
+pub enum Error {
+    // rustc couldn't be run at all.
+    Rustc(String),
+    // the struct doesn't compile, here's what rustc said.
+    Rejected(Vec<Diagnostic>),
+}
+
+// builds, and runs, a program that measures the struct, so the layout is
+// whatever rustc really makes of it.
+pub fn measure(definition: &Definition) -> Result<Layout, Error> {
+    let output = match rustc::run(&program(definition)) {
+        Ok(Ok(output)) => output,
+        Ok(Err(diagnostics)) => {
+            // a type that isn't there is missing from every line that
+            // measures it, once is enough.
+            let mut errors: Vec<Diagnostic> = Vec::new();
+            for diagnostic in diagnostics.into_iter().filter(Diagnostic::is_error) {
+                if !errors.iter().any(|error| error.headline() == diagnostic.headline()) {
+                    errors.push(diagnostic);
+                }
+            }
+            return Err(Error::Rejected(errors));
+        }
+        Err(e) => return Err(Error::Rustc(e.to_string())),
+    };
+    let numbers: Vec<Vec<usize>> = output
+        .lines()
+        .map(|line| line.split_whitespace().filter_map(|n| n.parse().ok()).collect())
+        .collect();
+    let unexpected = || Error::Rustc(format!("unexpected output from the program: {}", output.trim()));
+
+    let (size, align) = match numbers.first().map(Vec::as_slice) {
+        Some(&[size, align]) => (size, align),
+        _ => return Err(unexpected()),
+    };
+    let mut fields = Vec::new();
+    for (i, (name, ty)) in definition.fields.iter().enumerate() {
+        match numbers.get(i + 1).map(Vec::as_slice) {
+            Some(&[offset, size, align]) => fields.push(Field { name: name.clone(), ty: ty.clone(), offset, size, align }),
+            _ => return Err(unexpected()),
+        }
+    }
+    Ok(Layout { ty: definition.name.clone(), size, align, fields, insides: None })
+}
@@ -0,0 +1,292 @@
+// control flow
+
+use std::thread;
+use std::time::{Duration, Instant};
+
+use cli::Verbosity;
+use counting;
+use expr;
+use json::Value;
+use context::Context;
+use lessons::exercise::Exercise;
+use lessons::{self, Lesson, Section};
+use progress::Progress;
+
+pub struct ControlFlow;
+
+impl Lesson for ControlFlow {
+    fn id(&self) -> &'static str {
+        "controlflow"
+    }
+
+    fn title(&self) -> &'static str {
+        "control flow"
+    }
+
+    fn summary(&self) -> &'static str {
+        "if and else if, loop with break values, while, and for, by reading in a number and counting up to it."
+    }
+
+    fn prerequisites(&self) -> &'static [&'static str] {
+        &["datatypes", "functions"]
+    }
+
+    fn commands(&self) -> &'static [&'static str] {
+        &["bench"]
+    }
+
+    fn source(&self) -> &'static str {
+        include_str!("controlflow.rs")
+    }
+
+    fn sections(&self) -> &'static [Section] {
+        SECTIONS
+    }
+
+    fn quiz(&self) -> &'static str {
+        include_str!("quizzes/controlflow.quiz")
+    }
+
+    // a number small enough that counting up to it is quick.
+    fn example_input(&self) -> &'static str {
+        "10\n"
+    }
+
+    fn exercises(&self) -> &'static [Exercise] {
+        EXERCISES
+    }
+
+    // the counting loop can be split over several threads, and the "bench"
+    // command compares the different ways of doing the counting, instead of
+    // running the lesson.
+    fn run(&self, ctx: &mut Context) {
+        let options = ctx.options.clone();
+        if options.command.as_deref() == Some("bench") {
+            let result = bench(ctx, options.threads.unwrap_or_else(counting::default_threads),
+                               options.max.unwrap_or(10_000_000));
+            if let Err(message) = result {
+                ctx.error(&message);
+            }
+            return;
+        }
+        lessons::run_sections(ctx, SECTIONS);
+    }
+}
+
+static SECTIONS: &[Section] = &[
+    section!("if, loop and while", lesson, check_nonzero),
+    section!("if in a let statement"),
+];
+
+static EXERCISES: &[Exercise] = &[
+    Exercise {
+        name: "if_needs_bool",
+        title: "an if on something that isn't a bool",
+        code: include_str!("exercises/controlflow/if_needs_bool.rs"),
+        error: "E0308",
+        keep: None,
+    },
+];
+
+fn lesson(ctx: &mut Context) {
+
+    let options = ctx.options.clone();
+    let threads = options.threads.unwrap_or(1);
+
+    outln!(ctx, "Enter a number ..");
+
+    let mynum: i64 = loop {
+
+        let mut mynum = String::new();
+
+        let read = ctx.read_line( &mut mynum)
+            .expect("failed to read line");
+
+        if read == 0 {
+            ctx.end_of_input();
+            return;
+        }
+
+        // instead of a plain parse, the input is evaluated as an arithmetic
+        // expression, so "2**10 - 1" or "-0x10" work too, on failure the error
+        // is printed with a caret pointing at the offending part of the input.
+        let mynum = match expr::eval_i64(mynum.trim()) {
+            Ok(mynum) => mynum,
+            Err(e) => {
+                ctx.invalid_input(mynum.trim(), &e, "try again!");
+                continue;
+            }
+        };
+
+        let valid = check_nonzero(ctx, mynum);
+        ctx.event("input", vec![
+            ("value", Value::Int(mynum as i128)),
+            ("valid", Value::Bool(valid)),
+        ]);
+
+        if valid {
+            outln!(ctx, "Number is valid, exiting ...");
+            break mynum;
+        } else {
+            outln!(ctx, "Number is not valid, try again!");
+        }
+
+        // if expression leads to evaluation of it's arms, much like the match expression, and
+        // on success, leads to associated block's execution. else is optional, and can be used
+        // in case we want to handle some code execution for the if block failing. the condition
+        // evaluated must always result in a bool type.
+
+        // optionally, variables can be returned via the break expression, and be used together
+        // with a looped expression to assign values to variables, provided a variable is being
+        // returned by the loop body.
+    };
+
+    let mut counter  = 0;
+    let mut sumall: i128 = 0;
+    let mut tempsum: i128  = 0;
+    let arr_null = [1,2,3,4,5,6,7,8,9];
+    outln!(ctx, "counting up to the chosen number ..");
+
+    // printing every single counter floods the terminal for large numbers, so
+    // the progress reporter prints a summary every now and then instead, the
+    // old line per counter behaviour is still there, with --verbose.
+    let mut progress = Progress::new(ctx, "counting", counting::steps(mynum), options.verbosity);
+
+    if threads > 1 {
+        // with more than one thread, the sum is computed by the workers in the
+        // background, each worker taking a contiguous chunk of the counters,
+        // while the main thread reports their progress, the partial sums are
+        // combined in chunk order, so the output stays the same, no matter
+        // how many threads are used.
+        // with --verbose, every counter still gets its line, in the order the
+        // loop would have got to it, as soon as the workers are past it.
+        let workers = counting::spawn(mynum, threads);
+        let mut shown = 0;
+        loop {
+            let finished = workers.is_finished();
+            if options.verbosity == Verbosity::Verbose {
+                let reached = workers.reached();
+                while shown < reached {
+                    outln!(ctx, "currently at {}..", counting::counter(mynum, shown));
+                    shown += 1;
+                }
+            }
+            progress.set(ctx, workers.done());
+            if finished {
+                break;
+            }
+            thread::sleep(Duration::from_millis(10));
+        }
+        sumall = workers.join();
+    } else if mynum < 0 {
+        while counter >= mynum {
+            progress.inc(ctx, format_args!("currently at {}..", counter));
+            for element in arr_null.iter() {
+                tempsum += element * counter as i128;
+            }
+            sumall += tempsum;
+            tempsum = 0;
+            counter -= 1;
+        }
+    } else {
+        while counter <= mynum {
+            progress.inc(ctx, format_args!("currently at {}..", counter));
+            for element in arr_null.iter() {
+                tempsum += element * counter as i128;
+            }
+            sumall += tempsum;
+            tempsum = 0;
+            counter += 1;
+        }
+    }
+    progress.finish(ctx);
+
+    outln!(ctx, "the totally useless summed up value was calculated upto, {}", sumall);
+    ctx.event("result", vec![
+        ("mynum", Value::Int(mynum as i128)),
+        ("threads", Value::UInt(threads as u128)),
+        ("sumall", Value::Int(sumall)),
+    ]);
+    outln!(ctx, "all done, exiting...");
+}
+
+// runs the counting for growing values of mynum, single threaded, split over
+// worker threads, and using the closed form, and prints how long each took.
+fn bench(ctx: &mut Context, threads: usize, max: i64) -> Result<(), String> {
+    outln!(ctx, "benchmarking the counting loop, using {} threads", threads);
+    outln!(ctx, "{:>14} {:>12} {:>12} {:>12}   result", "mynum", "single", "multi", "closed");
+
+    let mut mynum: i64 = 10;
+    while mynum <= max.max(10) {
+        let (single, single_time) = timed(|| counting::sum_serial(mynum));
+        let (multi, multi_time) = timed(|| counting::sum_threaded(mynum, threads));
+        let (closed, closed_time) = timed(|| counting::sum_closed_form(mynum));
+        let closed = closed.ok_or_else(|| format!("the sum for {} doesn't fit in an i128", mynum))?;
+
+        outln!(ctx, "{:>14} {:>12} {:>12} {:>12}   {}", mynum,
+               millis(single_time), millis(multi_time), millis(closed_time), single);
+        ctx.event("bench", vec![
+            ("mynum", Value::Int(mynum as i128)),
+            ("threads", Value::UInt(threads as u128)),
+            ("single_ms", Value::Float(single_time.as_secs_f64() * 1000.0)),
+            ("multi_ms", Value::Float(multi_time.as_secs_f64() * 1000.0)),
+            ("closed_ms", Value::Float(closed_time.as_secs_f64() * 1000.0)),
+            ("result", Value::Int(single)),
+        ]);
+
+        // all three have to agree, otherwise one of them is wrong.
+        if single != multi || single != closed {
+            return Err(format!("mismatch at {}! single={}, multi={}, closed={}",
+                               mynum, single, multi, closed));
+        }
+
+        mynum = match mynum.checked_mul(10) {
+            Some(next) => next,
+            None => break,
+        };
+    }
+    Ok(())
+}
+
+fn timed<T, F: FnOnce() -> T>(f: F) -> (T, Duration) {
+    let start = Instant::now();
+    let value = f();
+    (value, start.elapsed())
+}
+
+fn millis(d: Duration) -> String {
+    format!("{:.3}ms", d.as_secs_f64() * 1000.0)
+}
+
+#[allow(clippy::needless_return)]
+fn check_nonzero(ctx: &mut Context, num: i64) -> bool {
+
+    // it's also possible to have nested and/or multiple conditions with if, else and "else if"
+
+    if num > 0 {
+        if num % 2 == 0 {
+            outln!(ctx, "Even and Positive");
+        } else {
+            outln!(ctx, "Odd and Positive");
+        }
+        return true
+    } else if num < 0 {
+        if num % 2 == 0 {
+            outln!(ctx, "Even and Negative");
+        } else {
+            outln!(ctx, "Odd and Negative");
+        }
+        return true
+    } else {
+        outln!(ctx, "Number is Zero");
+        return false
+    }
+}
+
+// if in a let statement
+
+// it's also possible to use if's inside a let statement as if is a expression that results in a value
+// being returned, unlike a statement. in cases where multiple value are being evaluated for
+// assignment, the types must be consistent, i.e. and if else used in a let for value assignment cannot
+// optionally assign different / incompatible types, as rust needs to know types at compile time,
+// and not at run time.
@@ -0,0 +1,723 @@
+//data types
+
+use context::Context;
+use float::{self, Anatomy, Class, Float, Rounding};
+use json::Value;
+use layout::{self, Layout, Niche};
+use lessons::exercise::Exercise;
+use lessons::{self, Lesson, Section};
+use literal;
+use overflow::{self, Op, Report};
+use unicode;
+
+pub struct DataTypes;
+
+impl Lesson for DataTypes {
+    fn id(&self) -> &'static str {
+        "datatypes"
+    }
+
+    fn title(&self) -> &'static str {
+        "data types"
+    }
+
+    fn summary(&self) -> &'static str {
+        "the scalar types, integers, floats, booleans and characters, and the compound types, tuples and arrays."
+    }
+
+    fn prerequisites(&self) -> &'static [&'static str] {
+        &["variables"]
+    }
+
+    fn commands(&self) -> &'static [&'static str] {
+        &["float", "layout", "literal", "overflow", "unicode"]
+    }
+
+    fn source(&self) -> &'static str {
+        include_str!("datatypes.rs")
+    }
+
+    fn sections(&self) -> &'static [Section] {
+        SECTIONS
+    }
+
+    fn quiz(&self) -> &'static str {
+        include_str!("quizzes/datatypes.quiz")
+    }
+
+    fn exercises(&self) -> &'static [Exercise] {
+        EXERCISES
+    }
+
+    // "datatypes literal" inspects any number literal we type in,
+    // "datatypes overflow" any integer operation, "datatypes float" any
+    // float, cast or float operation, "datatypes layout" any type's memory
+    // layout, and "datatypes unicode" any text, char by char, instead of
+    // running the lesson.
+    fn run(&self, ctx: &mut Context) {
+        match ctx.options.command.as_deref() {
+            Some("float") => explore_floats(ctx),
+            Some("layout") => explore_layouts(ctx),
+            Some("unicode") => explore_text(ctx),
+            Some("literal") => explore_literals(ctx),
+            Some("overflow") => explore_overflow(ctx),
+            _ => lessons::run_sections(ctx, SECTIONS),
+        }
+    }
+}
+
+static SECTIONS: &[Section] = &[
+    section!("SCALAR TYPES", scalar_types),
+    section!("number literals", number_literals),
+    section!("integer overflow", integer_overflow),
+    section!("floating point", floating_point),
+    section!("boolean type", boolean_type),
+    section!("character type", character_type),
+    section!("chars, bytes and graphemes", chars_and_bytes),
+    section!("COMPOUND TYPES", compound_types),
+    section!("the array type", array_type),
+    section!("memory layout", memory_layout),
+];
+
+static EXERCISES: &[Exercise] = &[
+    Exercise {
+        name: "array_length",
+        title: "an array that's too short for its type",
+        code: include_str!("exercises/datatypes/array_length.rs"),
+        error: "E0308",
+        keep: None,
+    },
+];
+
+// SCALAR TYPES
+
+#[allow(clippy::unnecessary_cast)]
+fn scalar_types(ctx: &mut Context){
+
+    // since rust is a statically typed language, the compiler needs to
+    // know the types of all variables at compile time, it can usually infer
+    // what type we want to use based on the value, and how it's used, but
+    // in cases when many types are possible, type annotations are a must.
+
+    let guess: u32 = "42".parse().expect("Not a Number!");
+
+    // scalar types represent a single value, rust has 4 primary scalar types
+    // integers, floating points, booleans and characters
+
+    // integers have 2 variants, signed and unsigned (i & u)
+    // and come in sizes of 8, 16, 32, 64 and 128 bits, and a last arch
+    // variant, depending on the computer architecture (usually 32 and 64)
+    // additionally, they can be written in any of the following forms,
+    // Decimal(98_22), Hex(0xff), Octal(0o77), Binary(0b'11110011')
+    // and Byte(b'A'), '_' is a visual separator
+
+    // using larger values, than what's specified by the type, will lead to
+    // overflow, and the program will panic, in debug mode, in release modes,
+    // overflow is not checked for, and values are wrapped around, i.e.
+    // passing 256 to 'u8' integer variable, makes it 0, 257 makes it 1 and so on...
+
+    // for floating points rust has 2 types, f32 and f64, f64 is the default type.
+
+    let float_x = 2.0; //f64
+
+    let float_y: f32 = 23.32; //f32
+
+    outln!(ctx, "{}, {}, {}",guess, float_x, float_y);
+    ctx.value("guess", &guess);
+    ctx.value("float_x", &float_x);
+    ctx.value("float_y", &float_y);
+
+    // Numeric Operations
+    // rust supports all mathematical operations, like add, subtract, multiply,
+    // divide and modulo.
+
+    let sum = float_x as f32 + 2.0 as f32;
+    let diff = float_y - 3.0;
+    let mul = float_x as f32 * float_y as f32;
+    let div = float_y / float_x as f32;
+    let rem = float_y % float_x as f32;
+
+    outln!(ctx, "sum={}, diff={}, mul={}, div={}, rem={}",sum,diff,mul,div,rem);
+    ctx.value("sum", &sum);
+    ctx.value("diff", &diff);
+    ctx.value("mul", &mul);
+    ctx.value("div", &div);
+    ctx.value("rem", &rem);
+}
+
+// number literals
+
+fn number_literals(ctx: &mut Context){
+
+    // here are all the ways of writing a number, with the type, and the
+    // value, each one ends up with. without a suffix, an integer literal is
+    // an i32, and a float literal an f64, unless how it's used says otherwise.
+
+    // a literal that doesn't fit in its type doesn't compile, like 256u8,
+    // but a minus in front lets a signed type go one further, -128i8 is fine.
+
+    // "rust_commons datatypes literal" inspects any literal we like.
+
+    let literals = [
+        "98_222", "0xff", "0o77", "0b1111_0000", "b'A'", "255u8",
+        "-128i8", "1e-3f32", "2.5", "1_000f64", "256u8", "0xffi8",
+    ];
+    for text in literals.iter() {
+        inspect_literal(ctx, text);
+    }
+}
+
+// prints what the literal is, or why it's refused.
+fn inspect_literal(ctx: &mut Context, text: &str) {
+    match literal::inspect(text) {
+        Ok(inspection) => {
+            let how = if inspection.defaulted { ", no suffix, so the default type" } else { "" };
+            outln!(ctx, "{:<14} {:<6} {:<10} {}{}", text, inspection.ty, inspection.value,
+                   inspection.literal.base.name(), how);
+            ctx.event("literal", vec![
+                ("input", Value::Str(text.to_string())),
+                ("type", Value::Str(inspection.ty.to_string())),
+                ("value", Value::Str(inspection.value.clone())),
+                ("base", Value::Str(inspection.literal.base.name().to_string())),
+                ("suffix", inspection.literal.suffix.map_or(Value::Null, |suffix| Value::Str(suffix.to_string()))),
+            ]);
+        }
+        Err(e) => {
+            outln!(ctx, "{}", e.render(text));
+            ctx.event("literal_rejected", vec![
+                ("input", Value::Str(text.to_string())),
+                ("message", Value::Str(e.message.clone())),
+            ]);
+        }
+    }
+}
+
+// asks for lines, and hands each one to handle, until we're done, with q,
+// or when the input runs out. the line only has its line ending taken off,
+// spaces and all, blank ones are skipped.
+fn explore<F: FnMut(&mut Context, &str)>(ctx: &mut Context, prompt: &str, mut handle: F) {
+    outln!(ctx, "{}", prompt);
+    loop {
+        let mut line = String::new();
+        let read = ctx.read_line(&mut line)
+            .expect("failed to read line");
+
+        let line = line.trim_end_matches(['\n', '\r']);
+        if read == 0 || line.trim() == "q" || line.trim() == "quit" {
+            return;
+        }
+        if !line.trim().is_empty() {
+            handle(ctx, line);
+        }
+    }
+}
+
+// says what the input was, and why it's no good.
+fn invalid_input(ctx: &mut Context, line: &str, message: String) {
+    outln!(ctx, "{}, try again!", message);
+    ctx.event("invalid_input", vec![
+        ("input", Value::Str(line.to_string())),
+        ("message", Value::Str(message)),
+    ]);
+}
+
+// asks for literals, and says what each of them is, until we're done.
+fn explore_literals(ctx: &mut Context) {
+    explore(ctx, "type a number literal, like 0xff, b'A', -128i8 or 1e-3f32, or q to quit ..", |ctx, line| {
+        inspect_literal(ctx, line.trim())
+    });
+}
+
+// integer overflow
+
+fn integer_overflow(ctx: &mut Context){
+
+    // what overflow does, for real, a u8 goes up to 255, so 255 + 1 doesn't
+    // fit, in a debug build, like the one cargo run makes, that's a panic,
+    // in a release build, cargo run --release, it wraps around to 0.
+
+    // when overflow is expected, rather than being a bug, the integer types
+    // have methods that say what should happen, whatever the build,
+    // checked_add gives None, wrapping_add wraps around, saturating_add
+    // stops at the largest value, and overflowing_add wraps around, and
+    // says whether it did.
+
+    // division overflows too, -128 is an i8, but 128 isn't, so i8::MIN / -1
+    // panics, in both kinds of build, as does dividing by zero.
+
+    // "rust_commons datatypes overflow" tries out any operation we like.
+
+    for example in ["255u8 + 1", "-128i8 / -1"].iter() {
+        let report = overflow::explore(example).expect("a valid example");
+        show_report(ctx, &report);
+    }
+}
+
+// prints the report, every way of doing the operation side by side.
+fn show_report(ctx: &mut Context, report: &Report) {
+    let show = |outcome: &overflow::Outcome| match *outcome {
+        Ok(ref value) => value.to_string(),
+        Err(ref e) => e.clone(),
+    };
+    let method = report.op.method();
+    let build = Report::build();
+
+    outln!(ctx, "{}", report.expression());
+    outln!(ctx, "  {:<18} {}", format!("checked_{}", method), match report.checked {
+        Ok(Value::Null) => "None".to_string(),
+        Ok(ref value) => format!("Some({})", value),
+        Err(ref e) => e.clone(),
+    });
+    outln!(ctx, "  {:<18} {}", format!("wrapping_{}", method), show(&report.wrapping));
+    match report.saturating {
+        Some(ref saturating) => outln!(ctx, "  {:<18} {}", format!("saturating_{}", method), show(saturating)),
+        None => outln!(ctx, "  {:<18} there isn't one", format!("saturating_{}", method)),
+    }
+    outln!(ctx, "  {:<18} {}", format!("overflowing_{}", method), match report.overflowing {
+        Ok((ref value, overflowed)) => format!("({}, {})", value, overflowed),
+        Err(ref e) => e.clone(),
+    });
+    for &(kind, outcome) in [("debug", &report.debug), ("release", &report.release)].iter() {
+        let note = match *outcome {
+            Err(ref e) if e.starts_with("doesn't compile") => "",
+            _ if kind == build => ", which is what this build just did",
+            _ => "",
+        };
+        outln!(ctx, "  {:<18} {}{}", format!("{} build", kind), show(outcome), note);
+    }
+
+    let json = |outcome: &overflow::Outcome| match *outcome {
+        Ok(ref value) => value.clone(),
+        Err(ref e) => Value::Str(e.clone()),
+    };
+    ctx.event("overflow", vec![
+        ("type", Value::Str(report.ty.to_string())),
+        ("op", Value::Str(method.to_string())),
+        ("lhs", report.lhs.clone()),
+        ("rhs", report.rhs.clone().unwrap_or(Value::Null)),
+        ("checked", json(&report.checked)),
+        ("wrapping", json(&report.wrapping)),
+        ("saturating", report.saturating.as_ref().map_or(Value::Null, json)),
+        ("overflowing", match report.overflowing {
+            Ok((ref value, overflowed)) => Value::Array(vec![value.clone(), Value::Bool(overflowed)]),
+            Err(ref e) => Value::Str(e.clone()),
+        }),
+        ("debug", json(&report.debug)),
+        ("release", json(&report.release)),
+        ("build", Value::Str(build.to_string())),
+    ]);
+}
+
+// asks for operations, and shows what each of them does, until we're done.
+fn explore_overflow(ctx: &mut Context) {
+    let prompt = format!("type an integer operation, like {}, or q to quit ..", overflow::EXAMPLES);
+    explore(ctx, &prompt, |ctx, line| {
+        let line = line.trim();
+        match overflow::explore(line) {
+            Ok(report) => show_report(ctx, &report),
+            Err(e) => invalid_input(ctx, line, e),
+        }
+    });
+}
+
+// floating point
+
+#[allow(clippy::unnecessary_cast)]
+fn floating_point(ctx: &mut Context){
+
+    // a float is stored in binary, as a sign bit, an exponent, the power of
+    // two it's multiplied by, and a mantissa, its digits, 23.32 has no exact
+    // binary form, any more than 1/3 has an exact decimal one, so float_y
+    // holds the f32 nearest to it, which is a little under.
+
+    // the answer to every operation is rounded to the nearest float too, so
+    // it's never more than half of the gap between two floats out, the
+    // unit in the last place, or ulp, but it adds up, which is why 0.1 + 0.2
+    // isn't 0.3, and why floats are hardly ever compared with ==.
+
+    // here are the literals, casts and operations from the scalar types,
+    // and how far each one's answer is from the exact one, dividing by 2.0
+    // only takes one off the exponent, so nothing's rounded, the error
+    // there is came from 23.32, before any of them.
+
+    // "rust_commons datatypes float" takes apart any float we like.
+
+    let float_x = 2.0;
+    let float_y: f32 = 23.32;
+    show_anatomy(ctx, &float::anatomy(Float::F32(float_y)));
+
+    let (x, y) = (Float::F32(float_x as f32), Float::F32(float_y));
+    let steps = [
+        float::literal("23.32", "f32"),
+        Ok(float::cast("float_x as f32", Float::F64(float_x), "f32")),
+        Ok(float::cast("2.0 as f32", Float::F64(2.0), "f32")),
+        float::operation("float_x as f32 + 2.0 as f32", x, Op::Add, Float::F32(2.0 as f32)),
+        float::operation("float_y - 3.0", y, Op::Sub, Float::F32(3.0)),
+        float::operation("float_x as f32 * float_y as f32", x, Op::Mul, y),
+        float::operation("float_y / float_x as f32", y, Op::Div, x),
+        float::operation("float_y % float_x as f32", y, Op::Rem, x),
+        float::explore("0.1 + 0.2"),
+    ];
+    for step in steps.iter() {
+        show_rounding(ctx, step.as_ref().expect("the lesson's floats"));
+    }
+}
+
+// prints the bits of a float, what they mean, and the floats either side.
+fn show_anatomy(ctx: &mut Context, anatomy: &Anatomy) {
+    let value = anatomy.value;
+    let exponent = format!("{:0w$b}", anatomy.exponent, w = value.widths().0 as usize);
+    let exponent = match anatomy.class {
+        Class::Normal => format!("{} is {}, less the bias of {}, so 2^{}", exponent, anatomy.exponent, value.bias(), anatomy.power),
+        Class::Subnormal => format!("{}, subnormal, so 2^{}, without the hidden 1", exponent, anatomy.power),
+        Class::Zero => format!("{}, and so's the mantissa, so it's zero", exponent),
+        _ => format!("{}, all ones, {}", exponent, anatomy.class.name()),
+    };
+
+    outln!(ctx, "{} as an {}", value, value.ty());
+    outln!(ctx, "  {:<9} {}", "bits", anatomy.bits());
+    outln!(ctx, "  {:<9} {}, {}", "sign", anatomy.sign, if anatomy.sign == 1 { "negative" } else { "positive" });
+    outln!(ctx, "  {:<9} {}", "exponent", exponent);
+    if let Some(ref exact) = anatomy.exact {
+        if anatomy.class != Class::Zero {
+            outln!(ctx, "  {:<9} {} × 2^{}", "mantissa", anatomy.significand, anatomy.power);
+        }
+        outln!(ctx, "  {:<9} {}", "exactly", exact);
+    }
+    for &(side, neighbour) in [("below", &anatomy.below), ("above", &anatomy.above)].iter() {
+        if let Some((float, ref gap)) = *neighbour {
+            outln!(ctx, "  {:<9} {}, {} away", side, float, gap.readable());
+        }
+    }
+
+    let gap = |neighbour: &Option<(Float, float::Decimal)>| {
+        neighbour.as_ref().map_or(Value::Null, |(_, gap)| Value::Str(gap.to_string()))
+    };
+    ctx.event("float", vec![
+        ("value", Value::Str(value.to_string())),
+        ("type", Value::Str(value.ty().to_string())),
+        ("bits", Value::Str(anatomy.bits())),
+        ("sign", Value::UInt(anatomy.sign as u128)),
+        ("exponent", Value::UInt(anatomy.exponent as u128)),
+        ("mantissa", Value::UInt(anatomy.mantissa as u128)),
+        ("class", Value::Str(anatomy.class.name().to_string())),
+        ("power", Value::Int(anatomy.power as i128)),
+        ("exact", anatomy.exact.as_ref().map_or(Value::Null, |exact| Value::Str(exact.to_string()))),
+        ("ulp_below", gap(&anatomy.below)),
+        ("ulp_above", gap(&anatomy.above)),
+    ]);
+}
+
+// prints what came out, what would have without rounding, and the error.
+fn show_rounding(ctx: &mut Context, rounding: &Rounding) {
+    let result = rounding.result;
+    let about = if rounding.terminates { "" } else { "about " };
+    outln!(ctx, "{} = {}, an {}", rounding.expression, result, result.ty());
+    match (&rounding.exact, &rounding.error) {
+        (Some(exact), Some(error)) => {
+            outln!(ctx, "  {:<9} {}{}", "exact", about, exact);
+            outln!(ctx, "  {:<9} {}", "stored", result.exact().expect("a finite result"));
+            if error.is_zero() {
+                outln!(ctx, "  {:<9} none, nothing was rounded", "error");
+            } else if rounding.terminates {
+                outln!(ctx, "  {:<9} {}, {:.2} ulp", "error", error.readable(), rounding.ulps.unwrap_or(0.0));
+            } else {
+                outln!(ctx, "  {:<9} about {:e}, {:.2} ulp", "error", error.to_f64(), rounding.ulps.unwrap_or(0.0));
+            }
+        }
+        (Some(exact), None) => {
+            outln!(ctx, "  {:<9} about {}, too big for an {}", "exact", exact.significant(17).scientific(), result.ty());
+        }
+        _ => outln!(ctx, "  there's no exact answer to compare {} with", result),
+    }
+
+    let decimal = |decimal: &Option<float::Decimal>| decimal.as_ref().map_or(Value::Null, |d| Value::Str(d.to_string()));
+    ctx.event("rounding", vec![
+        ("expression", Value::Str(rounding.expression.clone())),
+        ("type", Value::Str(result.ty().to_string())),
+        ("result", Value::Str(result.to_string())),
+        ("exact", decimal(&rounding.exact)),
+        ("terminates", Value::Bool(rounding.terminates)),
+        ("error", decimal(&rounding.error)),
+        ("ulps", rounding.ulps.map_or(Value::Null, Value::Float)),
+    ]);
+}
+
+// asks for floats, casts and operations, and takes each answer apart,
+// until we're done.
+fn explore_floats(ctx: &mut Context) {
+    let prompt = format!("type a float, a cast or an operation, like {}, or q to quit ..", float::EXAMPLES);
+    explore(ctx, &prompt, |ctx, line| {
+        let line = line.trim();
+        match float::explore(line) {
+            Ok(rounding) => {
+                show_rounding(ctx, &rounding);
+                show_anatomy(ctx, &float::anatomy(rounding.result));
+            }
+            Err(e) => invalid_input(ctx, line, e),
+        }
+    });
+}
+
+// boolean type
+
+fn boolean_type(ctx: &mut Context){
+
+    // usually used along with conditionals like 'if'
+
+    let t = true;
+    let f = false;
+    outln!(ctx, "using booleans: {}", t == f);
+    ctx.value("t == f", &(t == f));
+}
+
+// character type
+
+fn character_type(ctx: &mut Context){
+
+    // specified in single quotes, as opposed to String types which are in "".
+
+    let happy_cat = '😻';
+    outln!(ctx, "here's a happy cat, {}", happy_cat);
+    ctx.value("happy_cat", &happy_cat);
+}
+
+// chars, bytes and graphemes
+
+fn chars_and_bytes(ctx: &mut Context){
+
+    // a char is 4 bytes, and holds any unicode code point, but a String, or
+    // a &str, holds text as utf-8, where a char takes 1 to 4 bytes, so
+    // .len() counts bytes, like spaces.len() in the variables lesson, which
+    // is only the number of chars when they're all ascii.
+
+    // .chars().count() counts the chars, but that's not always what a
+    // reader sees either, an e with an accent can be an e, and a combining
+    // accent, 2 chars, and a waving hand with a skin tone is 2 chars too,
+    // what's seen as one is a grapheme cluster.
+
+    // "rust_commons datatypes unicode" takes apart any text we like.
+
+    for text in ["    ", "😻", "cafe\u{301}", "👋🏽🇯🇵"].iter() {
+        show_text(ctx, text);
+    }
+}
+
+// prints the text char by char, and how it splits into graphemes.
+fn show_text(ctx: &mut Context, text: &str) {
+    let report = unicode::explore(text);
+    outln!(ctx, "{:?}, .len() is {}, .chars().count() is {}, {} grapheme{}, {} utf-16 units", text, report.bytes,
+           report.chars.len(), report.graphemes.len(), if report.graphemes.len() == 1 { "" } else { "s" }, report.utf16);
+    if !report.chars.is_empty() {
+        outln!(ctx, "  {:>4}  {:<10}  {:<11}  {:<9}  {:<26}  char", "byte", "code point", "utf-8", "utf-16", "category");
+    }
+    for c in &report.chars {
+        let utf8: Vec<String> = c.utf8.iter().map(|byte| format!("{:02x}", byte)).collect();
+        let utf16: Vec<String> = c.utf16.iter().map(|unit| format!("{:04x}", unit)).collect();
+        outln!(ctx, "  {:>4}  {:<10}  {:<11}  {:<9}  {:<26}  {}", c.offset, c.code_point(), utf8.join(" "), utf16.join(" "),
+               format!("{} {}", c.category.code, c.category.name), c.shown());
+    }
+    if !report.graphemes.is_empty() {
+        let shown: Vec<String> = report.graphemes.iter().map(|grapheme| grapheme.escape_debug().to_string()).collect();
+        outln!(ctx, "  graphemes  |{}|", shown.join("|"));
+    }
+
+    ctx.event("text", vec![
+        ("text", Value::Str(text.to_string())),
+        ("len", Value::UInt(report.bytes as u128)),
+        ("chars", Value::UInt(report.chars.len() as u128)),
+        ("utf16", Value::UInt(report.utf16 as u128)),
+        ("graphemes", Value::Array(report.graphemes.iter().map(|grapheme| Value::Str(grapheme.clone())).collect())),
+        ("code_points", Value::Array(report.chars.iter().map(|c| Value::object(vec![
+            ("char", Value::Str(c.c.to_string())),
+            ("code_point", Value::Str(c.code_point())),
+            ("offset", Value::UInt(c.offset as u128)),
+            ("utf8", Value::Array(c.utf8.iter().map(|&byte| Value::UInt(byte as u128)).collect())),
+            ("utf16", Value::Array(c.utf16.iter().map(|&unit| Value::UInt(unit as u128)).collect())),
+            ("category", Value::Str(c.category.code.to_string())),
+        ])).collect())),
+    ]);
+}
+
+// asks for text, and takes each line apart, until we're done, the line is
+// taken as it is, spaces and all.
+fn explore_text(ctx: &mut Context) {
+    explore(ctx, "type some text, like caf\\u{e9}, e\\u{301}, 👋🏽 or 🇯🇵, or q to quit ..", |ctx, line| {
+        show_text(ctx, &unicode::unescape(line))
+    });
+}
+
+// COMPOUND TYPES
+
+fn compound_types(ctx: &mut Context){
+
+    // compound types can group multiple values into one type, rust has 2 compound
+    // types, tuples and arrays.
+
+    let tup: (i32, f64, u8) = (500, 25.55, 255);
+    // tuples are created by specifying a comma separated list of values inside
+    // parentheses, each position has a defined type, and types inside the tuple don't
+    // have to be of the same type, annotation is optional.
+
+    // elements inside a tuple can be pattern matched to destructure a tuple value.
+
+    let (xx, yy, zz) = tup;
+    outln!(ctx, "de-structed tuple scalars, {}, {}, {}",xx, yy, zz);
+    ctx.value("tup", &tup);
+
+    // in addition to destructing by pattern matching, tuple elements can also be
+    // extracted directly by using period (.), operator followed by the index of
+    // the value that we want to access.
+
+    outln!(ctx, "element extracted tuple scalars, {}, {}, {}",tup.0, tup.1, tup.2);
+}
+
+// the array type
+
+fn array_type(ctx: &mut Context){
+
+    // unlike tuples, array elements all share a common type, and have a fixed length.
+    // they are useful when we want to preallocate our data in stacks, for variable
+    // length arrays, rust provides vectors, which unlike arrays, can be grown or shrunk.
+
+    let arr_0 = [1, 2, 3, 4]; // i32 array of length 4, no annotation.
+    let arr_1: [i32; 4] = [1,2,3,4];  // same i32 array but with annotation.
+
+    outln!(ctx, "first elements of arrays arr_0 and arr_1: {}, {}", arr_0[0], arr_1[0]);
+    ctx.value("arr_0", &arr_0);
+    ctx.value("arr_1", &arr_1);
+    // care has to be taken to not overflow the index, or we get and
+    // index out of bounds error during runtime, and that is bad, very bad.
+
+}
+
+// memory layout
+
+fn memory_layout(ctx: &mut Context){
+
+    // every value takes up some bytes in memory, its size, and has to start
+    // at an address that's a multiple of its alignment, 4 for an i32, 8 for
+    // an f64. a tuple keeps all of its fields aligned, so there can be gaps
+    // between them, padding, and rust is free to reorder them, so there's
+    // as little of it as can be, tup doesn't keep the order it's written in.
+
+    // an array's elements are one after the other, without gaps, a String,
+    // or a &str, doesn't hold the text, only where it is, and how long.
+
+    // each byte of the diagram is marked with the field it belongs to, and
+    // a dot for padding, "rust_commons datatypes layout" draws any struct
+    // we type in, measured by rustc itself.
+
+    for ty in ["(i32, f64, u8)", "[i32; 4]", "String", "&str"].iter() {
+        show_layout(ctx, &layout::find(ty).expect("a known type"));
+    }
+
+    // an Option needs to say whether it's Some or None, which usually takes
+    // more bytes, unless there's a value the type it holds can never have,
+    // a niche, like a null reference, that None can be.
+
+    for niche in layout::niches() {
+        show_niche(ctx, &niche);
+    }
+}
+
+fn bytes(n: usize) -> String {
+    format!("{} byte{}", n, if n == 1 { "" } else { "s" })
+}
+
+// prints the fields, in the order they're in memory, the padding, and a
+// diagram of every byte.
+fn show_layout(ctx: &mut Context, layout: &Layout) {
+    outln!(ctx, "{}, {}, aligned to {}", layout.ty, bytes(layout.size), layout.align);
+    if let Some(insides) = layout.insides {
+        outln!(ctx, "  {}", insides);
+    }
+
+    // .0 for a tuple's fields, .x for a struct's, [0] for an array's.
+    let name = |name: &str| if name.starts_with('[') { name.to_string() } else { format!(".{}", name) };
+    let fields = layout.in_memory();
+    let name_width = fields.iter().map(|(_, field)| name(&field.name).len()).max().unwrap_or(0);
+    let ty_width = fields.iter().map(|(_, field)| field.ty.len()).max().unwrap_or(0);
+    let padding = layout.padding();
+    let mut lines: Vec<(usize, String)> = fields.iter().map(|&(label, field)| {
+        (field.offset, format!("{}  {:<n$}  {:<t$}  offset {:>2}, {}", label, name(&field.name), field.ty,
+                               field.offset, bytes(field.size), n = name_width, t = ty_width))
+    }).collect();
+    lines.extend(padding.iter().map(|&(offset, len)| {
+        (offset, format!(".  {:<w$}  offset {:>2}, {}", "padding", offset, bytes(len), w = name_width + ty_width + 2))
+    }));
+    lines.sort_by_key(|&(offset, _)| offset);
+    for (_, line) in lines {
+        outln!(ctx, "  {}", line);
+    }
+    if layout.size > 0 {
+        outln!(ctx, "  {}", layout.diagram());
+    }
+
+    ctx.event("layout", vec![
+        ("type", Value::Str(layout.ty.clone())),
+        ("size", Value::UInt(layout.size as u128)),
+        ("align", Value::UInt(layout.align as u128)),
+        ("fields", Value::Array(layout.fields.iter().map(|field| Value::object(vec![
+            ("name", Value::Str(field.name.clone())),
+            ("type", Value::Str(field.ty.clone())),
+            ("offset", Value::UInt(field.offset as u128)),
+            ("size", Value::UInt(field.size as u128)),
+            ("align", Value::UInt(field.align as u128)),
+        ])).collect())),
+        ("padding", Value::Array(padding.iter().map(|&(offset, len)| {
+            Value::Array(vec![Value::UInt(offset as u128), Value::UInt(len as u128)])
+        }).collect())),
+        ("insides", layout.insides.map_or(Value::Null, |insides| Value::Str(insides.to_string()))),
+    ]);
+}
+
+// prints how big an Option<T> is, next to T.
+fn show_niche(ctx: &mut Context, niche: &Niche) {
+    let option = format!("Option<{}>", niche.ty);
+    if niche.option_size == niche.size {
+        outln!(ctx, "{:<20} {}, the same as {}, {}", option, bytes(niche.option_size), niche.ty, niche.why);
+    } else {
+        outln!(ctx, "{:<20} {}, {} more than {}, {}", option, bytes(niche.option_size),
+               niche.option_size - niche.size, niche.ty, niche.why);
+    }
+    ctx.event("niche", vec![
+        ("type", Value::Str(niche.ty.to_string())),
+        ("size", Value::UInt(niche.size as u128)),
+        ("option_size", Value::UInt(niche.option_size as u128)),
+    ]);
+}
+
+// asks for types, or structs, and draws each one's layout, until we're done.
+fn explore_layouts(ctx: &mut Context) {
+    let known: Vec<String> = layout::known().into_iter().map(|layout| layout.ty).collect();
+    let prompt = format!("type one of {},\nor a struct, like struct Point {{ x: u8, y: u32, z: u16 }}, or q to quit ..",
+                         known.join(", "));
+    explore(ctx, &prompt, |ctx, line| {
+        let line = line.trim();
+        if let Some(layout) = layout::find(line) {
+            show_layout(ctx, &layout);
+            return;
+        }
+        let is_struct = line.starts_with("struct") || line.starts_with("pub ") || line.starts_with("#[");
+        if !is_struct {
+            invalid_input(ctx, line, format!("`{}` isn't one of the types here, but a struct can be anything", line));
+            return;
+        }
+        let definition = match layout::parse(line) {
+            Ok(definition) => definition,
+            Err(e) => return invalid_input(ctx, line, e),
+        };
+        match layout::measure(&definition) {
+            Ok(layout) => show_layout(ctx, &layout),
+            Err(layout::Error::Rejected(errors)) => {
+                let headlines: Vec<String> = errors.iter().map(|error| error.headline()).collect();
+                invalid_input(ctx, line, format!("rustc doesn't accept that, {}", headlines.join(", ")));
+            }
+            Err(layout::Error::Rustc(e)) => {
+                outln!(ctx, "couldn't ask rustc, is rust installed? ({})", e);
+                ctx.event("no_rustc", vec![("message", Value::Str(e))]);
+            }
+        }
+    });
+}
@@ -0,0 +1,305 @@
+// exercises
+
+// the lessons are full of code that doesn't compile, commented out, with a
+// note on the error it gives. with "rust_commons ownership exercise", that
+// code is handed over to us, to uncomment, and see the error for ourselves,
+// and then to fix, every check asks the local rustc, see rustc.rs.
+
+// an exercise starts out as a file, under $RUSTY_HOME/exercises/<lesson>,
+// to edit in any editor, or to paste new code into, right here. the file
+// is kept, so an unfinished exercise can be picked up later.
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use context::Context;
+use json::Value;
+use lessons::Lesson;
+use profile;
+use rustc::{self, Diagnostic};
+
+pub struct Exercise {
+    // short, and unique within the lesson.
+    pub name: &'static str,
+    pub title: &'static str,
+    // the code to start from, include_str!("exercises/<lesson>/<name>.rs").
+    pub code: &'static str,
+    // the error the exercise is about, an error code like "E0382", or part
+    // of the message, for the errors that don't have a code.
+    pub error: &'static str,
+    // a line the fixed code has to keep, so the error can't be fixed by
+    // deleting the line that causes it.
+    pub keep: Option<&'static str>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Outcome {
+    // fails with the error the exercise is about.
+    Reproduced(Diagnostic),
+    // compiles, with the line to keep still there.
+    Fixed,
+    // compiles, but without the line to keep, it's probably still commented out.
+    Missing(&'static str),
+    // fails, with other errors.
+    Broken(Vec<Diagnostic>),
+}
+
+impl Outcome {
+    pub fn name(&self) -> &'static str {
+        match *self {
+            Outcome::Reproduced(_) => "reproduced",
+            Outcome::Fixed => "fixed",
+            Outcome::Missing(_) => "missing",
+            Outcome::Broken(_) => "broken",
+        }
+    }
+}
+
+impl Exercise {
+    fn matches(&self, diagnostic: &Diagnostic) -> bool {
+        diagnostic.is_error()
+            && (diagnostic.code.as_deref() == Some(self.error) || diagnostic.message.contains(self.error))
+    }
+
+    // what the diagnostics rustc gave for the code mean for the exercise.
+    pub fn judge(&self, source: &str, diagnostics: Vec<Diagnostic>) -> Outcome {
+        if let Some(diagnostic) = diagnostics.iter().find(|d| self.matches(d)) {
+            return Outcome::Reproduced(diagnostic.clone());
+        }
+
+        let errors: Vec<Diagnostic> = diagnostics.into_iter().filter(Diagnostic::is_error).collect();
+        if !errors.is_empty() {
+            return Outcome::Broken(errors);
+        }
+
+        match self.keep {
+            Some(keep) if !source.lines().any(|line| line.trim() == keep) => Outcome::Missing(keep),
+            _ => Outcome::Fixed,
+        }
+    }
+
+    // checks the code with rustc, and judges it.
+    pub fn check(&self, source: &str) -> io::Result<Outcome> {
+        rustc::check(source).map(|diagnostics| self.judge(source, diagnostics))
+    }
+}
+
+fn path(home: &Path, lesson: &dyn Lesson, exercise: &Exercise) -> PathBuf {
+    home.join("exercises")
+        .join(lesson.id())
+        .join(format!("{}.rs", exercise.name))
+}
+
+pub fn run(ctx: &mut Context, lesson: &dyn Lesson) {
+    let exercise = match pick(ctx, lesson) {
+        Some(exercise) => exercise,
+        None => return,
+    };
+
+    // the file is only written if it isn't there yet, so whatever was done
+    // in an earlier run is kept.
+    let path = match ctx.home {
+        Some(ref home) => path(home, lesson, exercise),
+        None => return eprintln!("nowhere to keep the exercise, set RUSTY_HOME, or HOME"),
+    };
+    if !path.exists() {
+        let written = path
+            .parent()
+            .map_or(Ok(()), fs::create_dir_all)
+            .and_then(|_| fs::write(&path, exercise.code));
+        if let Err(e) = written {
+            return eprintln!("failed to write {}: {}", path.display(), e);
+        }
+    }
+
+    outln!(ctx, "exercise: {}\n", exercise.title);
+    outln!(ctx, "the code is in {}, edit it in any editor,", path.display());
+    outln!(ctx, "then come back here to check it.\n");
+    ctx.event("exercise", vec![
+        ("lesson", Value::Str(lesson.id().to_string())),
+        ("name", Value::Str(exercise.name.to_string())),
+        ("title", Value::Str(exercise.title.to_string())),
+        ("error", Value::Str(exercise.error.to_string())),
+        ("path", Value::Str(path.display().to_string())),
+    ]);
+
+    loop {
+        outln!(ctx, "-- enter: check, show: print the code, paste: type in new code, reset: start over, q: quit");
+
+        let mut choice = String::new();
+        let read = ctx.read_line(&mut choice)
+            .expect("failed to read line");
+        if read == 0 {
+            ctx.end_of_input();
+            return;
+        }
+
+        let done = match choice.trim() {
+            "" | "check" => check(ctx, lesson, exercise, &path),
+            "show" => show(ctx, &path),
+            "paste" => paste(ctx, &path),
+            "reset" => write(ctx, &path, exercise.code, "back to the code we started with."),
+            "q" => return,
+            other => {
+                outln!(ctx, "`{}` is not something we can do, try again!", other);
+                false
+            }
+        };
+        if done || ctx.aborted() {
+            return;
+        }
+    }
+}
+
+// the exercise to do, asked for if the lesson has more than one.
+fn pick(ctx: &mut Context, lesson: &dyn Lesson) -> Option<&'static Exercise> {
+    let exercises = lesson.exercises();
+    if exercises.len() == 1 {
+        return exercises.first();
+    }
+
+    for (i, exercise) in exercises.iter().enumerate() {
+        outln!(ctx, "{:>3}) {:<22} {}", i + 1, exercise.name, exercise.title);
+    }
+
+    loop {
+        outln!(ctx, "pick an exercise by number or name ..");
+
+        let mut choice = String::new();
+        let read = ctx.read_line(&mut choice)
+            .expect("failed to read line");
+        if read == 0 {
+            ctx.end_of_input();
+            return None;
+        }
+
+        let choice = choice.trim();
+        let exercise = match choice.parse::<usize>() {
+            Ok(n) if n >= 1 => exercises.get(n - 1),
+            Ok(_) => None,
+            Err(_) => exercises.iter().find(|exercise| exercise.name == choice),
+        };
+        match exercise {
+            Some(exercise) => return Some(exercise),
+            None => outln!(ctx, "there is no exercise `{}`, try again!", choice),
+        }
+    }
+}
+
+// checks the code, and says what that means, true once the exercise is done.
+fn check(ctx: &mut Context, lesson: &dyn Lesson, exercise: &Exercise, path: &Path) -> bool {
+    let source = match fs::read_to_string(path) {
+        Ok(source) => source,
+        Err(e) => {
+            outln!(ctx, "couldn't read {}: {}", path.display(), e);
+            return false;
+        }
+    };
+
+    let outcome = match exercise.check(&source) {
+        Ok(outcome) => outcome,
+        Err(e) => {
+            outln!(ctx, "couldn't ask rustc, is rust installed? ({})", e);
+            ctx.event("check", vec![
+                ("exercise", Value::Str(exercise.name.to_string())),
+                ("outcome", Value::Str("no_rustc".to_string())),
+                ("message", Value::Str(e.to_string())),
+            ]);
+            return true;
+        }
+    };
+
+    let errors = match outcome {
+        Outcome::Reproduced(ref diagnostic) => vec![diagnostic.clone()],
+        Outcome::Broken(ref errors) => errors.clone(),
+        _ => vec![],
+    };
+
+    match outcome {
+        Outcome::Reproduced(ref diagnostic) => {
+            outln!(ctx, "that's the one! rustc says, {}", at(diagnostic));
+            match exercise.keep {
+                Some(keep) => outln!(ctx, "now change the code so it compiles, with `{}` still in it.\n", keep),
+                None => outln!(ctx, "now change the code so it compiles.\n"),
+            }
+        }
+        Outcome::Fixed => outln!(ctx, "it compiles, well done, that's the exercise finished!"),
+        Outcome::Missing(keep) => {
+            outln!(ctx, "it compiles, but `{}` isn't in it,", keep);
+            outln!(ctx, "uncomment it, and check again, to see what goes wrong.\n");
+        }
+        Outcome::Broken(ref errors) => {
+            outln!(ctx, "it doesn't compile, but not because of the error this exercise is about,");
+            for error in errors {
+                outln!(ctx, "  {}", at(error));
+            }
+            outln!(ctx);
+        }
+    }
+
+    ctx.event("check", vec![
+        ("exercise", Value::Str(exercise.name.to_string())),
+        ("outcome", Value::Str(outcome.name().to_string())),
+        ("errors", Value::Array(errors.iter().map(|error| Value::object(vec![
+            ("code", error.code.clone().map_or(Value::Null, Value::Str)),
+            ("message", Value::Str(error.message.clone())),
+            ("line", error.line.map_or(Value::Null, |line| Value::UInt(line as u128))),
+            ("column", error.column.map_or(Value::Null, |column| Value::UInt(column as u128))),
+        ])).collect())),
+    ]);
+
+    profile::record(ctx, lesson, |progress| progress.try_exercise(exercise.name, outcome.name()));
+    outcome == Outcome::Fixed
+}
+
+// "error[E0382]: borrow of moved value: `s1`, on line 10"
+fn at(diagnostic: &Diagnostic) -> String {
+    match diagnostic.line {
+        Some(line) => format!("{}, on line {}", diagnostic.headline(), line),
+        None => diagnostic.headline(),
+    }
+}
+
+fn show(ctx: &mut Context, path: &Path) -> bool {
+    match fs::read_to_string(path) {
+        Ok(source) => {
+            for (i, line) in source.lines().enumerate() {
+                outln!(ctx, "{:>4} | {}", i + 1, line);
+            }
+            outln!(ctx);
+        }
+        Err(e) => outln!(ctx, "couldn't read {}: {}", path.display(), e),
+    }
+    false
+}
+
+// reads new code, up to a line with just a "." on it.
+fn paste(ctx: &mut Context, path: &Path) -> bool {
+    outln!(ctx, "type or paste in the new code, and end it with a line with just a . on it");
+
+    let mut source = String::new();
+    loop {
+        let mut line = String::new();
+        let read = ctx.read_line(&mut line)
+            .expect("failed to read line");
+        if read == 0 {
+            ctx.end_of_input();
+            return false;
+        }
+        if line.trim_end() == "." {
+            break;
+        }
+        source.push_str(line.trim_end_matches(['\n', '\r']));
+        source.push('\n');
+    }
+    write(ctx, path, &source, "got it, enter checks it.")
+}
+
+fn write(ctx: &mut Context, path: &Path, source: &str, done: &str) -> bool {
+    match fs::write(path, source) {
+        Ok(()) => outln!(ctx, "{}", done),
+        Err(e) => outln!(ctx, "couldn't write {}: {}", path.display(), e),
+    }
+    false
+}
@@ -0,0 +1,12 @@
+// an if on something that isn't a bool
+//
+// check this as it is, to see the error rust gives, then change the
+// condition, so it compiles, and prints "number is not zero".
+
+fn main() {
+    let number = 3;
+
+    if number {
+        println!("number is not zero");
+    }
+}
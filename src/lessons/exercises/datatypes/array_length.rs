@@ -0,0 +1,9 @@
+// an array that's too short for its type
+//
+// check this as it is, to see the error rust gives, then change the code
+// so it compiles.
+
+fn main() {
+    let arr: [i32; 4] = [1, 2, 3];
+    println!("first element: {}", arr[0]);
+}
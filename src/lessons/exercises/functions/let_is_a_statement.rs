@@ -0,0 +1,9 @@
+// a let statement where an expression should be
+//
+// check this as it is, to see the error rust gives, then change the code
+// so it compiles, and still prints 6.
+
+fn main() {
+    let x = (let y = 6);
+    println!("{}", x);
+}
@@ -0,0 +1,12 @@
+// a function that doesn't return what it says it does
+//
+// check this as it is, to see the error rust gives, then change the body of
+// plus_one, so it compiles, without changing its signature.
+
+fn plus_one(x: i32) -> i32 {
+    x + 1;
+}
+
+fn main() {
+    println!("{}", plus_one(5));
+}
@@ -0,0 +1,15 @@
+// using a String after passing it to a function
+//
+// uncomment the println! after the call, and check, to see the error rust
+// gives, then change the code so it compiles, with that println! still in it.
+
+fn takes_ownership(some_string: String) {
+    println!("{}", some_string);
+}
+
+fn main() {
+    let s = String::from("hello");
+    takes_ownership(s);
+
+    // println!("{}", s);
+}
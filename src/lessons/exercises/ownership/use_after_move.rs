@@ -0,0 +1,12 @@
+// using a String after it was moved
+//
+// uncomment the println! that prints s1, and check, to see the error rust
+// gives, then change the code so it compiles, with that println! still in it.
+
+fn main() {
+    let s1 = String::from("hello");
+    let s2 = s1;
+
+    // println!("{}, world!", s1);
+    println!("{}, world!", s2);
+}
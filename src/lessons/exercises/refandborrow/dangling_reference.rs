@@ -0,0 +1,15 @@
+// handing back a reference to something that's dropped
+//
+// check this as it is, to see the error rust gives, then change dangle so
+// it compiles, and main still prints what it hands back.
+
+fn dangle() -> &String {
+    let s = String::from("hello");
+
+    &s
+}
+
+fn main() {
+    let reference_to_nothing = dangle();
+    println!("{}", reference_to_nothing);
+}
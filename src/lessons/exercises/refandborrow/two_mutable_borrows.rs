@@ -0,0 +1,15 @@
+// two mutable references to the same String
+//
+// uncomment the lines that make r2, and print both, and check, to see the
+// error rust gives, then change the code so it compiles, with that println!
+// still in it.
+
+fn main() {
+    let mut s = String::from("hello");
+
+    let r1 = &mut s;
+    // let r2 = &mut s;
+
+    // println!("{}, {}", r1, r2);
+    println!("{}", r1);
+}
@@ -0,0 +1,12 @@
+// assigning to an immutable variable
+//
+// uncomment the line assigning 6 to x, and check, to see the error rust
+// gives, then change the code so it compiles, with that line still in it.
+
+fn main() {
+    let x = 5;
+    println!("the value of x is {}", x);
+
+    // x = 6;
+    println!("the value of x is {}", x);
+}
@@ -0,0 +1,184 @@
+// exporting the lessons
+
+// the lessons are meant to be run, but they read just as well as a book,
+// "rust_commons export markdown" prints every lesson, a chapter each, with
+// each section's notes, its code, and what the code prints, which is taken
+// from actually running it, so the book can't drift away from the lessons.
+// "rust_commons export html" prints the same book as a single html page,
+// with its styles inlined, so it can be put anywhere as is.
+
+use cli::Verbosity;
+use context::{Context, Options};
+use json::Value;
+use lessons::{step, Lesson};
+use output::Format;
+
+pub struct Page {
+    pub title: &'static str,
+    pub notes: Vec<String>,
+    pub code: String,
+    // what the section printed, when run with the lesson's example input.
+    pub output: String,
+}
+
+pub struct Chapter {
+    pub lesson: &'static dyn Lesson,
+    pub pages: Vec<Page>,
+}
+
+// runs every section of the lesson on its own, each with a fresh context,
+// and a fresh copy of the example input, and with nowhere to keep progress.
+// quietly, progress reports depend on how fast the machine is, and would
+// make the book different every time.
+pub fn chapter(lesson: &'static dyn Lesson) -> Chapter {
+    let options = Options {
+        verbosity: Verbosity::Quiet,
+        ..Options::default()
+    };
+    let pages = lesson
+        .sections()
+        .iter()
+        .map(|section| {
+            let (mut ctx, capture) = Context::capture(Format::Human, options.clone(), lesson.example_input());
+            if let Some(run) = section.run {
+                run(&mut ctx);
+            }
+            Page {
+                title: section.title,
+                notes: step::section_notes(lesson, section),
+                code: step::section_code(lesson, section),
+                output: capture.contents().trim_end().to_string(),
+            }
+        })
+        .collect();
+    Chapter { lesson, pages }
+}
+
+// "variables" for the chapter, "variables-2" for its second page.
+fn anchor(chapter: &Chapter, page: Option<usize>) -> String {
+    match page {
+        Some(i) => format!("{}-{}", chapter.lesson.id(), i + 1),
+        None => chapter.lesson.id().to_string(),
+    }
+}
+
+fn prerequisites(chapter: &Chapter) -> Option<String> {
+    let ids = chapter.lesson.prerequisites();
+    if ids.is_empty() {
+        None
+    } else {
+        Some(format!("take these first: {}", ids.join(", ")))
+    }
+}
+
+pub fn markdown(title: &str, chapters: &[Chapter]) -> String {
+    let mut book = format!("# {}\n\n## contents\n\n", title);
+    for (i, chapter) in chapters.iter().enumerate() {
+        book += &format!("{}. [{}](#{})\n", i + 1, chapter.lesson.title(), anchor(chapter, None));
+        for (j, page) in chapter.pages.iter().enumerate() {
+            book += &format!("   - [{}](#{})\n", page.title, anchor(chapter, Some(j)));
+        }
+    }
+
+    for (i, chapter) in chapters.iter().enumerate() {
+        book += &format!("\n<a id=\"{}\"></a>\n\n## {}. {}\n\n", anchor(chapter, None), i + 1, chapter.lesson.title());
+        book += &format!("{}\n", chapter.lesson.summary());
+        if let Some(prerequisites) = prerequisites(chapter) {
+            book += &format!("\n_{}_\n", prerequisites);
+        }
+
+        for (j, page) in chapter.pages.iter().enumerate() {
+            book += &format!("\n<a id=\"{}\"></a>\n\n### {}\n\n", anchor(chapter, Some(j)), page.title);
+            let mut blocks = page.notes.clone();
+            // a section that's only notes may have no code at all.
+            if !page.code.is_empty() {
+                blocks.push(format!("```rust\n{}\n```", page.code));
+            }
+            book += &format!("{}\n", blocks.join("\n\n"));
+            if !page.output.is_empty() {
+                book += &format!("\nprints:\n\n```text\n{}\n```\n", page.output);
+            }
+        }
+    }
+    book
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+const STYLE: &str = "body { max-width: 48em; margin: 2em auto; padding: 0 1em; font-family: sans-serif; line-height: 1.5; }
+pre { background: #f4f4f4; padding: 0.75em; overflow-x: auto; }
+pre.output { background: #222; color: #eee; }
+nav ol ul { margin: 0; }";
+
+pub fn html(title: &str, chapters: &[Chapter]) -> String {
+    let mut book = format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n<style>\n{}\n</style>\n</head>\n<body>\n<h1>{}</h1>\n",
+        escape(title),
+        STYLE,
+        escape(title)
+    );
+
+    book += "<nav>\n<h2>contents</h2>\n<ol>\n";
+    for chapter in chapters {
+        book += &format!("<li><a href=\"#{}\">{}</a>\n<ul>\n", anchor(chapter, None), escape(chapter.lesson.title()));
+        for (j, page) in chapter.pages.iter().enumerate() {
+            book += &format!("<li><a href=\"#{}\">{}</a></li>\n", anchor(chapter, Some(j)), escape(page.title));
+        }
+        book += "</ul>\n</li>\n";
+    }
+    book += "</ol>\n</nav>\n";
+
+    for (i, chapter) in chapters.iter().enumerate() {
+        book += &format!("<section id=\"{}\">\n<h2>{}. {}</h2>\n", anchor(chapter, None), i + 1, escape(chapter.lesson.title()));
+        book += &format!("<p>{}</p>\n", escape(chapter.lesson.summary()));
+        if let Some(prerequisites) = prerequisites(chapter) {
+            book += &format!("<p><em>{}</em></p>\n", escape(&prerequisites));
+        }
+
+        for (j, page) in chapter.pages.iter().enumerate() {
+            book += &format!("<h3 id=\"{}\">{}</h3>\n", anchor(chapter, Some(j)), escape(page.title));
+            for note in page.notes.iter() {
+                book += &format!("<p>{}</p>\n", escape(note));
+            }
+            if !page.code.is_empty() {
+                book += &format!("<pre><code>{}</code></pre>\n", escape(&page.code));
+            }
+            if !page.output.is_empty() {
+                book += &format!("<p>prints:</p>\n<pre class=\"output\">{}</pre>\n", escape(&page.output));
+            }
+        }
+        book += "</section>\n";
+    }
+    book += "</body>\n</html>";
+    book
+}
+
+// prints the book, as markdown or html, or with --format json, every page
+// as an event of its own.
+pub fn run(ctx: &mut Context, lessons: &[&'static dyn Lesson]) {
+    let chapters: Vec<Chapter> = lessons.iter().map(|lesson| chapter(*lesson)).collect();
+
+    for chapter in chapters.iter() {
+        for page in chapter.pages.iter() {
+            ctx.event("page", vec![
+                ("lesson", Value::Str(chapter.lesson.id().to_string())),
+                ("title", Value::Str(page.title.to_string())),
+                ("notes", Value::Array(page.notes.iter().map(|note| Value::Str(note.clone())).collect())),
+                ("code", Value::Str(page.code.clone())),
+                ("output", Value::Str(page.output.clone())),
+            ]);
+        }
+    }
+
+    let title = "rusty commons";
+    let book = match ctx.options.command.as_deref() {
+        Some("html") => html(title, &chapters),
+        _ => markdown(title, &chapters),
+    };
+    outln!(ctx, "{}", book);
+}
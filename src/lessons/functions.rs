@@ -0,0 +1,149 @@
+//functions
+
+use context::Context;
+use lessons::exercise::Exercise;
+use lessons::{Lesson, Section};
+
+pub struct Functions;
+
+impl Lesson for Functions {
+    fn id(&self) -> &'static str {
+        "functions"
+    }
+
+    fn title(&self) -> &'static str {
+        "functions"
+    }
+
+    fn summary(&self) -> &'static str {
+        "defining and calling functions, parameters, statements versus expressions, and return values."
+    }
+
+    fn prerequisites(&self) -> &'static [&'static str] {
+        &["variables"]
+    }
+
+    fn source(&self) -> &'static str {
+        include_str!("functions.rs")
+    }
+
+    fn sections(&self) -> &'static [Section] {
+        SECTIONS
+    }
+
+    fn quiz(&self) -> &'static str {
+        include_str!("quizzes/functions.quiz")
+    }
+
+    fn exercises(&self) -> &'static [Exercise] {
+        EXERCISES
+    }
+}
+
+// the one section runs the lesson's own main function, the real one lives
+// in the binary, and hands over to us, along with ctx, which every function
+// here uses to talk. main calls not_main, which calls do_sth, which calls the
+// rest, so they're all in it.
+static SECTIONS: &[Section] = &[
+    section!("calling functions", main, not_main, do_sth, show_and_express, return_sth, simple_return),
+];
+
+static EXERCISES: &[Exercise] = &[
+    Exercise {
+        name: "let_is_a_statement",
+        title: "a let statement where an expression should be",
+        code: include_str!("exercises/functions/let_is_a_statement.rs"),
+        error: "expected expression, found `let` statement",
+        keep: None,
+    },
+    Exercise {
+        name: "missing_return",
+        title: "a function that doesn't return what it says it does",
+        code: include_str!("exercises/functions/missing_return.rs"),
+        error: "E0308",
+        keep: Some("fn plus_one(x: i32) -> i32 {"),
+    },
+];
+
+fn main(ctx: &mut Context){
+
+    // in rust, main function is the entry-point of programs,
+    // and is defined using the "fn" keyword
+    outln!(ctx, "this is our main function!");
+
+    // for function names, rust uses snake case, i.e all letters
+    // are in lowercase and "_" is used to separate words.
+
+    // call! is the same call, written down, so running the lesson with
+    // --calls shows who called whom, with what, and what came back, ctx
+    // goes before the ";", as it's only passed along.
+    call!(not_main(ctx;));
+}
+
+// ctx is passed along to every function that wants to say something, so
+// they all print to the same place, in the same format.
+fn not_main(ctx: &mut Context){
+
+    // rust doesnt care where the function is defined,
+    // as long as they are defined and in scope.
+    outln!(ctx, "this is not our main function!");
+
+    call!(do_sth(ctx; 999));
+}
+
+fn do_sth(ctx: &mut Context, x: i64){
+
+    // in rust annotation for parameters are mandatory,
+    // and are defined inside the function definition itself.
+    outln!(ctx, "the value of x is: {}", x);
+    ctx.value("x", &x);
+    call!(show_and_express(ctx;));
+    let returned = call!(return_sth());
+    outln!(ctx, "the value returned is: {}", returned);
+    ctx.value("returned", &returned);
+}
+
+fn show_and_express(ctx: &mut Context){
+
+    // inside the function body, we can have statements and
+    // expressions, statements are instruction and expressions
+    // are evaluations.
+    let y = 6; // a statement
+    // function definitions are also statements
+
+    // let x = (let y = 6); => wont work, as statements cannot
+    // return values, as let y = 6; , doesnt return any value,
+    // and so, x won't have anything to bind to.
+
+    let x = {
+        let y = y * 3;
+        y + 32
+    };
+
+    // expression can be part of statements, calling a function,
+    // or calling a macro are both expressions, blocks are also
+    // expressions, also expression don't include semicolons, if we
+    // added a semicolon to y+32 in the block above we would have made
+    // it into a statement.
+
+    outln!(ctx, "the value of x from the expression is: {}", x);
+    ctx.value("x", &x);
+}
+
+#[allow(clippy::needless_return)]
+fn return_sth() -> i32 {
+
+    let x = call!(simple_return());
+    return x;
+    // functions can be made to return a specific value-type by using
+    // "->" keyword, in rust the return value from a function is by'
+    // default the last expression in the block of the function's body,
+    // a value can be returned earlier, through the use of return keyword.
+
+    // anything below the return keyword, if specified is rendered unreachable.
+}
+
+fn simple_return() -> i32 {
+    5
+    // perfectly valid
+}
\ No newline at end of file
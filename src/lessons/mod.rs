@@ -0,0 +1,231 @@
+// the rust_commons lessons
+
+// each lesson lives in its own module, and implements the Lesson trait, the
+// registry below lists them all, in the order they are meant to be taken,
+// so the launcher, tests, and anything else can find and run them.
+
+// lessons are run in-process, either by the rust_commons launcher, or by
+// the lesson's own rust_commons_<id> binary, which is nothing more than a
+// shortcut for "rust_commons <id>".
+
+// a lesson is made up of sections, each one a function in the lesson's
+// source, run one after the other, or with --step, one at a time, with the
+// comments in the function shown as the section's notes, see step.rs.
+
+// every lesson with a quiz, see quiz.rs, also understands the "quiz" command,
+// and every lesson with exercises, see exercise.rs, the "exercise" command.
+
+// all of it, notes, code and what the code prints, can be exported as a
+// book, to read without running anything, see export.rs.
+
+use calls;
+use cli::{self, Args};
+use context::{Context, Options, OPTIONS};
+use lessons::exercise::Exercise;
+use json::Value;
+use profile;
+use output::Format;
+
+// a section titled "VARIABLE SCOPE", run by the variable_scope function,
+// with its notes taken from variable_scope, and any helpers listed after it.
+// section!("CONSTANTS") is a section that's only notes, with nothing to run,
+// they're the comments under its "//CONSTANTS" heading, see step.rs.
+macro_rules! section {
+    ($title:expr) => {
+        ::lessons::Section {
+            title: $title,
+            run: None,
+            functions: &[],
+        }
+    };
+    ($title:expr, $run:ident $(, $helper:ident)*) => {
+        ::lessons::Section {
+            title: $title,
+            run: Some($run),
+            functions: &[stringify!($run) $(, stringify!($helper))*],
+        }
+    };
+}
+
+pub mod controlflow;
+pub mod datatypes;
+pub mod exercise;
+pub mod export;
+pub mod functions;
+pub mod ownership;
+pub mod quiz;
+pub mod refandborrow;
+pub mod step;
+pub mod variables;
+
+pub struct Section {
+    pub title: &'static str,
+    // None for a section that's only notes.
+    pub run: Option<fn(&mut Context)>,
+    // the functions in the lesson's source the notes are read from.
+    pub functions: &'static [&'static str],
+}
+
+pub trait Lesson: Sync {
+    // short, lowercase, and unique, used on the command line.
+    fn id(&self) -> &'static str;
+
+    fn title(&self) -> &'static str;
+
+    // a sentence or two on what the lesson covers.
+    fn summary(&self) -> &'static str;
+
+    // the ids of the lessons that should be taken before this one.
+    fn prerequisites(&self) -> &'static [&'static str] {
+        &[]
+    }
+
+    // commands the lesson understands besides running, like "bench".
+    fn commands(&self) -> &'static [&'static str] {
+        &[]
+    }
+
+    // the lesson's source code, include_str!("<id>.rs"), for the notes.
+    fn source(&self) -> &'static str;
+
+    fn sections(&self) -> &'static [Section];
+
+    // the lesson's quiz, include_str!("quizzes/<id>.quiz"), if it has one.
+    fn quiz(&self) -> &'static str {
+        ""
+    }
+
+    fn exercises(&self) -> &'static [Exercise] {
+        &[]
+    }
+
+    // what to type in when the lesson runs with nobody at the keyboard, like
+    // when it's exported, see export.rs, for the lessons that ask for input.
+    fn example_input(&self) -> &'static str {
+        ""
+    }
+
+    fn run(&self, ctx: &mut Context) {
+        run_sections(ctx, self.sections())
+    }
+}
+
+// runs every section, in order, stopping early if the input ran out.
+pub fn run_sections(ctx: &mut Context, sections: &[Section]) {
+    for section in sections {
+        // a section is a call like any other, the first one the lesson makes.
+        if let Some(run) = section.run {
+            calls::call(section.functions[0], || run(ctx));
+        }
+        if ctx.aborted() {
+            return;
+        }
+    }
+}
+
+pub static LESSONS: &[&dyn Lesson] = &[
+    &variables::Variables,
+    &datatypes::DataTypes,
+    &functions::Functions,
+    &controlflow::ControlFlow,
+    &ownership::Ownership,
+    &refandborrow::RefAndBorrow,
+];
+
+pub fn find(id: &str) -> Option<&'static dyn Lesson> {
+    LESSONS.iter().find(|lesson| lesson.id() == id).cloned()
+}
+
+// the lesson's own commands, and the ones every lesson has, like "quiz".
+pub fn commands(lesson: &dyn Lesson) -> Vec<&'static str> {
+    let mut commands = lesson.commands().to_vec();
+    if !lesson.quiz().is_empty() {
+        commands.push("quiz");
+    }
+    if !lesson.exercises().is_empty() {
+        commands.push("exercise");
+    }
+    commands
+}
+
+// runs the lesson, or the command asked for, a section at a time with --step.
+pub fn present(ctx: &mut Context, lesson: &dyn Lesson) {
+    let command = ctx.options.command.clone();
+    match command.as_deref() {
+        Some("quiz") => quiz::run(ctx, lesson),
+        Some("exercise") => exercise::run(ctx, lesson),
+        // the lesson's own commands, like "controlflow bench", are never
+        // stepped through, they aren't sections.
+        None if ctx.options.step => step::step(ctx, lesson),
+        None => {
+            run(ctx, lesson);
+            if !ctx.aborted() {
+                profile::record(ctx, lesson, |progress| {
+                    progress.finished = true;
+                    for section in lesson.sections() {
+                        progress.see_section(section.title);
+                    }
+                });
+            }
+        }
+        Some(_) => run(ctx, lesson),
+    }
+}
+
+// runs the lesson, and with --calls, shows every call it made, at the end.
+fn run(ctx: &mut Context, lesson: &dyn Lesson) {
+    if !ctx.options.calls {
+        lesson.run(ctx);
+        return;
+    }
+
+    let ((), made) = calls::record(|| lesson.run(ctx));
+    outln!(ctx, "\nthe calls {} made:", lesson.id());
+    if made.is_empty() {
+        outln!(ctx, "  none, it doesn't use call!");
+    }
+    for line in calls::tree(&made) {
+        outln!(ctx, "  {}", line);
+    }
+    ctx.event("calls", vec![("calls", Value::Array(made.iter().map(call_value).collect()))]);
+}
+
+fn call_value(call: &calls::Call) -> Value {
+    Value::object(vec![
+        ("name", Value::Str(call.name.to_string())),
+        ("args", Value::Array(call.args.iter().map(|&(written, ref value)| Value::object(vec![
+            ("written", Value::Str(written.to_string())),
+            ("value", Value::Str(value.clone())),
+        ])).collect())),
+        ("returned", call.returned.clone().map_or(Value::Null, Value::Str)),
+        ("ns", Value::UInt(call.time.as_nanos())),
+        ("calls", Value::Array(call.calls.iter().map(call_value).collect())),
+    ])
+}
+
+pub fn usage(lesson: &dyn Lesson, bin: &str) -> String {
+    let commands = commands(lesson);
+    let commands = if commands.is_empty() {
+        String::new()
+    } else {
+        format!("[{}] ", commands.join(" | "))
+    };
+    format!("usage: {} {}[options]\n\n{}", bin, commands, OPTIONS)
+}
+
+// the main function of the rust_commons_<id> binaries.
+pub fn main(id: &str) {
+    let lesson = find(id).expect("no such lesson");
+    let bin = format!("rust_commons_{}", id);
+    let usage = usage(lesson, &bin);
+
+    let mut args = Args::from_env();
+    let format: Format = args.format().unwrap_or_else(|e| cli::exit_with_usage(&e, &usage));
+    let options = Options::from_args(&mut args, &commands(lesson))
+        .unwrap_or_else(|e| cli::exit_with_usage(&e, &usage));
+    args.finish().unwrap_or_else(|e| cli::exit_with_usage(&e, &usage));
+
+    let mut ctx = Context::stdio(&bin, format, options);
+    present(&mut ctx, lesson);
+    ctx.finish();
+}
@@ -0,0 +1,505 @@
+//ownership
+
+use std::time::Duration;
+
+use context::Context;
+use costs::{self, Measurement};
+use heap;
+use json::Value;
+use strings::{self, Look};
+use tracer::{Journal, Kind, Traced};
+use lessons::exercise::Exercise;
+use lessons::{self, Lesson, Section};
+
+pub struct Ownership;
+
+impl Lesson for Ownership {
+    fn id(&self) -> &'static str {
+        "ownership"
+    }
+
+    fn title(&self) -> &'static str {
+        "ownership"
+    }
+
+    fn summary(&self) -> &'static str {
+        "the stack and the heap, the ownership rules, scopes and drop, moves versus clones, and how ownership passes in and out of functions."
+    }
+
+    fn prerequisites(&self) -> &'static [&'static str] {
+        &["datatypes", "functions"]
+    }
+
+    fn commands(&self) -> &'static [&'static str] {
+        &["bench"]
+    }
+
+    fn source(&self) -> &'static str {
+        include_str!("ownership.rs")
+    }
+
+    fn sections(&self) -> &'static [Section] {
+        SECTIONS
+    }
+
+    fn quiz(&self) -> &'static str {
+        include_str!("quizzes/ownership.quiz")
+    }
+
+    fn exercises(&self) -> &'static [Exercise] {
+        EXERCISES
+    }
+
+    // the "bench" command measures what a move, a clone, and a borrow cost,
+    // instead of running the lesson.
+    fn run(&self, ctx: &mut Context) {
+        if ctx.options.command.as_deref() == Some("bench") {
+            let max = ctx.options.max.unwrap_or(1_000_000);
+            bench(ctx, max.max(0) as usize);
+            return;
+        }
+        lessons::run_sections(ctx, SECTIONS);
+    }
+}
+
+static SECTIONS: &[Section] = &[
+    section!("why ownership"),
+    section!("OWNERSHIP RULES"),
+    section!("VARIABLE SCOPE", variable_scope),
+    section!("the String type", string_type),
+    section!("moving complex types", moving),
+    section!("deep copying with clone", cloning),
+    section!("copying stack only data", copying),
+    section!("ownership and functions", ownership_and_functions,
+             takes_ownership, makes_copy, gives_ownership, takes_and_gives_back),
+];
+
+static EXERCISES: &[Exercise] = &[
+    Exercise {
+        name: "use_after_move",
+        title: "using a String after it was moved",
+        code: include_str!("exercises/ownership/use_after_move.rs"),
+        error: "E0382",
+        keep: Some("println!(\"{}, world!\", s1);"),
+    },
+    Exercise {
+        name: "moved_into_function",
+        title: "using a String after passing it to a function",
+        code: include_str!("exercises/ownership/moved_into_function.rs"),
+        error: "E0382",
+        keep: Some("println!(\"{}\", s);"),
+    },
+];
+
+// why ownership
+
+// ownership allows rust to make memory safety guarantees without
+// needing a garbage collector.
+
+// all programs have to manage the way the computer's memory
+// is used while running, some use garbage collectors that
+// are constantly on the lookout for memory references that
+// are no longer being used, while in other languages, the
+// memory allocation has to be defined explicitly by the
+// programmer. rust does things a bit differently, the memory in
+// rust is managed through a system of ownership and rules that
+// allow the compiler to do checks at compile time.
+
+// for a systems programming language like rust, managing memory
+// is crucial, and strongly affects the programs behavior.
+
+// stacks use lifo scheme, that allows for fast additions and
+// deletions, as all deletions and additions always take place at
+// the top, the downside is that all that data must take up a known
+// and fixed space.
+
+// for data with variable or unknown size, a heap is used, a heap
+// is less organized, as when a new block of data is put in a heap,
+// the os has to find an empty spot within the heap that is big enough
+// and mark it as being in used, returning a pointer which points to
+// the address of that location, this process is know as allocation.
+
+// stacks don't have to allocate data, as all data in the stack is
+// of a known and fixed size, and a pointer can simply be stored in
+// the stack and data can be retrieved by following the pointer.
+
+// so, accessing the data on a heap is slower, as the processor has
+// to constantly look for pointers and then follow them, if the data
+// is spread out too much, it slows it even further, plus allocating
+// large amounts of data in the heap also takes time on it's own.
+
+// rust's ownership system tries to handles all this,
+// i.e. keeping track of what parts of code are using what data
+// on the heap, thereby minimizing duplicate data on the heap,
+// and cleaning up unused data on the heap to avoid running out of space.
+
+// OWNERSHIP RULES
+
+// Each value in rust has a variable that's called it's owner.
+// There can only be one owner at a time.
+// When the owner goes out of scope, the value is dropped.
+
+// VARIABLE SCOPE
+
+fn variable_scope(ctx: &mut Context) {
+
+    // the journal writes down when each value is created, moved, and dropped, and how
+    // deep in scopes that happens, the lines starting with | are what it wrote down.
+    let journal = Journal::new();
+
+    { // s is not a valid entity here, as it's still not defined
+        let _scope = journal.scope("inner scope");
+        let s = journal.make("s", "hello"); // now s is valid
+        tell(ctx, &journal);
+
+        // we can do stuff with s here onwards
+        outln!(ctx, "string s in scope is => {} \nExiting Scope!",s);
+    } // drop scope, s is invalid again.
+    // rust calls the "drop" function to return the block back to the os.
+    tell(ctx, &journal);
+    outln!(ctx, "\t scope dropped, string s is no more!\n");
+}
+
+fn string_type(ctx: &mut Context) {
+
+    let journal = Journal::new();
+    {
+        let _scope = journal.scope("inner scope");
+        outln!(ctx, "reallocating string s, from none ");
+        let mut s = on_the_heap(ctx, "String::from(\"hello\")", || journal.make("s", String::from("hello")));
+        tell(ctx, &journal);
+        draw(ctx, &[strings::look("s", &s)]);
+        // String::from allows us to use the namespace from String.
+        // and request the os for memory to store it in the heap.
+        // we can mutate this string now, like so, using the allocated
+        // memory in the heap, it's 5 bytes long, and too small for
+        // ", world!", so push_str asks for a bigger block, that's the realloc.
+        on_the_heap(ctx, "s.push_str(\", world!\")", || s.push_str(", world!"));
+        draw(ctx, &[strings::look("s", &s)]);
+        // the len went up to 13, and so did the capacity, and the pointer may well have
+        // changed too, as the bytes had to go somewhere bigger.
+        outln!(ctx, "string s is: {}",s);
+        ctx.value("s", &*s);
+    }
+    tell(ctx, &journal);
+    outln!(ctx);
+    // on exiting the scope we return all this memory back to the os.
+    // if we forget to do this, we waste memory, if we do this too early
+    // we have an invalid variable, even doing this twice can cause bugs.
+    // for proper allocation, we must exactly pair one allocate with one free.
+    // in rust, this is done automatically on exiting the scope, through
+    // the built-in drop function, that's triggered at the closing "}".
+}
+
+fn moving(ctx: &mut Context) {
+
+    let journal = Journal::new();
+    {
+        let _scope = journal.scope("inner scope");
+        let x = 5;
+        let y = x;
+        // both x and y are allocated on the stack, as they are of fixed size
+        // that is known at compile time.
+        out!(ctx, "Fixed Type, Original and Copied variables are both present");
+        outln!(ctx, ", and exist in stack: x={}, y={}",x,y);
+
+        let s1 = journal.make("s1", String::from("hello"));
+        tell(ctx, &journal);
+        let before = strings::look("s1", &s1);
+        draw(ctx, std::slice::from_ref(&before));
+        let s2 = on_the_heap(ctx, "let s2 = s1", move || s1.hand_to("s2"));
+        tell(ctx, &journal);
+        draw(ctx, &[strings::moved(&before, "s2"), strings::look("s2", &s2)]);
+        // although this looks similar to the above code, for a complex type
+        // like String, the stack stores the pointer, the length and the capacity
+        // the actual data inside the string is stored in the heap as an indexed list.
+        // the length denotes the memory content in bytes that the string is currently holding,
+        // the capacity is the total amount of memory allocated to it by the os. when the copy s2
+        // is made, we only copy the data that is on the stack, while the data on the heap remains
+        // as is. that's what the drawings above show, s2 sits somewhere else on the stack, but its
+        // pointer, len and capacity are the ones s1 had, pointing at the very same bytes.
+
+        // as both s1 and s2 are going to go out of scope at the same time, they will both try to
+        // free the same memory, which can lead to memory corruption and security vulnerabilities.
+        // so what rust does is it invalidates s1 when s2 is created, so there's no need to free
+        // anything when s1 goes out of scope.
+
+        // so instead of making copies, rust moves the stack and heap memory of s1 into s2.
+        // the behavior can be checked by trying to print s1.
+
+        // println!("{}, world!", s1);
+        // error message: note: move occurs because `s1` has type `std::string::String`,
+        // ...which does not implement the `Copy` trait
+        out!(ctx, "Complex Type, Original var s1 = Invalidated after copy!");
+        outln!(ctx, " and transferred over to s2={}",s2); // this will work.
+        ctx.value("s2", &*s2);
+
+        // this also means that rust will never automatically create deep copies of our data
+        // ensuring that any automatic copying remains inexpensive in runtime, the move
+        // above did nothing at all on the heap.
+    }
+    // only s2 is dropped at the end of the scope, s1 no longer owns anything to drop.
+    tell(ctx, &journal);
+    outln!(ctx);
+}
+
+fn cloning(ctx: &mut Context) {
+
+    let journal = Journal::new();
+    {
+        let _scope = journal.scope("inner scope");
+        // on the other hand, if we really want to deeply copy the heap data of the String,
+        // we can use the clone method, like so. keeping in mind that it may be expensive on runtime.
+
+        let s1 = journal.make("s1", String::from("hello"));
+        tell(ctx, &journal);
+        out!(ctx, "s1 created, value is: {}\n",s1);
+        let s2 = on_the_heap(ctx, "s1.clone()", || s1.clone_as("s2"));
+        tell(ctx, &journal);
+        // the clone is a new block, of the same 5 bytes, with "hello" copied into it.
+        draw(ctx, &[strings::look("s1", &s1), strings::look("s2", &s2)]);
+
+        outln!(ctx, "Deep Copying, Complex Type s1 into s2");
+        outln!(ctx, "s1 = {}, s2 = {} \nDone, Exiting Scope!", s1, s2); // this will work
+        ctx.value("s1", &*s1);
+        ctx.value("s2", &*s2);
+    }
+    // both of them own a String of their own, so both are dropped, in the reverse order
+    // they were made in.
+    tell(ctx, &journal);
+    outln!(ctx);
+}
+
+// copying stack only data
+
+fn copying(ctx: &mut Context) {
+
+    {
+        // for type with fixed and know size, the data is automatically copied on assignment
+
+        let x  = 5;
+        let y = x;
+        outln!(ctx, "For Fixed Types with copy trait and no Drop! trait");
+        outln!(ctx, "E.g: var x copied to y, x={}, y={}",x,y);
+
+        // for such type, rust utilizes a special trait called copy, it is not available on
+        // types that implement the Drop trait in any form, or in any part, or if the type
+        // need something special to happen when the values go out of scope.
+
+        // copy trait is available for all integer, floating point, boolean, character and
+        // tuple types strictly containing simple types. a tuple with any String wont have
+        // the copy trait.
+    }
+}
+
+// ownership and functions
+
+fn ownership_and_functions(ctx: &mut Context) {
+
+    // as the semantics for passing a value to a function are similar to those for
+    // assigning a value to a variable, passing a variable to a function will either
+    // move or copy, just like assigning does.
+
+    outln!(ctx, "Demonstrating Ownerships\n");
+
+    // every call is a scope of its own in the journal, and what's passed in, and given back,
+    // is handed over with hand_to, so the moves in and out of the functions show up too.
+    let journal = Journal::new();
+    {
+        let _scope = journal.scope("ownership_and_functions");
+
+        let s1 = journal.call("gives_ownership", || gives_ownership(&journal));
+        tell(ctx, &journal);
+        outln!(ctx, "This String was created by an external function,\nand passed over to main:\t{}\n",s1);
+        ctx.value("s1", &*s1);
+
+        let s2 = journal.make("s2", String::from("Hello, from the Inside!")); // s comes in scope
+        // s moves to function, so is no longer valid here
+        journal.call("takes_ownership", || takes_ownership(ctx, s2.hand_to("some_string")));
+        tell(ctx, &journal);
+        outln!(ctx);
+
+        let s3 = journal.make("s3", String::from("Hello, from the Inside!"));
+        let before = strings::look("s3", &s3);
+        let (s3, len) = on_the_heap(ctx, "takes_and_gives_back(s3)", || {
+            journal.call("takes_and_gives_back", move || takes_and_gives_back(s3.hand_to("a_string")))
+        });
+        // takes_and_gives_back clones twice, and grows one of the clones with push_str, and the
+        // String passed in, and the first clone, are both dropped before it returns, so the s3 we
+        // get back is a whole new String, with a buffer of its own.
+        tell(ctx, &journal);
+        draw(ctx, &[strings::moved(&before, "takes_and_gives_back"), strings::look("s3, again", &s3)]);
+        outln!(ctx, "This string belonged to main, was passed over to an external function shadowed");
+        outln!(ctx, "and returned back with an additional variable\t\t{} Length={}\n",s3, len);
+        ctx.value("s3", &*s3);
+        ctx.value("len", &len);
+
+        let x:i128 = 54665134841589113413484198; // x comes into scope
+        makes_copy(ctx, x); // x moves into function, but as i128 has copy trait, it will
+    }
+    // s1 and s3 are all that's left to drop, s2 was dropped at the end of takes_ownership,
+    // and the s3 passed to takes_and_gives_back at the end of that.
+    tell(ctx, &journal);
+}
+
+fn takes_ownership(ctx: &mut Context, some_string: Traced<String>) {
+    tell(ctx, some_string.journal());
+    outln!(ctx, "this string was passed over from main\nwhere it no longer belongs\t{}", some_string);
+    ctx.value("some_string", &*some_string);
+    // some_string will go out of scope and drop will be called, freeing memory
+}
+
+fn makes_copy(ctx: &mut Context, some_int: i128) {
+
+    outln!(ctx, "This Fixed Type Integer wad passed to an external function:\t{}", some_int);
+    ctx.value("some_int", &some_int);
+    // some_int will go out of scope
+}
+
+fn gives_ownership(journal: &Journal) -> Traced<String> {
+
+    let some_string = journal.make("some_string", String::from("Hello, from the outside!"));
+
+    some_string.hand_to("s1")         //some string is returned and moves over to the calling function.
+
+}
+
+fn takes_and_gives_back(a_string: Traced<String>) -> (Traced<String>,usize) {
+
+    let mut tempstr:Traced<String> = a_string.clone_as("tempstr");
+    tempstr.push_str(", and shadowed from the outside!");
+    let a_string = tempstr.clone_as("a_string");
+    let length = a_string.len();
+
+    (a_string.hand_to("s3"),length)
+
+    //a_string and length are returned and moved over to the calling function, tempstr is
+    //dropped, and then the a_string that was passed in, which the new one only shadowed.
+
+    //neither clone is needed, a_string is ours, so it could be changed, and handed back, as
+    //it is, "rust_commons ownership bench" measures what the two clones cost, next to that.
+
+}
+
+// prints what the journal wrote down since the last time, see tracer.rs.
+fn tell(ctx: &mut Context, journal: &Journal) {
+    for entry in journal.take() {
+        outln!(ctx, "  | {}", entry);
+        let mut fields = vec![
+            ("depth", Value::UInt(entry.depth as u128)),
+            ("kind", Value::Str(entry.kind.name().to_string())),
+            ("name", Value::Str(entry.name.to_string())),
+        ];
+        if let Kind::Moved(into) | Kind::Cloned(into) = entry.kind {
+            fields.push(("into", Value::Str(into.to_string())));
+        }
+        ctx.event("trace", fields);
+    }
+}
+
+// draws the Strings, their pointer, len and capacity on the stack, and the
+// bytes they point at on the heap, see strings.rs.
+fn draw(ctx: &mut Context, looks: &[Look]) {
+    for line in strings::draw(looks) {
+        outln!(ctx, "{}", line);
+    }
+    let looks = looks.iter().map(|look| match *look {
+        Look::Live { ref name, stack, ptr, len, capacity, ref bytes } => Value::object(vec![
+            ("name", Value::Str(name.clone())),
+            ("stack", Value::Str(strings::address(stack))),
+            ("ptr", Value::Str(strings::address(ptr))),
+            ("len", Value::UInt(len as u128)),
+            ("capacity", Value::UInt(capacity as u128)),
+            ("text", Value::Str(String::from_utf8_lossy(bytes).into_owned())),
+        ]),
+        Look::Moved { ref name, stack, ref into } => Value::object(vec![
+            ("name", Value::Str(name.clone())),
+            ("stack", Value::Str(strings::address(stack))),
+            ("moved_into", Value::Str(into.clone())),
+        ]),
+    });
+    ctx.event("strings", vec![("strings", Value::Array(looks.collect()))]);
+}
+
+// runs f, and tells what it did on the heap, with the counting allocator,
+// see heap.rs, installed, and when it isn't, just runs f.
+#[track_caller]
+fn on_the_heap<T, F: FnOnce() -> T>(ctx: &mut Context, what: &str, f: F) -> T {
+    if !heap::installed() {
+        return f();
+    }
+    let (value, trace) = heap::trace(f);
+
+    let site = format!("{}:{}", trace.site.file(), trace.site.line());
+    outln!(ctx, "  on the heap, {} at {}: {}", what, site, trace.summary());
+    for event in &trace.events {
+        match *event {
+            heap::Event::Realloc(old, new) => outln!(ctx, "    realloc {} -> {} bytes", old, new),
+            _ => outln!(ctx, "    {} {} bytes", event.kind(), event.size()),
+        }
+    }
+    ctx.event("heap", vec![
+        ("what", Value::Str(what.to_string())),
+        ("site", Value::Str(site)),
+        ("allocs", Value::UInt(trace.allocs() as u128)),
+        ("reallocs", Value::UInt(trace.reallocs() as u128)),
+        ("deallocs", Value::UInt(trace.deallocs() as u128)),
+        ("events", Value::Array(trace.events.iter().map(|event| {
+            let mut fields = vec![("kind", Value::Str(event.kind().to_string()))];
+            if let heap::Event::Realloc(old, _) = *event {
+                fields.push(("from", Value::UInt(old as u128)));
+            }
+            fields.push(("size", Value::UInt(event.size() as u128)));
+            Value::object(fields)
+        }).collect())),
+    ]);
+    value
+}
+
+// moves, clones and borrows Strings and Vecs, of sizes growing up to max, and
+// takes_and_gives_back, as it's written, and without the clones, and prints
+// how long each call took, and what it asked of the allocator, see costs.rs.
+fn bench(ctx: &mut Context, max: usize) {
+    outln!(ctx, "benchmarking moves, clones and borrows, of sizes up to {}", max.max(10));
+    if !heap::installed() {
+        outln!(ctx, "the counting allocator isn't installed, so allocations aren't counted");
+    }
+    outln!(ctx, "{:>20} {:>10} {:>10} {:>14} {:>7} {:>12}", "payload", "size", "how", "time", "allocs", "bytes");
+
+    for size in costs::sizes(max) {
+        for &strategy in costs::STRATEGIES.iter() {
+            show_measurement(ctx, &costs::strategy::<String>(strategy, size));
+        }
+        for &strategy in costs::STRATEGIES.iter() {
+            show_measurement(ctx, &costs::strategy::<Vec<u64>>(strategy, size));
+        }
+    }
+
+    // takes_and_gives_back never needed its clones, it owns a_string, so it can push_str
+    // onto it, and hand it back, which costs at most a realloc, if it has to grow.
+    outln!(ctx, "\ntakes_and_gives_back, as it's written, without the clones, and borrowing instead");
+    for size in costs::sizes(max) {
+        for &variant in costs::VARIANTS.iter() {
+            show_measurement(ctx, &costs::variant(variant, size));
+        }
+    }
+}
+
+fn show_measurement(ctx: &mut Context, m: &Measurement) {
+    let count = |n: Option<usize>| n.map_or("-".to_string(), |n| n.to_string());
+    outln!(ctx, "{:>20} {:>10} {:>10} {:>14} {:>7} {:>12}", m.payload, m.size, m.what,
+           nanos(m.time), count(m.allocs), count(m.bytes));
+    let count = |n: Option<usize>| n.map_or(Value::Null, |n| Value::UInt(n as u128));
+    ctx.event("bench", vec![
+        ("payload", Value::Str(m.payload.to_string())),
+        ("size", Value::UInt(m.size as u128)),
+        ("how", Value::Str(m.what.to_string())),
+        ("ns", Value::Float(m.time.as_secs_f64() * 1e9)),
+        ("allocs", count(m.allocs)),
+        ("bytes", count(m.bytes)),
+    ]);
+}
+
+fn nanos(d: Duration) -> String {
+    format!("{:.1}ns", d.as_secs_f64() * 1e9)
+}
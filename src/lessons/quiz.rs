@@ -0,0 +1,375 @@
+// quizzes
+
+// every lesson can come with a quiz, to check that what it covers has sunk
+// in, "rust_commons ownership quiz" asks the ownership questions, in a
+// random order, explains the answer whenever we get one wrong, and keeps
+// the score, under $RUSTY_HOME/quiz/<user>.jsonl, one line per quiz taken.
+
+// the questions live in the lesson's quiz file, quizzes/<id>.quiz, each
+// question starts with its kind in brackets, followed by the question
+// itself, and then lines marked by their first character,
+//
+//   [choice] which keyword makes a variable mutable?
+//   - let                  a wrong choice
+//   * mut                  the right one, there's exactly one of these
+//   > the explanation, shown on a wrong answer, and it may go on
+//   > for as many lines as it needs to.
+//
+//   [output] what does this print?
+//   | let x = 5;           the code the question is about
+//   | let x = x + 1;
+//   | println!("{}", x);
+//   = 6                    what it prints
+//   > ...
+//
+//   [compiles] does this compile?
+//   | ...
+//   = no                   yes, or no
+//   > ...
+//
+// lines starting with # are comments, and blank lines are ignored.
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use rand::{self, Rng, SeedableRng, StdRng};
+
+use context::Context;
+use json::Value;
+use lessons::Lesson;
+use profile;
+use store;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kind {
+    Choice,
+    Output,
+    Compiles,
+}
+
+impl Kind {
+    fn name(self) -> &'static str {
+        match self {
+            Kind::Choice => "choice",
+            Kind::Output => "output",
+            Kind::Compiles => "compiles",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Question {
+    // where the question is in its quiz file, 1 for the first one.
+    pub number: usize,
+    pub kind: Kind,
+    pub prompt: String,
+    pub code: Vec<String>,
+    // for multiple choice questions, each choice, and whether it's the right one.
+    pub choices: Vec<(String, bool)>,
+    // for the other kinds, the expected answer.
+    pub answer: String,
+    pub explanation: Vec<String>,
+}
+
+impl Question {
+    fn new(number: usize, kind: Kind, prompt: &str) -> Question {
+        Question {
+            number,
+            kind,
+            prompt: prompt.to_string(),
+            code: Vec::new(),
+            choices: Vec::new(),
+            answer: String::new(),
+            explanation: Vec::new(),
+        }
+    }
+
+    // whether the answer is right, or None if it isn't an answer to this
+    // question at all, like "maybe" to a yes or no question.
+    pub fn check(&self, answer: &str) -> Option<bool> {
+        let answer = answer.trim();
+        match self.kind {
+            Kind::Choice => {
+                let answer = answer.to_lowercase();
+                let i = (0..self.choices.len()).find(|&i| letter(i) == answer)?;
+                Some(self.choices[i].1)
+            }
+            Kind::Output => Some(words(answer) == words(&self.answer)),
+            Kind::Compiles => match answer.to_lowercase().as_str() {
+                "y" | "yes" => Some(self.answer == "yes"),
+                "n" | "no" => Some(self.answer == "no"),
+                _ => None,
+            },
+        }
+    }
+
+    // the right answer, the way it would be typed in.
+    pub fn expected(&self) -> String {
+        match self.kind {
+            Kind::Choice => {
+                let i = self.choices.iter().position(|choice| choice.1).unwrap_or(0);
+                format!("{}) {}", letter(i), self.choices[i].0)
+            }
+            _ => self.answer.clone(),
+        }
+    }
+
+    // checks the question is complete, once all of its lines are read.
+    fn validate(&self) -> Result<(), String> {
+        if self.explanation.is_empty() {
+            return Err("has no explanation".to_string());
+        }
+        match self.kind {
+            Kind::Choice => {
+                if self.choices.len() < 2 {
+                    return Err("needs at least two choices".to_string());
+                }
+                if self.choices.iter().filter(|choice| choice.1).count() != 1 {
+                    return Err("needs exactly one right choice, marked with *".to_string());
+                }
+                if !self.answer.is_empty() {
+                    return Err("has choices, it doesn't need an = answer".to_string());
+                }
+            }
+            Kind::Output | Kind::Compiles => {
+                if !self.choices.is_empty() {
+                    return Err(format!("is a {} question, it can't have choices", self.kind.name()));
+                }
+                if self.answer.is_empty() {
+                    return Err("has no = answer".to_string());
+                }
+                if self.kind == Kind::Compiles && self.answer != "yes" && self.answer != "no" {
+                    return Err("can only be answered with = yes or = no".to_string());
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+fn letter(i: usize) -> String {
+    ((b'a' + i as u8) as char).to_string()
+}
+
+// the same words, however they're spaced.
+fn words(s: &str) -> Vec<&str> {
+    s.split_whitespace().collect()
+}
+
+// reads the questions from a quiz file, any mistake in it is reported
+// along with the name of the file, and the line it was found on.
+pub fn parse(name: &str, text: &str) -> Result<Vec<Question>, String> {
+    let mut questions: Vec<Question> = Vec::new();
+    // the line each question starts on, for reporting mistakes.
+    let mut starts = Vec::new();
+
+    for (i, line) in text.lines().enumerate() {
+        let error = |message: &str| format!("{}:{}: {}", name, i + 1, message);
+
+        if line.trim().is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        if line.starts_with('[') {
+            let end = line.find(']').ok_or_else(|| error("missing ] after the kind"))?;
+            let kind = match &line[1..end] {
+                "choice" => Kind::Choice,
+                "output" => Kind::Output,
+                "compiles" => Kind::Compiles,
+                other => return Err(error(&format!(
+                    "unknown kind `{}`, expected choice, output or compiles", other
+                ))),
+            };
+            let prompt = line[end + 1..].trim();
+            if prompt.is_empty() {
+                return Err(error("the question is missing"));
+            }
+            let number = questions.len() + 1;
+            questions.push(Question::new(number, kind, prompt));
+            starts.push(i + 1);
+            continue;
+        }
+
+        let question = questions
+            .last_mut()
+            .ok_or_else(|| error("expected a question, like `[choice] ...`"))?;
+        let mark = line.chars().next().unwrap();
+        let rest = &line[mark.len_utf8()..];
+        let rest = rest.strip_prefix(' ').unwrap_or(rest);
+
+        match mark {
+            '|' => question.code.push(rest.to_string()),
+            '-' => question.choices.push((rest.trim().to_string(), false)),
+            '*' => question.choices.push((rest.trim().to_string(), true)),
+            '=' if question.answer.is_empty() => question.answer = rest.trim().to_string(),
+            '=' => return Err(error("the question already has an answer")),
+            '>' => question.explanation.push(rest.trim().to_string()),
+            _ => return Err(error("expected a line starting with |, -, *, =, > or #")),
+        }
+    }
+
+    for (question, start) in questions.iter().zip(starts) {
+        question
+            .validate()
+            .map_err(|e| format!("{}:{}: the question {}", name, start, e))?;
+    }
+    Ok(questions)
+}
+
+// the same shuffle for the same seed, a different one every time without.
+fn rng(seed: Option<u64>) -> StdRng {
+    match seed {
+        Some(seed) => {
+            let mut bytes = [0u8; 32];
+            bytes[..8].copy_from_slice(&seed.to_le_bytes());
+            StdRng::from_seed(bytes)
+        }
+        None => StdRng::from_rng(rand::thread_rng()).expect("failed to seed the quiz"),
+    }
+}
+
+pub fn run(ctx: &mut Context, lesson: &dyn Lesson) {
+    let name = format!("quizzes/{}.quiz", lesson.id());
+    let mut questions = match parse(&name, lesson.quiz()) {
+        Ok(questions) => questions,
+        // the quizzes are compiled in, and checked by the tests, so this
+        // would be a bug, not something the user did.
+        Err(e) => panic!("{}", e),
+    };
+
+    // the questions, and the choices of each question, come in a random order.
+    let mut rng = rng(ctx.options.seed);
+    rng.shuffle(&mut questions);
+    for question in questions.iter_mut() {
+        rng.shuffle(&mut question.choices);
+    }
+
+    let total = questions.len();
+    outln!(ctx, "quiz: {}, {} questions\n", lesson.title(), total);
+
+    let mut score = 0;
+    let mut results = Vec::new();
+    for (i, question) in questions.iter().enumerate() {
+        let correct = match ask(ctx, i, total, question) {
+            Some(correct) => correct,
+            None => {
+                ctx.end_of_input();
+                return;
+            }
+        };
+        if correct {
+            score += 1;
+        }
+        results.push(Value::object(vec![
+            ("number", Value::UInt(question.number as u128)),
+            ("question", Value::Str(question.prompt.clone())),
+            ("correct", Value::Bool(correct)),
+        ]));
+    }
+
+    outln!(ctx, "you scored {}/{} ({}%)", score, total, score * 100 / total.max(1));
+    ctx.event("score", vec![
+        ("lesson", Value::Str(lesson.id().to_string())),
+        ("score", Value::UInt(score as u128)),
+        ("total", Value::UInt(total as u128)),
+    ]);
+
+    save(ctx, lesson, score, total, results);
+    profile::record(ctx, lesson, |progress| progress.score_quiz(score, total));
+}
+
+// asks the question until it gets an answer, which is either right or
+// wrong, None if the input ran out before that.
+fn ask(ctx: &mut Context, i: usize, total: usize, question: &Question) -> Option<bool> {
+    outln!(ctx, "[{}/{}] {}", i + 1, total, question.prompt);
+    for line in question.code.iter() {
+        outln!(ctx, "    {}", line);
+    }
+    for (i, choice) in question.choices.iter().enumerate() {
+        outln!(ctx, "  {}) {}", letter(i), choice.0);
+    }
+    ctx.event("question", vec![
+        ("index", Value::UInt(i as u128 + 1)),
+        ("number", Value::UInt(question.number as u128)),
+        ("kind", Value::Str(question.kind.name().to_string())),
+        ("question", Value::Str(question.prompt.clone())),
+        ("code", Value::Array(question.code.iter().map(|line| Value::Str(line.clone())).collect())),
+        ("choices", Value::Array(question.choices.iter().map(|choice| Value::Str(choice.0.clone())).collect())),
+    ]);
+
+    let hint = match question.kind {
+        Kind::Choice => format!("answer with a letter, a to {}", letter(question.choices.len() - 1)),
+        Kind::Output => "type in what it prints".to_string(),
+        Kind::Compiles => "answer yes or no".to_string(),
+    };
+
+    loop {
+        outln!(ctx, "{} ..", hint);
+
+        let mut answer = String::new();
+        let read = ctx.read_line(&mut answer)
+            .expect("failed to read line");
+        if read == 0 {
+            return None;
+        }
+
+        let correct = match question.check(&answer) {
+            Some(correct) => correct,
+            None => {
+                outln!(ctx, "`{}` is not an answer to this one.", answer.trim());
+                continue;
+            }
+        };
+
+        if correct {
+            outln!(ctx, "correct!\n");
+        } else {
+            outln!(ctx, "not quite, the answer is: {}", question.expected());
+            for line in question.explanation.iter() {
+                outln!(ctx, "  {}", line);
+            }
+            outln!(ctx);
+        }
+        ctx.event("answer", vec![
+            ("index", Value::UInt(i as u128 + 1)),
+            ("answer", Value::Str(answer.trim().to_string())),
+            ("correct", Value::Bool(correct)),
+            ("expected", Value::Str(question.expected())),
+            ("explanation", Value::Str(question.explanation.join(" "))),
+        ]);
+        return Some(correct);
+    }
+}
+
+// adds the result to the user's file, failing to save is reported, but
+// doesn't take away from having taken the quiz.
+fn save(ctx: &mut Context, lesson: &dyn Lesson, score: usize, total: usize, answers: Vec<Value>) {
+    let home = match ctx.home {
+        Some(ref home) => home.clone(),
+        None => return,
+    };
+    let user = ctx.user();
+    let time = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|time| time.as_secs())
+        .unwrap_or(0);
+
+    let record = Value::object(vec![
+        ("user", Value::Str(user.clone())),
+        ("lesson", Value::Str(lesson.id().to_string())),
+        ("score", Value::UInt(score as u128)),
+        ("total", Value::UInt(total as u128)),
+        ("time", Value::UInt(time as u128)),
+        ("answers", Value::Array(answers)),
+    ]);
+
+    let path = home.join("quiz").join(format!("{}.jsonl", user));
+    let saved = store::append_line(&path, &record.to_string());
+
+    match saved {
+        Ok(()) => {
+            outln!(ctx, "saved to {}'s results.", user);
+            ctx.event("saved", vec![("user", Value::Str(user))]);
+        }
+        Err(e) => eprintln!("failed to save the quiz results: {}", e),
+    }
+}
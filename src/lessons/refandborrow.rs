@@ -0,0 +1,393 @@
+// references and borrowing
+
+use borrows::{self, Tracked};
+use context::Context;
+use json::Value;
+use lessons::exercise::Exercise;
+use lessons::{Lesson, Section};
+use panics;
+
+pub struct RefAndBorrow;
+
+impl Lesson for RefAndBorrow {
+    fn id(&self) -> &'static str {
+        "refandborrow"
+    }
+
+    fn title(&self) -> &'static str {
+        "references and borrowing"
+    }
+
+    fn summary(&self) -> &'static str {
+        "using values without taking ownership of them, shared and mutable references, the borrowing rules, dangling references, and slices."
+    }
+
+    fn prerequisites(&self) -> &'static [&'static str] {
+        &["ownership"]
+    }
+
+    fn source(&self) -> &'static str {
+        include_str!("refandborrow.rs")
+    }
+
+    fn sections(&self) -> &'static [Section] {
+        SECTIONS
+    }
+
+    fn quiz(&self) -> &'static str {
+        include_str!("quizzes/refandborrow.quiz")
+    }
+
+    fn exercises(&self) -> &'static [Exercise] {
+        EXERCISES
+    }
+}
+
+static SECTIONS: &[Section] = &[
+    section!("references", references, calculate_length),
+    section!("mutable references", mutable_references, change),
+    section!("one mutable, or many shared", borrowing_rules),
+    section!("borrowing at runtime, with RefCell", runtime_borrows),
+    section!("dangling references", dangling_references, no_dangle),
+    section!("string slices", string_slices, first_word),
+    section!("other slices", other_slices, sum),
+    section!("borrowing instead of moving", borrowing_instead_of_moving, takes_and_gives_back),
+];
+
+static EXERCISES: &[Exercise] = &[
+    Exercise {
+        name: "two_mutable_borrows",
+        title: "two mutable references to the same String",
+        code: include_str!("exercises/refandborrow/two_mutable_borrows.rs"),
+        error: "E0499",
+        keep: Some("println!(\"{}, {}\", r1, r2);"),
+    },
+    Exercise {
+        name: "dangling_reference",
+        title: "handing back a reference to something that's dropped",
+        code: include_str!("exercises/refandborrow/dangling_reference.rs"),
+        error: "E0106",
+        keep: Some("println!(\"{}\", reference_to_nothing);"),
+    },
+];
+
+fn references(ctx: &mut Context) {
+
+    // in the ownership lesson, passing a String to a function moved it, so to
+    // keep using it, the function had to hand it back, along with whatever it
+    // worked out, as a tuple. that gets tedious quickly.
+
+    // instead, we can hand the function a reference to the String, written with
+    // an &, a reference lets us refer to a value, without taking ownership of it.
+    let s1 = String::from("hello");
+    let len = calculate_length(&s1);
+
+    // s1 is still ours, so it can be used after the call.
+    outln!(ctx, "the length of '{}' is {}.\n", s1, len);
+    ctx.value("s1", &s1);
+    ctx.value("len", &len);
+
+    // a reference is a pointer, on the stack, to s1, which in turn points at
+    // the text on the heap, the reference doesn't own any of it, so when it goes
+    // out of scope, nothing is dropped.
+
+    // having a reference as a parameter is called borrowing, just like in real
+    // life, when we're done with what we borrowed, we have to give it back, we
+    // don't own it.
+}
+
+// s is a reference to a String, a &str would do too, see string slices below.
+#[allow(clippy::ptr_arg)]
+fn calculate_length(s: &String) -> usize {
+    s.len()
+} // here s goes out of scope, but as it doesn't own what it refers to, nothing happens.
+
+fn mutable_references(ctx: &mut Context) {
+
+    // references are immutable by default, just like variables, so trying to
+    // change something we only borrowed won't work.
+
+    // fn change(some_string: &String) {
+    //     some_string.push_str(", world");
+    // }
+    // error[E0596]: cannot borrow `*some_string` as mutable, as it is behind a `&` reference
+
+    // to change a borrowed value, both the variable, and the reference have to be
+    // mutable, the variable with mut, and the reference with &mut.
+    let mut s = String::from("hello");
+    change(&mut s);
+
+    outln!(ctx, "s was borrowed mutably, and changed to: {}\n", s);
+    ctx.value("s", &s);
+}
+
+fn change(some_string: &mut String) {
+    some_string.push_str(", world");
+}
+
+fn borrowing_rules(ctx: &mut Context) {
+
+    // the big restriction on mutable references: while there's a mutable reference
+    // to a value, there can be no other reference to it, mutable or not.
+
+    // let mut s = String::from("hello");
+    // let r1 = &mut s;
+    // let r2 = &mut s;
+    // println!("{}, {}", r1, r2);
+    // error[E0499]: cannot borrow `s` as mutable more than once at a time
+
+    // this is how rust prevents data races at compile time, a data race happens
+    // when two or more pointers access the same data at the same time, at least one
+    // of them writes to it, and nothing synchronizes the accesses, which leads to
+    // undefined behavior, that's hard to track down at runtime.
+
+    // any number of immutable references are fine though, as nobody who's only
+    // reading the data can affect anyone else reading it.
+    let mut s = String::from("hello");
+    let r1 = &s;
+    let r2 = &s;
+    outln!(ctx, "two shared references, r1 = {}, r2 = {}", r1, r2);
+    ctx.value("r1", r1);
+    ctx.value("r2", r2);
+
+    // but not a mutable one, while the immutable ones are still in use, whoever
+    // is reading doesn't expect the value to suddenly change under them.
+
+    // let r3 = &mut s;
+    // println!("{}, {}, and {}", r1, r2, r3);
+    // error[E0502]: cannot borrow `s` as mutable because it is also borrowed as immutable
+
+    // a reference's scope starts where it's made, and ends the last time it's used,
+    // not at the closing "}", r1 and r2 aren't used after the println! above, so a
+    // mutable reference can be made now.
+    let r3 = &mut s;
+    r3.push_str(", world");
+    outln!(ctx, "r1 and r2 are done with, so r3 can change it to: {}\n", r3);
+    ctx.value("r3", r3);
+
+    // we can also use curly brackets to make a new scope, allowing multiple mutable
+    // references, just not simultaneous ones.
+}
+
+fn runtime_borrows(ctx: &mut Context) {
+
+    // the compiler checks the borrowing rules before the program ever runs, but some
+    // programs are fine, and it just can't prove it. a RefCell moves the check to when
+    // the program runs, it hands out borrows through a shared reference, keeping count
+    // of them, and panics when one would break the rules.
+
+    // s is a RefCell, tracked, so every borrow has a name, and the table of who's
+    // borrowing s is printed as the borrows come and go, see borrows.rs.
+    let s = Tracked::new("s", String::from("hello"));
+    {
+        let r1 = s.borrow("r1");
+        let r2 = s.borrow("r2");
+        show_borrows(ctx, &s);
+        outln!(ctx, "r1 = {}, r2 = {}, any number of shared borrows is fine", *r1, *r2);
+    } // r1 and r2 are dropped here, and give their borrows back.
+    show_borrows(ctx, &s);
+
+    {
+        // a mutable borrow, note that s itself isn't mut, the RefCell allows it anyway.
+        let mut r3 = s.borrow_mut("r3");
+        r3.push_str(", world");
+        show_borrows(ctx, &s);
+
+        // asking politely, with try_borrow, gets an error back while r3 is around.
+        if let Err(conflict) = s.try_borrow("r4") {
+            outln!(ctx, "try_borrow says no, {}", conflict);
+            borrow_conflict(ctx, &conflict);
+        }
+
+        // but borrow just panics, which would end the lesson right here, so it's caught,
+        // the panic is the very same one a plain RefCell raises.
+        if let Err(message) = panics::catch(|| drop(s.borrow("r5"))) {
+            outln!(ctx, "borrow panics with \"{}\", as r3 is still using s", message);
+            ctx.event("panic", vec![("message", Value::Str(message))]);
+        }
+        outln!(ctx, "r3 = {}", *r3);
+    }
+    show_borrows(ctx, &s);
+
+    // with r3 gone, anyone can borrow s again.
+    let r6 = s.borrow("r6");
+    outln!(ctx, "r6 = {}\n", *r6);
+    ctx.value("s", &*r6);
+
+    // a RefCell is handy, but every mistake becomes a panic at runtime, instead of an
+    // error at compile time, so plain references are the first thing to reach for.
+}
+
+fn dangling_references(ctx: &mut Context) {
+
+    // in languages with pointers, it's easy to end up with a dangling pointer, a
+    // pointer to memory that has been freed, and maybe handed to someone else.
+    // in rust, the compiler guarantees that references will never be dangling,
+    // the data will not go out of scope before the reference to it does.
+
+    // let reference_to_nothing = dangle();
+    //
+    // fn dangle() -> &String { // dangle returns a reference to a String
+    //     let s = String::from("hello"); // s is a new String
+    //     &s // we return a reference to the String, s
+    // } // here, s goes out of scope, and is dropped, its memory goes away.
+    //
+    // error[E0106]: missing lifetime specifier
+    // help: this function's return type contains a borrowed value, but there is
+    // no value for it to be borrowed from
+
+    // as s is created inside dangle, it's dropped at the end of it, so the reference
+    // would point at an invalid String, rust won't let us do that. the fix is to
+    // hand back the String itself, moving its ownership out, so nothing is dropped.
+    let s = no_dangle();
+    outln!(ctx, "no_dangle gave us the String itself: {}\n", s);
+    ctx.value("s", &s);
+}
+
+#[allow(clippy::let_and_return)]
+fn no_dangle() -> String {
+    let s = String::from("hello");
+
+    s
+}
+
+fn string_slices(ctx: &mut Context) {
+
+    // a slice is another kind of reference, to a contiguous part of a collection,
+    // rather than the whole of it, like a part of a String.
+    let s = String::from("hello world");
+
+    // the range [starting_index..ending_index] goes from the starting index, up to,
+    // but not including, the ending index, the slice stores the starting position,
+    // and its length, which is ending_index - starting_index.
+    let hello = &s[0..5];
+    let world = &s[6..11];
+    outln!(ctx, "slicing '{}' gives us '{}' and '{}'", s, hello, world);
+    ctx.value("hello", hello);
+    ctx.value("world", world);
+
+    // the starting index can be dropped when it's 0, and the ending one when it's
+    // the length, &s[..] is the whole String. the indices are in bytes, and have to
+    // fall on character boundaries, slicing in the middle of a multibyte character
+    // panics.
+
+    // the type of a string slice is written &str. a function that finds the first
+    // word in a String can now hand back the word itself, instead of an index into
+    // the String, that has nothing to do with it, and can go stale.
+    let word = first_word(&s);
+    outln!(ctx, "the first word of '{}' is '{}'", s, word);
+    ctx.value("word", word);
+
+    // and as word borrows s, clearing s while word is still in use won't compile,
+    // clear needs a mutable reference, and word is an immutable one.
+
+    // s.clear();
+    // println!("the first word is: {}", word);
+    // error[E0502]: cannot borrow `s` as mutable because it is also borrowed as immutable
+
+    // string literals are slices too, the type of "hello world" is &str, a slice
+    // pointing at a particular spot in the binary, which is why they're immutable.
+    // first_word takes a &str, so it works on literals, and on Strings, which are
+    // turned into a slice of the whole String with &s, or &s[..].
+    let literal = "hello again";
+    let word = first_word(literal);
+    outln!(ctx, "and of the literal '{}', it's '{}'\n", literal, word);
+    ctx.value("literal_word", word);
+}
+
+#[allow(clippy::redundant_slicing)]
+fn first_word(s: &str) -> &str {
+    let bytes = s.as_bytes();
+
+    for (i, &item) in bytes.iter().enumerate() {
+        if item == b' ' {
+            return &s[0..i];
+        }
+    }
+
+    &s[..]
+}
+
+fn other_slices(ctx: &mut Context) {
+
+    // slices aren't only for strings, a part of an array can be referred to the same
+    // way, this slice has the type &[i32], and works just like a string slice does,
+    // it stores a reference to the first element, and a length.
+    let a = [1, 2, 3, 4, 5];
+    let slice = &a[1..3];
+    outln!(ctx, "the slice [1..3] of {:?} is {:?}", a, slice);
+    ctx.value("slice", slice);
+
+    // a function taking a &[i32] works on a whole array, a part of one, or a Vec,
+    // without ever owning any of them.
+    let v = vec![10, 20, 30];
+    outln!(ctx, "sum of the array: {}, of the slice: {}, of the vec: {}\n", sum(&a), sum(slice), sum(&v));
+    ctx.value("sum", &sum(&a));
+}
+
+fn sum(numbers: &[i32]) -> i32 {
+    let mut total = 0;
+    for n in numbers {
+        total += n;
+    }
+    total
+}
+
+fn borrowing_instead_of_moving(ctx: &mut Context) {
+
+    // back in the ownership lesson, takes_and_gives_back had to take the String,
+    // clone it twice, and hand a String back, just so main could still have one.
+    // with a reference, it only borrows s3, and main keeps it all along.
+
+    let s3 = String::from("Hello, from the Inside!");
+    let (shadowed, len) = takes_and_gives_back(&s3);
+
+    outln!(ctx, "s3 was only borrowed, so it's still here:\t{}", s3);
+    outln!(ctx, "and what came back is a new String\t\t{} Length={}", shadowed, len);
+    ctx.value("s3", &s3);
+    ctx.value("shadowed", &shadowed);
+    ctx.value("len", &len);
+
+    // to recap, at any given time, we can have either one mutable reference, or any
+    // number of immutable ones, and references must always be valid.
+}
+
+// no moves, no clones, a_string is read through the reference, and format! makes the
+// one new String that's handed back.
+#[allow(clippy::ptr_arg)]
+fn takes_and_gives_back(a_string: &String) -> (String,usize) {
+
+    let shadowed = format!("{}, and shadowed from the outside!", a_string);
+    let length = shadowed.len();
+
+    (shadowed,length)
+
+}
+
+// prints who's borrowing the tracked value right now.
+fn show_borrows<T>(ctx: &mut Context, tracked: &Tracked<T>) {
+    let table = tracked.table();
+    let holders: Vec<String> = table.iter().map(|borrow| format!("{} ({})", borrow.name, borrow.kind.name())).collect();
+    let holders = if holders.is_empty() { "nobody".to_string() } else { holders.join(", ") };
+    outln!(ctx, "  | {} is borrowed by: {}", tracked.name(), holders);
+    ctx.event("borrows", vec![
+        ("cell", Value::Str(tracked.name().to_string())),
+        ("borrows", borrow_table(&table)),
+    ]);
+}
+
+fn borrow_table(table: &[borrows::Borrow]) -> Value {
+    Value::Array(table.iter().map(|borrow| Value::object(vec![
+        ("name", Value::Str(borrow.name.to_string())),
+        ("kind", Value::Str(borrow.kind.name().to_string())),
+    ])).collect())
+}
+
+fn borrow_conflict(ctx: &mut Context, conflict: &borrows::Conflict) {
+    ctx.event("borrow_conflict", vec![
+        ("cell", Value::Str(conflict.cell.to_string())),
+        ("name", Value::Str(conflict.name.to_string())),
+        ("kind", Value::Str(conflict.kind.name().to_string())),
+        ("holders", borrow_table(&conflict.holders)),
+    ]);
+}
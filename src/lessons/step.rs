@@ -0,0 +1,218 @@
+// stepping through a lesson
+
+// with --step, a lesson is shown one section at a time, each section starts
+// with its notes, the comments from its functions in the lesson's source, so
+// the explanations are right next to what they explain, followed by what the
+// section prints. after each section we wait, enter moves on to the next
+// one, and the others let us go back, jump around, or see the same section
+// again.
+
+// a section that's only notes, with nothing to run, has its notes under a
+// heading instead, a comment line of its own, at the start of the line, that
+// is the section's title, like "//CONSTANTS", they're the comments from there
+// up to the next section's heading, or the next function, and whatever code
+// is in between is the section's code.
+
+use context::Context;
+use json::Value;
+use lessons::{Lesson, Section};
+use profile;
+
+// the comments inside the given functions, in paragraphs, a paragraph being
+// a run of comment lines, without any code or blank lines in between.
+// comments at the end of a line of code belong to that code, and are left
+// out, unless the code is just the brace opening or closing a scope, as in
+// "} // s is dropped here".
+pub fn notes(source: &str, functions: &[&str]) -> Vec<String> {
+    let mut notes = Vec::new();
+    for function in functions {
+        paragraphs(&body(source, function), &mut notes);
+    }
+    notes
+}
+
+fn paragraphs(lines: &[&str], notes: &mut Vec<String>) {
+    let mut paragraph: Vec<&str> = Vec::new();
+    for line in lines {
+        let line = line.trim().trim_start_matches(['{', '}']).trim_start();
+        if let Some(text) = line.strip_prefix("//") {
+            paragraph.push(text.strip_prefix(' ').unwrap_or(text));
+        } else if !paragraph.is_empty() {
+            notes.push(paragraph.join("\n"));
+            paragraph.clear();
+        }
+    }
+    if !paragraph.is_empty() {
+        notes.push(paragraph.join("\n"));
+    }
+}
+
+// the lines under the heading of the section titled title, the titles being
+// those of every section in the lesson.
+fn under<'a>(source: &'a str, title: &str, titles: &[&str]) -> Vec<&'a str> {
+    fn heading(line: &str) -> Option<&str> {
+        line.strip_prefix("//").map(str::trim)
+    }
+    source
+        .lines()
+        .skip_while(|line| heading(line) != Some(title))
+        .skip(1)
+        .take_while(|line| !line.starts_with("fn ") && !heading(line).is_some_and(|text| titles.contains(&text)))
+        .collect()
+}
+
+pub fn heading_notes(source: &str, title: &str, titles: &[&str]) -> Vec<String> {
+    let mut notes = Vec::new();
+    paragraphs(&under(source, title, titles), &mut notes);
+    notes
+}
+
+pub fn heading_code(source: &str, title: &str, titles: &[&str]) -> String {
+    let code: Vec<&str> = under(source, title, titles)
+        .into_iter()
+        .filter(|line| !line.trim().is_empty() && !line.trim_start().starts_with("//"))
+        .collect();
+    code.join("\n")
+}
+
+fn titles(lesson: &dyn Lesson) -> Vec<&'static str> {
+    lesson.sections().iter().map(|section| section.title).collect()
+}
+
+// the section's notes, from its functions, or its heading, if it's only notes.
+pub fn section_notes(lesson: &dyn Lesson, section: &Section) -> Vec<String> {
+    match section.run {
+        Some(_) => notes(lesson.source(), section.functions),
+        None => heading_notes(lesson.source(), section.title, &titles(lesson)),
+    }
+}
+
+pub fn section_code(lesson: &dyn Lesson, section: &Section) -> String {
+    match section.run {
+        Some(_) => code(lesson.source(), section.functions),
+        None => heading_code(lesson.source(), section.title, &titles(lesson)),
+    }
+}
+
+// the code of the given functions, with the comments taken out, they are
+// the notes, and with no more than one blank line in a row.
+pub fn code(source: &str, functions: &[&str]) -> String {
+    let mut code: Vec<&str> = Vec::new();
+    for function in functions {
+        let start = format!("fn {}(", function);
+        let signature = match source.lines().find(|line| line.starts_with(&start)) {
+            Some(signature) => signature,
+            None => continue,
+        };
+        if !code.is_empty() {
+            code.push("");
+        }
+        code.push(signature);
+        for line in body(source, function) {
+            let blank = line.trim().is_empty();
+            if line.trim_start().starts_with("//")
+                || (blank && code.last().is_some_and(|last| last.trim().is_empty() || last.ends_with('{')))
+            {
+                continue;
+            }
+            code.push(line);
+        }
+        while code.last().is_some_and(|last| last.trim().is_empty()) {
+            code.pop();
+        }
+        code.push("}");
+    }
+    code.join("\n")
+}
+
+// the lines between "fn <name>(" and the "}" closing it, the lessons are
+// formatted plainly enough that the closing brace is the first line after it
+// that is just "}".
+fn body<'a>(source: &'a str, function: &str) -> Vec<&'a str> {
+    let start = format!("fn {}(", function);
+    source
+        .lines()
+        .skip_while(|line| !line.starts_with(&start))
+        .skip(1)
+        .take_while(|line| *line != "}")
+        .collect()
+}
+
+fn show(ctx: &mut Context, lesson: &dyn Lesson, at: usize) {
+    let sections = lesson.sections();
+    let section = &sections[at];
+    let notes = section_notes(lesson, section);
+
+    outln!(ctx, "--- [{}/{}] {} ---\n", at + 1, sections.len(), section.title);
+    for note in notes.iter() {
+        for line in note.lines() {
+            outln!(ctx, "    {}", line);
+        }
+        outln!(ctx);
+    }
+    ctx.event("section", vec![
+        ("lesson", Value::Str(lesson.id().to_string())),
+        ("index", Value::UInt(at as u128 + 1)),
+        ("of", Value::UInt(sections.len() as u128)),
+        ("title", Value::Str(section.title.to_string())),
+        ("notes", Value::Array(notes.into_iter().map(Value::Str).collect())),
+    ]);
+
+    if let Some(run) = section.run {
+        run(ctx);
+    }
+    if !ctx.aborted() {
+        profile::record(ctx, lesson, |progress| progress.see_section(section.title));
+    }
+}
+
+fn list(ctx: &mut Context, lesson: &dyn Lesson, at: usize) {
+    for (i, section) in lesson.sections().iter().enumerate() {
+        let marker = if i == at { ">" } else { " " };
+        outln!(ctx, "{} {:>2}) {}", marker, i + 1, section.title);
+    }
+}
+
+pub fn step(ctx: &mut Context, lesson: &dyn Lesson) {
+    let count = lesson.sections().len();
+    if count == 0 {
+        outln!(ctx, "there is nothing to step through in this lesson, yet.");
+        return;
+    }
+
+    let mut at = 0;
+    loop {
+        show(ctx, lesson, at);
+        if ctx.aborted() {
+            return;
+        }
+
+        // keeps asking until we're told which section to show next.
+        at = loop {
+            outln!(ctx, "-- enter or n: next, p: previous, r: replay, 1-{}: jump, l: list, q: quit", count);
+
+            let mut choice = String::new();
+            let read = ctx.read_line(&mut choice)
+                .expect("failed to read line");
+            if read == 0 {
+                return;
+            }
+
+            match choice.trim() {
+                "" | "n" if at + 1 == count => {
+                    outln!(ctx, "that was the last section, all done!");
+                    return;
+                }
+                "" | "n" => break at + 1,
+                "p" => break at.saturating_sub(1),
+                "r" => break at,
+                "l" => list(ctx, lesson, at),
+                "q" => return,
+                choice => match choice.parse::<usize>() {
+                    Ok(n) if n >= 1 && n <= count => break n - 1,
+                    _ => outln!(ctx, "there is no section `{}`, try again!", choice),
+                },
+            }
+        };
+    }
+}
@@ -0,0 +1,136 @@
+// variables and mutability
+
+use context::Context;
+use lessons::exercise::Exercise;
+use lessons::{Lesson, Section};
+
+pub struct Variables;
+
+impl Lesson for Variables {
+    fn id(&self) -> &'static str {
+        "variables"
+    }
+
+    fn title(&self) -> &'static str {
+        "variables and mutability"
+    }
+
+    fn summary(&self) -> &'static str {
+        "variables are immutable by default, mut makes them mutable, constants are always immutable, and shadowing lets a name be reused, even with a different type."
+    }
+
+    fn source(&self) -> &'static str {
+        include_str!("variables.rs")
+    }
+
+    fn sections(&self) -> &'static [Section] {
+        SECTIONS
+    }
+
+    fn quiz(&self) -> &'static str {
+        include_str!("quizzes/variables.quiz")
+    }
+
+    fn exercises(&self) -> &'static [Exercise] {
+        EXERCISES
+    }
+}
+
+static SECTIONS: &[Section] = &[
+    section!("VARIABLES", variables),
+    section!("CONSTANTS"),
+    section!("SHADOWING", shadowing),
+];
+
+static EXERCISES: &[Exercise] = &[
+    Exercise {
+        name: "assign_twice",
+        title: "assigning to an immutable variable",
+        code: include_str!("exercises/variables/assign_twice.rs"),
+        error: "E0384",
+        keep: Some("x = 6;"),
+    },
+];
+
+// everything the lesson shows goes through ctx, which prints prose for
+// us humans, or with --format json, the values themselves for our tools.
+
+//VARIABLES
+
+fn variables(ctx: &mut Context) {
+
+    // in rust variables are immutable, by default
+    // this is done to ensure safety and concurrency
+
+    let x  = 5;
+    outln!(ctx, "the value of x is {}, and x is immutable", x);
+    ctx.value("x", &x);
+    // x is now an immutable variable bound to the value 5
+    // attempts to change its value will lead to errors.
+
+    let mut y = 5;
+    outln!(ctx, "the original value of y is {}, and y is mutable", y);
+    ctx.value("y", &y);
+
+    y = 65;
+    outln!(ctx, "the value of y is now {}", y);
+    ctx.value("y", &y);
+
+    // mut conveys the intent to readers that other parts of the
+    // code are going to be changing this variable's value.
+    // also, in larger data structures mutating an instance in place
+    // maybe faster than copying and returning newly allocated instances.
+    // while for smaller structures, creating newer instances and allowing
+    // a more functional style maybe be easier to practice and reason with,
+    // a worthwhile trade-off, for gaining extra clarity.
+}
+
+//CONSTANTS
+
+// while this sounds like variables are constants by default, it's not
+// the case, as constants in rust are not only immutable by default,
+// they are always immutable.
+
+// constants are defined using the const keyword, instead of the let
+// keyword, while the type annotation is mandatory. constants can be
+// declared in any scope, including the global scope, and may only be
+// set to a constant expression, not a function call, or any value that
+// is calculated in runtime, their definitions are explicit throughout.
+
+const MAXIMUM: u32 = 100_000;
+
+//SHADOWING
+
+fn shadowing(ctx: &mut Context) {
+
+    // shadowing is the act of declaring a new variable with the same name as
+    // a previously existing variable, where this new variable shadows the pre-
+    // -vious variable, in all subsequent sections of the code that follow the
+    // shadowing.
+
+    let z: i32 = 5 * MAXIMUM as i32;
+
+    let z: i64 = z as i64 + 1;
+
+    let z: i64 = z*z + 3*z;
+
+    outln!(ctx, "the value of z is: {}",z);
+    ctx.value("z", &z);
+
+    // shadowing is different than marking a variable as mut, as with mut we can assign
+    // without let, but in shadowing we are explicitly writing over the variable instance,
+    // with a new instance of different value, in mut the instance remains the same,
+    // with this, shadowing allows us to do things like, change the associated type, while still
+    // keeping the variable immutable for everything else.
+
+    let spaces = "    "; //4 spaces
+    let spaces = spaces.len();
+    outln!(ctx, "spaces is {}",spaces);
+    ctx.value("spaces", &spaces);
+
+    // if we had used mut, we would not have been able to change the type associated with space.
+
+    // len() counts bytes, not characters, it's 4 here because a space is a
+    // single byte, "rust_commons datatypes unicode" shows where they differ.
+
+}
@@ -0,0 +1,36 @@
+// shared code for the lessons
+
+// everything in here is used by more than one of the binaries, cargo picks
+// this file up automatically as the library part of the package, and each
+// binary brings it in with "extern crate rusty;".
+
+extern crate libc;
+extern crate rand;
+
+#[macro_use]
+pub mod output;
+#[macro_use]
+pub mod calls;
+
+pub mod borrows;
+pub mod cli;
+pub mod context;
+pub mod costs;
+pub mod counting;
+pub mod expr;
+pub mod float;
+pub mod heap;
+pub mod interp;
+pub mod json;
+pub mod layout;
+pub mod lessons;
+pub mod literal;
+pub mod overflow;
+pub mod panics;
+pub mod profile;
+pub mod progress;
+pub mod rustc;
+pub mod store;
+pub mod strings;
+pub mod tracer;
+pub mod unicode;
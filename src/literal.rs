@@ -0,0 +1,356 @@
+// number literals
+
+// rust has a lot of ways of writing a number down, 98_222, 0xff, 0o77,
+// 0b1111_0000, b'A', 2.5, 1e-3, and any of them can have a type stuck on
+// the end, like 255u8 or 1e-3f32. this reads all of them, the way rustc's
+// lexer does, and says what type each one ends up with, and what value.
+
+// a literal without a suffix gets its type from how it's used, and when
+// nothing says, it's an i32, or an f64, which is what's assumed here.
+
+// a literal too big for its type is an error, rustc's overflowing_literals
+// lint denies it, and the reasons given here are the ones rustc gives. the
+// minus sign isn't part of the literal, it's the negation operator, but it's
+// taken into account, so -128i8 fits, and -1u8 doesn't.
+
+use expr::{Error, Span};
+
+pub const INT_TYPES: &[&str] = &[
+    "i8", "i16", "i32", "i64", "i128", "isize", "u8", "u16", "u32", "u64", "u128", "usize",
+];
+
+pub const FLOAT_TYPES: &[&str] = &["f32", "f64"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Base {
+    Decimal,
+    Hex,
+    Octal,
+    Binary,
+    // b'A', a u8 holding the ascii code of the character.
+    Byte,
+}
+
+impl Base {
+    pub fn name(self) -> &'static str {
+        match self {
+            Base::Decimal => "decimal",
+            Base::Hex => "hexadecimal",
+            Base::Octal => "octal",
+            Base::Binary => "binary",
+            Base::Byte => "byte",
+        }
+    }
+
+    fn radix(self) -> u32 {
+        match self {
+            Base::Hex => 16,
+            Base::Octal => 8,
+            Base::Binary => 2,
+            _ => 10,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Number {
+    Int(u128),
+    Float(f64),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Literal {
+    pub base: Base,
+    pub number: Number,
+    pub suffix: Option<&'static str>,
+}
+
+fn error(start: usize, end: usize, message: String) -> Error {
+    Error { span: Span::new(start, end), message }
+}
+
+fn suffix_type(suffix: &str) -> Option<&'static str> {
+    INT_TYPES.iter().chain(FLOAT_TYPES).find(|ty| **ty == suffix).cloned()
+}
+
+// reads the literal at the start of the text, returns it, and how many
+// bytes of the text it took up.
+pub fn lex(text: &str) -> Result<(Literal, usize), Error> {
+    if text.starts_with("b'") {
+        return byte(text);
+    }
+
+    let bytes = text.as_bytes();
+    if !bytes.first().is_some_and(u8::is_ascii_digit) {
+        return Err(error(0, 1, "expected a number literal".to_string()));
+    }
+    let (base, digits_start) = match (bytes[0], bytes.get(1)) {
+        (b'0', Some(&b'x')) => (Base::Hex, 2),
+        (b'0', Some(&b'o')) => (Base::Octal, 2),
+        (b'0', Some(&b'b')) => (Base::Binary, 2),
+        _ => (Base::Decimal, 0),
+    };
+
+    // like rustc, every decimal digit is read, whatever the base, so 0b102
+    // is a binary literal with a bad digit, rather than 0b10 followed by 2,
+    // in hex, a to f are digits too, so 0x1f32 is a number, not a float.
+    let is_digit = |c: u8| c.is_ascii_digit() || c == b'_' || (base == Base::Hex && c.is_ascii_hexdigit());
+    let mut end = digits_start;
+    while end < bytes.len() && is_digit(bytes[end]) {
+        end += 1;
+    }
+
+    // a fraction, 1.5, or just 1., but not 1..2, which is a range, or
+    // 1.foo(), which is a method call.
+    let mut float = false;
+    if base == Base::Decimal && bytes.get(end) == Some(&b'.') {
+        let next = bytes.get(end + 1).cloned();
+        if !next.is_some_and(|c| c == b'.' || c == b'_' || c.is_ascii_alphabetic()) {
+            float = true;
+            end += 1;
+            while end < bytes.len() && (bytes[end].is_ascii_digit() || bytes[end] == b'_') {
+                end += 1;
+            }
+        }
+    }
+    // an exponent, 1e3, 2.5E-4.
+    if base == Base::Decimal && (bytes.get(end) == Some(&b'e') || bytes.get(end) == Some(&b'E')) {
+        let mut exponent = end + 1;
+        if bytes.get(exponent) == Some(&b'+') || bytes.get(exponent) == Some(&b'-') {
+            exponent += 1;
+        }
+        while bytes.get(exponent) == Some(&b'_') {
+            exponent += 1;
+        }
+        if !bytes.get(exponent).is_some_and(u8::is_ascii_digit) {
+            return Err(error(end, exponent, "expected at least one digit in exponent".to_string()));
+        }
+        float = true;
+        end = exponent;
+        while end < bytes.len() && (bytes[end].is_ascii_digit() || bytes[end] == b'_') {
+            end += 1;
+        }
+    }
+    let digits = &text[digits_start..end];
+
+    let suffix_end = end + text[end..]
+        .find(|c: char| !c.is_ascii_alphanumeric() && c != '_')
+        .unwrap_or(text.len() - end);
+    let suffix = match &text[end..suffix_end] {
+        "" => None,
+        suffix => match suffix_type(suffix) {
+            Some(ty) => Some(ty),
+            None => {
+                let message = if float {
+                    format!("invalid suffix `{}` for float literal, valid suffixes are `f32` and `f64`", suffix)
+                } else {
+                    format!("invalid suffix `{}` for number literal, the suffix must be one of the numeric types, like `u32`, `isize` or `f32`", suffix)
+                };
+                return Err(error(end, suffix_end, message));
+            }
+        },
+    };
+    let float_suffix = suffix.is_some_and(|ty| FLOAT_TYPES.contains(&ty));
+
+    if let (true, false, Some(suffix)) = (float, float_suffix, suffix) {
+        let message = format!("invalid suffix `{}` for float literal, valid suffixes are `f32` and `f64`", suffix);
+        return Err(error(end, suffix_end, message));
+    }
+    if float_suffix && base != Base::Decimal {
+        return Err(error(0, suffix_end, format!("{} float literal is not supported", base.name())));
+    }
+
+    let number = if float || float_suffix {
+        let digits: String = digits.chars().filter(|&c| c != '_').collect();
+        Number::Float(digits.parse().expect("a valid float"))
+    } else {
+        Number::Int(integer(text, base, digits_start, end)?)
+    };
+    Ok((Literal { base, number, suffix }, suffix_end))
+}
+
+fn integer(text: &str, base: Base, start: usize, end: usize) -> Result<u128, Error> {
+    let mut value: u128 = 0;
+    let mut seen_digit = false;
+    for (offset, c) in text[start..end].char_indices() {
+        if c == '_' {
+            continue;
+        }
+        let digit = match c.to_digit(base.radix()) {
+            Some(digit) => digit,
+            None => {
+                let at = start + offset;
+                return Err(error(at, at + 1, format!("invalid digit for a base {} literal", base.radix())));
+            }
+        };
+        seen_digit = true;
+        value = match value.checked_mul(u128::from(base.radix())).and_then(|v| v.checked_add(u128::from(digit))) {
+            Some(value) => value,
+            None => return Err(error(0, end, format!(
+                "integer literal is too large, it exceeds the limit of `{}`",
+                u128::MAX
+            ))),
+        };
+    }
+    if !seen_digit {
+        return Err(error(0, end, "no valid digits found for number".to_string()));
+    }
+    Ok(value)
+}
+
+// b'A', b'\n', b'\x7f', only ascii fits in a byte.
+fn byte(text: &str) -> Result<(Literal, usize), Error> {
+    let mut chars = text[2..].char_indices().map(|(i, c)| (i + 2, c));
+    let unterminated = || error(0, text.len(), "unterminated byte constant".to_string());
+
+    let (at, c) = chars.next().ok_or_else(unterminated)?;
+    let value = match c {
+        '\'' => return Err(error(0, at + 1, "empty byte literal".to_string())),
+        '\\' => match chars.next().ok_or_else(unterminated)?.1 {
+            'n' => b'\n',
+            'r' => b'\r',
+            't' => b'\t',
+            '0' => b'\0',
+            '\\' => b'\\',
+            '\'' => b'\'',
+            '"' => b'"',
+            'x' => {
+                let hex: String = chars.by_ref().take(2).map(|(_, c)| c).collect();
+                match u8::from_str_radix(&hex, 16) {
+                    Ok(value) if hex.len() == 2 => value,
+                    _ => return Err(error(at, at + 2 + hex.len(), "invalid character in numeric character escape".to_string())),
+                }
+            }
+            other => return Err(error(at, at + 1 + other.len_utf8(), format!("unknown byte escape: `{}`", other))),
+        },
+        c if c.is_ascii() => c as u8,
+        c => return Err(error(at, at + c.len_utf8(), format!(
+            "non-ASCII character in byte literal, `{}` takes more than a byte, a b'' literal holds a single ascii character",
+            c
+        ))),
+    };
+
+    match chars.next() {
+        Some((end, '\'')) => Ok((
+            Literal { base: Base::Byte, number: Number::Int(u128::from(value)), suffix: Some("u8") },
+            end + 1,
+        )),
+        Some(_) => Err(error(0, text.len(), "a byte literal holds exactly one character".to_string())),
+        None => Err(unterminated()),
+    }
+}
+
+// INSPECTING
+
+// what a literal, maybe with a minus in front, turns out to be.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Inspection {
+    pub literal: Literal,
+    pub negative: bool,
+    pub ty: &'static str,
+    // there was no suffix, so the type is the default one.
+    pub defaulted: bool,
+    // the value, as rust would print it, with {:?}.
+    pub value: String,
+}
+
+fn bits(ty: &str) -> u32 {
+    match &ty[1..] {
+        "size" => 64,
+        bits => bits.parse().expect("an integer type"),
+    }
+}
+
+// the largest value of an integer type, and its range, as rustc shows it.
+fn range(ty: &str) -> (u128, String) {
+    let bits = bits(ty);
+    if ty.starts_with('u') {
+        let max = if bits == 128 { u128::MAX } else { (1 << bits) - 1 };
+        (max, format!("0..={}", max))
+    } else {
+        let max = (1u128 << (bits - 1)) - 1;
+        (max, format!("-{}..={}", max + 1, max))
+    }
+}
+
+// reads a literal, like "0xff", "-128i8" or "1e-3f32", and works out its type
+// and value, or why rustc would refuse it.
+pub fn inspect(text: &str) -> Result<Inspection, Error> {
+    let negative = text.starts_with('-');
+    let start = if negative { 1 } else { 0 };
+    let (literal, len) = lex(&text[start..]).map_err(|e| Error {
+        span: Span::new(e.span.start + start, e.span.end + start),
+        message: e.message,
+    })?;
+    let end = start + len;
+    if end < text.len() {
+        return Err(error(end, text.len(), format!("unexpected `{}` after the literal", &text[end..])));
+    }
+
+    let written = &text[start..end];
+    let defaulted = literal.suffix.is_none();
+    let ty = literal.suffix.unwrap_or(match literal.number {
+        Number::Int(_) => "i32",
+        Number::Float(_) => "f64",
+    });
+    let sign = if negative { "-" } else { "" };
+
+    let value = match literal.number {
+        Number::Float(x) => {
+            // rounding to an f32 can go all the way to infinity.
+            let (value, infinite) = if ty == "f32" {
+                let x = x as f32;
+                (format!("{:?}", if negative { -x } else { x }), x.is_infinite())
+            } else {
+                (format!("{:?}", if negative { -x } else { x }), x.is_infinite())
+            };
+            if infinite {
+                return Err(error(start, end, format!(
+                    "literal out of range for `{}`, the literal `{}` does not fit into the type `{}` and will be converted to `{}{}::INFINITY`",
+                    ty, written, ty, sign, ty
+                )));
+            }
+            value
+        }
+        Number::Int(n) => {
+            if negative && ty.starts_with('u') {
+                return Err(Error {
+                    span: Span::new(0, end),
+                    message: format!("error[E0600]: cannot apply unary operator `-` to type `{}`, unsigned values cannot be negated", ty),
+                });
+            }
+            let (max, range) = range(ty);
+            // -128i8 is fine, the negative side goes one further.
+            let limit = if negative { max + 1 } else { max };
+            if n > limit {
+                return Err(error(start, end, out_of_range(written, &literal, n, ty, negative, &range)));
+            }
+            format!("{}{}", if negative && n > 0 { "-" } else { "" }, n)
+        }
+    };
+
+    Ok(Inspection { literal, negative, ty, defaulted, value })
+}
+
+// rustc's reasons, for a hex, octal or binary literal that fits in the
+// bits of a signed type, it says what it would become, and suggests the
+// unsigned type instead.
+fn out_of_range(written: &str, literal: &Literal, n: u128, ty: &str, negative: bool, range: &str) -> String {
+    let message = format!("literal out of range for `{}`", ty);
+    let bits = bits(ty);
+    let fits_in_bits = bits == 128 || n >> bits == 0;
+    let not_decimal = literal.base != Base::Decimal && literal.base != Base::Byte;
+
+    if not_decimal && !negative && ty.starts_with('i') && fits_in_bits {
+        let shift = 128 - bits;
+        let wrapped = ((n << shift) as i128) >> shift;
+        return format!(
+            "{}, the literal `{}` (decimal `{}`) does not fit into the type `{}` and will become `{}{}`, consider using the type `u{}` instead",
+            message, written, n, ty, wrapped, ty, &ty[1..]
+        );
+    }
+    format!(
+        "{}, the literal `{}{}` does not fit into the type `{}` whose range is `{}`",
+        message, if negative { "-" } else { "" }, written, ty, range
+    )
+}
@@ -0,0 +1,259 @@
+// output, for humans or for machines
+
+// by default the binaries talk to us in plain prose, that's what the
+// lessons are for. with "--format json" they instead print one json object
+// per line, describing what happened, so that other tools can read the
+// results without having to pick apart sentences meant for people.
+
+// every json line has the same three leading keys,
+//
+//   {"schema":"rusty.v1","bin":"<binary>","event":"<kind>", ...}
+//
+// "schema" only changes if existing events change in an incompatible way,
+// new events and new keys may be added at any time. events every binary
+// emits:
+//
+//   start                             the binary has started
+//   value   name, type, value         a value computed by the lesson
+//   invalid_input  input, message, column
+//                                     input that was rejected, column is 1 based
+//   aborted reason                    stopping early, e.g. stdin was closed
+//   end                               the binary is done
+//
+// the rest are specific to each binary, e.g. "guess" in the guessing game,
+// or "result" in the controlflow lesson.
+
+use std::cell::RefCell;
+use std::fmt;
+use std::io::{self, Write};
+use std::process;
+use std::rc::Rc;
+use std::str::FromStr;
+
+use cli::{self, Args};
+use expr;
+use json::{ToJson, Value};
+use progress;
+
+pub const SCHEMA: &str = "rusty.v1";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Human,
+    Json,
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Format, String> {
+        match s {
+            "human" => Ok(Format::Human),
+            "json" => Ok(Format::Json),
+            _ => Err("expected `human` or `json`".to_string()),
+        }
+    }
+}
+
+pub struct Output {
+    bin: String,
+    format: Format,
+    writer: Box<dyn Write>,
+    // whether the writer is a terminal, which can redraw lines in place.
+    tty: bool,
+    aborted: bool,
+}
+
+impl Output {
+    pub fn new(bin: &str, format: Format) -> Output {
+        let mut out = Output::with_writer(bin, format, Box::new(io::stdout()));
+        out.tty = progress::stdout_is_tty();
+        out
+    }
+
+    pub fn with_writer(bin: &str, format: Format, writer: Box<dyn Write>) -> Output {
+        let mut out = Output {
+            bin: bin.to_string(),
+            format,
+            writer,
+            tty: false,
+            aborted: false,
+        };
+        out.event("start", vec![]);
+        out
+    }
+
+    // for binaries that take no options but --format, reads it from the
+    // command line, and exits with the usage text on anything else.
+    pub fn from_env(bin: &str) -> Output {
+        let usage = format!("usage: {} [--format human|json]", bin);
+        let mut args = Args::from_env();
+        let format = args
+            .format()
+            .unwrap_or_else(|e| cli::exit_with_usage(&e, &usage));
+        args.finish()
+            .unwrap_or_else(|e| cli::exit_with_usage(&e, &usage));
+        Output::new(bin, format)
+    }
+
+    pub fn format(&self) -> Format {
+        self.format
+    }
+
+    pub fn is_json(&self) -> bool {
+        self.format == Format::Json
+    }
+
+    pub fn is_tty(&self) -> bool {
+        self.tty
+    }
+
+    // prose, only shown to humans, use the out! and outln! macros rather
+    // than calling these directly, just like print! and println!.
+    pub fn print(&mut self, args: fmt::Arguments) {
+        if self.format == Format::Human {
+            self.writer
+                .write_fmt(args)
+                .and_then(|_| self.writer.flush())
+                .expect("failed to write output");
+        }
+    }
+
+    pub fn println(&mut self, args: fmt::Arguments) {
+        if self.format == Format::Human {
+            writeln!(self.writer, "{}", args).expect("failed to write output");
+        }
+    }
+
+    // a structured event, only shown to machines.
+    pub fn event(&mut self, event: &str, fields: Vec<(&str, Value)>) {
+        if self.format != Format::Json {
+            return;
+        }
+        let mut all = vec![
+            ("schema", Value::Str(SCHEMA.to_string())),
+            ("bin", Value::Str(self.bin.clone())),
+            ("event", Value::Str(event.to_string())),
+        ];
+        all.extend(fields);
+        writeln!(self.writer, "{}", Value::object(all)).expect("failed to write output");
+    }
+
+    // a named value computed by the lesson, along with its rust type.
+    pub fn value<T: ToJson + ?Sized>(&mut self, name: &str, value: &T) {
+        if self.format != Format::Json {
+            return;
+        }
+        self.event(
+            "value",
+            vec![
+                ("name", Value::Str(name.to_string())),
+                ("type", Value::Str(value.rust_type())),
+                ("value", value.to_json()),
+            ],
+        );
+    }
+
+    // input that could not be evaluated, for humans the input is shown with
+    // a caret pointing at the problem, then the hint is printed.
+    pub fn invalid_input(&mut self, input: &str, error: &expr::Error, hint: &str) {
+        self.println(format_args!("{}", error.render(input)));
+        self.println(format_args!("{}", hint));
+        self.event(
+            "invalid_input",
+            vec![
+                ("input", Value::Str(input.to_string())),
+                ("message", Value::Str(error.message.clone())),
+                ("column", Value::UInt(input[..error.span.start].chars().count() as u128 + 1)),
+            ],
+        );
+    }
+
+    // stdin was closed while we were still waiting for input, there is no
+    // point in asking again, so we give up, and finish will exit with a
+    // failing exit code.
+    pub fn end_of_input(&mut self) {
+        self.println(format_args!("no more input, giving up!"));
+        self.event("aborted", vec![("reason", Value::Str("end of input".to_string()))]);
+        self.aborted = true;
+    }
+
+    // something went wrong that the lesson can't go on from, it's reported
+    // like everything else, as prose or as an "error" event, and finish will
+    // exit with a failing exit code.
+    pub fn error(&mut self, message: &str) {
+        self.println(format_args!("error: {}", message));
+        self.event("error", vec![("message", Value::Str(message.to_string()))]);
+        self.aborted = true;
+    }
+
+    pub fn aborted(&self) -> bool {
+        self.aborted
+    }
+
+    pub fn finish(mut self) {
+        self.event("end", vec![]);
+        if self.aborted {
+            process::exit(1);
+        }
+    }
+}
+
+// a writer that keeps everything written to it, for running a lesson and
+// looking at its output afterwards, e.g. in tests. it's cheap to clone, and
+// all clones share the same buffer.
+#[derive(Clone, Default)]
+pub struct Capture {
+    buffer: Rc<RefCell<Vec<u8>>>,
+}
+
+impl Capture {
+    pub fn new() -> Capture {
+        Capture::default()
+    }
+
+    pub fn contents(&self) -> String {
+        String::from_utf8_lossy(&self.buffer.borrow()).into_owned()
+    }
+
+    // takes everything written so far, leaving the buffer empty.
+    pub fn take(&self) -> String {
+        let bytes = self.buffer.replace(Vec::new());
+        String::from_utf8_lossy(&bytes).into_owned()
+    }
+}
+
+impl Write for Capture {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.buffer.borrow_mut().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl Args {
+    // "--format human" or "--format json", human if not given.
+    pub fn format(&mut self) -> Result<Format, String> {
+        self.value("--format").map(|format| format.unwrap_or(Format::Human))
+    }
+}
+
+#[macro_export]
+macro_rules! out {
+    ($out:expr, $($arg:tt)*) => {
+        $out.print(format_args!($($arg)*))
+    };
+}
+
+#[macro_export]
+macro_rules! outln {
+    ($out:expr) => {
+        $out.println(format_args!(""))
+    };
+    ($out:expr, $($arg:tt)*) => {
+        $out.println(format_args!($($arg)*))
+    };
+}
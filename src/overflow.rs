@@ -0,0 +1,375 @@
+// integer overflow, up close
+
+// the datatypes lesson says that an integer that overflows panics in a
+// debug build, and wraps around in a release build, this is where that's
+// shown. for any of the integer types, and any of the operations that can
+// overflow, it works out what each of the ways rust offers of doing it
+// gives, checked, wrapping, saturating and overflowing, side by side.
+
+// it also runs the plain operator, like a + b, catching the panic if there
+// is one, so what this build does is shown for real, what the other kind of
+// build would do is worked out from the same rules rustc follows, a release
+// build wraps around, except for division, which panics either way.
+
+use json::{ToJson, Value};
+use panics;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Op {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Rem,
+    Shl,
+    Shr,
+    Pow,
+    Neg,
+}
+
+const OPS: [Op; 9] = [Op::Add, Op::Sub, Op::Mul, Op::Div, Op::Rem, Op::Shl, Op::Shr, Op::Pow, Op::Neg];
+
+impl Op {
+    // how it's typed in, "neg" and "pow" are methods, or close enough.
+    pub fn symbol(self) -> &'static str {
+        match self {
+            Op::Add => "+",
+            Op::Sub => "-",
+            Op::Mul => "*",
+            Op::Div => "/",
+            Op::Rem => "%",
+            Op::Shl => "<<",
+            Op::Shr => ">>",
+            Op::Pow => "pow",
+            Op::Neg => "neg",
+        }
+    }
+
+    // the end of the methods' names, like checked_add.
+    pub fn method(self) -> &'static str {
+        match self {
+            Op::Add => "add",
+            Op::Sub => "sub",
+            Op::Mul => "mul",
+            Op::Div => "div",
+            Op::Rem => "rem",
+            Op::Shl => "shl",
+            Op::Shr => "shr",
+            Op::Pow => "pow",
+            Op::Neg => "neg",
+        }
+    }
+
+    // what a debug build panics with when it overflows, pow multiplies
+    // its way there, so that's what it says.
+    fn overflow_message(self) -> &'static str {
+        match self {
+            Op::Add => "attempt to add with overflow",
+            Op::Sub => "attempt to subtract with overflow",
+            Op::Mul | Op::Pow => "attempt to multiply with overflow",
+            Op::Div => "attempt to divide with overflow",
+            Op::Rem => "attempt to calculate the remainder with overflow",
+            Op::Shl => "attempt to shift left with overflow",
+            Op::Shr => "attempt to shift right with overflow",
+            Op::Neg => "attempt to negate with overflow",
+        }
+    }
+}
+
+pub const TYPES: &[&str] = &[
+    "i8", "i16", "i32", "i64", "i128", "isize", "u8", "u16", "u32", "u64", "u128", "usize",
+];
+
+// the results are values, or, when something panics, or can't be written
+// at all, a sentence saying so.
+pub type Outcome = Result<Value, String>;
+
+pub struct Report {
+    pub ty: &'static str,
+    pub op: Op,
+    pub lhs: Value,
+    pub rhs: Option<Value>,
+    // checked_<op>, which is Null for None.
+    pub checked: Outcome,
+    pub wrapping: Outcome,
+    // there's no saturating_rem, or saturating_shl, and no saturating_neg
+    // for unsigned types.
+    pub saturating: Option<Outcome>,
+    // overflowing_<op>, the wrapped value, and whether it overflowed.
+    pub overflowing: Result<(Value, bool), String>,
+    // the plain operator, in this build, and in both kinds of build.
+    pub plain: Outcome,
+    pub debug: Outcome,
+    pub release: Outcome,
+}
+
+impl Report {
+    // like the operation would be written in rust, "250u8 + 10".
+    pub fn expression(&self) -> String {
+        let rhs = self.rhs.as_ref().map(|rhs| rhs.to_string()).unwrap_or_default();
+        match self.op {
+            Op::Neg => format!("-({}{})", self.lhs, self.ty),
+            Op::Pow => format!("{}{}.pow({})", self.lhs, self.ty, rhs),
+            op => format!("{}{} {} {}", self.lhs, self.ty, op.symbol(), rhs),
+        }
+    }
+
+    // the kind of build this is, whose column was run rather than worked out.
+    pub fn build() -> &'static str {
+        if cfg!(debug_assertions) {
+            "debug"
+        } else {
+            "release"
+        }
+    }
+}
+
+// runs f, and if it panics, catches it, quietly, see panics.rs.
+pub fn catch<F: FnOnce() -> Value>(f: F) -> Outcome {
+    panics::catch(f).map_err(|message| format!("panics, {}", message))
+}
+
+// what the plain operator does in a debug build, where every overflow is
+// checked, going by what checked_<op> said.
+fn debug_rule(op: Op, checked: &Outcome, rhs_is_zero: bool) -> Outcome {
+    match *checked {
+        Ok(Value::Null) => Err(format!("panics, {}", division_by_zero(op, rhs_is_zero).unwrap_or_else(|| op.overflow_message()))),
+        ref other => other.clone(),
+    }
+}
+
+// and in a release build, where overflows wrap around, except that
+// division still panics, whether by zero, or MIN by -1.
+fn release_rule(op: Op, checked: &Outcome, wrapping: &Outcome, rhs_is_zero: bool) -> Outcome {
+    match op {
+        Op::Div | Op::Rem => debug_rule(op, checked, rhs_is_zero),
+        _ => wrapping.clone(),
+    }
+}
+
+fn division_by_zero(op: Op, rhs_is_zero: bool) -> Option<&'static str> {
+    match op {
+        Op::Div if rhs_is_zero => Some("attempt to divide by zero"),
+        Op::Rem if rhs_is_zero => Some("attempt to calculate the remainder with a divisor of zero"),
+        _ => None,
+    }
+}
+
+// the number, of the given type, "1_000" is fine, like in rust.
+fn number<T: ::std::str::FromStr>(text: &str, ty: &str, min: &str, max: &str) -> Result<T, String> {
+    text.replace('_', "")
+        .parse()
+        .map_err(|_| format!("`{}` isn't a `{}`, which goes from {} to {}", text, ty, min, max))
+}
+
+// one report per operation, every integer type gets its own explorer, as
+// the methods are the same, but the types aren't. negation is the odd one
+// out, an unsigned value can't be negated with "-" at all.
+macro_rules! report {
+    ($t:ident, $op:expr, $a:expr, $b:expr, $rhs:expr, $checked:ident, $wrapping:ident, $overflowing:ident,
+     $plain:expr, $saturating:expr) => {{
+        let (a, b) = ($a, $b);
+        let checked = catch(|| a.$checked(b).map_or(Value::Null, |n| n.to_json()));
+        let wrapping = catch(|| a.$wrapping(b).to_json());
+        let overflowing = catch(|| {
+            let (n, overflowed) = a.$overflowing(b);
+            Value::Array(vec![n.to_json(), Value::Bool(overflowed)])
+        });
+        Report {
+            ty: stringify!($t),
+            op: $op,
+            lhs: a.to_json(),
+            rhs: $rhs,
+            checked,
+            wrapping,
+            saturating: $saturating,
+            overflowing: overflowing.map(|pair| match pair {
+                Value::Array(mut pair) => (pair.remove(0), pair[0] == Value::Bool(true)),
+                _ => unreachable!(),
+            }),
+            plain: catch($plain),
+            debug: Ok(Value::Null),
+            release: Ok(Value::Null),
+        }
+    }};
+}
+
+macro_rules! explorer {
+    ($name:ident, $t:ident, $neg:ident) => {
+        fn $name(op: Op, lhs: &str, rhs: Option<&str>) -> Result<Report, String> {
+            let (min, max) = ($t::MIN.to_string(), $t::MAX.to_string());
+            let a: $t = number(lhs, stringify!($t), &min, &max)?;
+            let rhs = rhs.unwrap_or("0");
+
+            let report = match op {
+                Op::Shl | Op::Shr | Op::Pow => {
+                    let b: u32 = number(rhs, "u32", "0", &u32::MAX.to_string())?;
+                    let r = Some(b.to_json());
+                    match op {
+                        Op::Shl => report!($t, op, a, b, r, checked_shl, wrapping_shl, overflowing_shl,
+                                           || (a << b).to_json(), None),
+                        Op::Shr => report!($t, op, a, b, r, checked_shr, wrapping_shr, overflowing_shr,
+                                           || (a >> b).to_json(), None),
+                        _ => report!($t, op, a, b, r, checked_pow, wrapping_pow, overflowing_pow,
+                                     || a.pow(b).to_json(), Some(catch(|| a.saturating_pow(b).to_json()))),
+                    }
+                }
+                Op::Neg => explorer!(@$neg $t, a),
+                _ => {
+                    let b: $t = number(rhs, stringify!($t), &min, &max)?;
+                    let r = Some(b.to_json());
+                    match op {
+                        Op::Add => report!($t, op, a, b, r, checked_add, wrapping_add, overflowing_add,
+                                           || (a + b).to_json(), Some(catch(|| a.saturating_add(b).to_json()))),
+                        Op::Sub => report!($t, op, a, b, r, checked_sub, wrapping_sub, overflowing_sub,
+                                           || (a - b).to_json(), Some(catch(|| a.saturating_sub(b).to_json()))),
+                        Op::Mul => report!($t, op, a, b, r, checked_mul, wrapping_mul, overflowing_mul,
+                                           || (a * b).to_json(), Some(catch(|| a.saturating_mul(b).to_json()))),
+                        Op::Div => report!($t, op, a, b, r, checked_div, wrapping_div, overflowing_div,
+                                           || (a / b).to_json(), Some(catch(|| a.saturating_div(b).to_json()))),
+                        _ => report!($t, op, a, b, r, checked_rem, wrapping_rem, overflowing_rem,
+                                     || (a % b).to_json(), None),
+                    }
+                }
+            };
+            Ok(finish(report, rhs.trim_start_matches('-').trim_matches(|c| c == '0' || c == '_').is_empty()))
+        }
+    };
+    // the methods that take no argument, given a unit one, so report! can
+    // call them the same way as the others.
+    (@signed $t:ident, $a:expr) => {{
+        let a = Neg($a);
+        report!($t, Op::Neg, a, (), None, checked, wrapping, overflowing,
+                || (-a.0).to_json(), Some(catch(|| a.0.saturating_neg().to_json())))
+    }};
+    (@unsigned $t:ident, $a:expr) => {{
+        let a = Neg($a);
+        let mut report = report!($t, Op::Neg, a, (), None, checked, wrapping, overflowing, || Value::Null, None);
+        report.plain = Err("doesn't compile, error[E0600]: cannot apply unary operator `-` to an unsigned type".to_string());
+        report
+    }};
+}
+
+// negation, dressed up to look like the other operations, a.checked(())
+// is a.0.checked_neg().
+#[derive(Clone, Copy)]
+struct Neg<T>(T);
+
+macro_rules! neg {
+    ($($t:ident),*) => {
+        $(
+            impl Neg<$t> {
+                fn checked(&self, _: ()) -> Option<$t> {
+                    self.0.checked_neg()
+                }
+                fn wrapping(&self, _: ()) -> $t {
+                    self.0.wrapping_neg()
+                }
+                fn overflowing(&self, _: ()) -> ($t, bool) {
+                    self.0.overflowing_neg()
+                }
+            }
+            impl ToJson for Neg<$t> {
+                fn to_json(&self) -> Value {
+                    self.0.to_json()
+                }
+            }
+        )*
+    };
+}
+
+neg!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
+
+explorer!(explore_i8, i8, signed);
+explorer!(explore_i16, i16, signed);
+explorer!(explore_i32, i32, signed);
+explorer!(explore_i64, i64, signed);
+explorer!(explore_i128, i128, signed);
+explorer!(explore_isize, isize, signed);
+explorer!(explore_u8, u8, unsigned);
+explorer!(explore_u16, u16, unsigned);
+explorer!(explore_u32, u32, unsigned);
+explorer!(explore_u64, u64, unsigned);
+explorer!(explore_u128, u128, unsigned);
+explorer!(explore_usize, usize, unsigned);
+
+// fills in the debug and release columns, the one for this build is what
+// actually happened, the other one follows the rules.
+fn finish(mut report: Report, rhs_is_zero: bool) -> Report {
+    let debug = debug_rule(report.op, &report.checked, rhs_is_zero);
+    let release = release_rule(report.op, &report.checked, &report.wrapping, rhs_is_zero);
+    let compiles = !report.plain.as_ref().err().is_some_and(|e| e.starts_with("doesn't compile"));
+
+    let (debug, release) = match (compiles, cfg!(debug_assertions)) {
+        (false, _) => (report.plain.clone(), report.plain.clone()),
+        (true, true) => (report.plain.clone(), release),
+        (true, false) => (debug, report.plain.clone()),
+    };
+    report.debug = debug;
+    report.release = release;
+    report
+}
+
+// the type a number is written as, "250u8" is a u8, and "250" an i32,
+// like in rust, returns the number without its suffix.
+fn split_suffix(text: &str) -> (&str, Option<&'static str>) {
+    for ty in TYPES {
+        if text.len() > ty.len() && text.ends_with(ty) {
+            let number = &text[..text.len() - ty.len()];
+            if number.ends_with(|c: char| c.is_ascii_digit() || c == '_') {
+                return (number.trim_end_matches('_'), Some(ty));
+            }
+        }
+    }
+    (text, None)
+}
+
+pub const EXAMPLES: &str = "`250u8 + 10`, `-128i8 / -1`, `1u8 << 9`, `2i32 pow 31` or `neg -128i8`";
+
+// reads an operation, like "250u8 + 10", or "neg -128i8", and explores it.
+pub fn explore(line: &str) -> Result<Report, String> {
+    let words: Vec<&str> = line.split_whitespace().collect();
+    let (op, lhs, rhs) = match words[..] {
+        ["neg", operand] => (Op::Neg, operand, None),
+        [lhs, symbol, rhs] => match OPS.iter().find(|op| op.symbol() == symbol && **op != Op::Neg) {
+            Some(&op) => (op, lhs, Some(rhs)),
+            None => return Err(format!("there's no `{}` operation, try one of + - * / % << >> pow", symbol)),
+        },
+        _ => return Err(format!("expected an operation, like {}", EXAMPLES)),
+    };
+
+    let (lhs, ty) = split_suffix(lhs);
+    let ty = ty.unwrap_or("i32");
+    // the right side can have a suffix too, as long as it's the right type,
+    // the same as the left side's, or the u32 shifts and pow take.
+    let rhs = match rhs.map(split_suffix) {
+        Some((rhs, Some(suffix))) => {
+            let expected = match op {
+                Op::Shl | Op::Shr | Op::Pow => "u32",
+                _ => ty,
+            };
+            if suffix != expected {
+                return Err(format!("mismatched types, the right side of `{}` has to be `{}`, not `{}`", op.symbol(), expected, suffix));
+            }
+            Some(rhs)
+        }
+        Some((rhs, None)) => Some(rhs),
+        None => None,
+    };
+
+    let explore = match ty {
+        "i8" => explore_i8,
+        "i16" => explore_i16,
+        "i32" => explore_i32,
+        "i64" => explore_i64,
+        "i128" => explore_i128,
+        "isize" => explore_isize,
+        "u8" => explore_u8,
+        "u16" => explore_u16,
+        "u32" => explore_u32,
+        "u64" => explore_u64,
+        "u128" => explore_u128,
+        _ => explore_usize,
+    };
+    explore(op, lhs, rhs)
+}
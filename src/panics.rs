@@ -0,0 +1,58 @@
+// catching panics
+
+// the overflow explorer runs a + b to see whether it panics, and the
+// references lesson borrows a RefCell twice to show that it does, neither
+// wants the panic to take the program down, or to print its message, the
+// way a panic usually does, they say what happened in their own words.
+
+// printing the message is up to the panic hook, which is shared by every
+// thread, so it isn't swapped for a silent one while catching, a thread
+// catching a panic would silence every other thread's panics too, and two
+// of them at once could put each other's hooks back. instead, the first
+// catch puts a hook in front of the one there was, once, under a lock,
+// which stays quiet only on the threads that are catching right then.
+
+use std::any::Any;
+use std::cell::Cell;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::Once;
+
+static QUIET_HOOK: Once = Once::new();
+
+thread_local! {
+    // whether this thread is inside catch().
+    static CATCHING: Cell<bool> = const { Cell::new(false) };
+}
+
+fn install_quiet_hook() {
+    QUIET_HOOK.call_once(|| {
+        let hook = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            if !CATCHING.with(|catching| catching.get()) {
+                hook(info);
+            }
+        }));
+    });
+}
+
+// what a panic said, panic!("..") with a plain string, or with arguments.
+pub fn message(payload: Box<dyn Any + Send>) -> String {
+    match payload.downcast::<String>() {
+        Ok(message) => *message,
+        Err(payload) => match payload.downcast::<&'static str>() {
+            Ok(message) => message.to_string(),
+            Err(_) => "a panic without a message".to_string(),
+        },
+    }
+}
+
+// runs f, and if it panics, hands back the panic's message, instead of
+// letting it take everything down. the panic isn't printed, the caller
+// gets to explain it instead.
+pub fn catch<R, F: FnOnce() -> R>(f: F) -> Result<R, String> {
+    install_quiet_hook();
+    let was = CATCHING.with(|catching| catching.replace(true));
+    let result = panic::catch_unwind(AssertUnwindSafe(f));
+    CATCHING.with(|catching| catching.set(was));
+    result.map_err(message)
+}
@@ -0,0 +1,357 @@
+// learner profiles
+
+// what each learner has done so far, which sections of which lessons they
+// have gone through, how they did in the quizzes, and which exercises they
+// have fixed, is kept in a profile, one json file per learner, under
+// $RUSTY_HOME/profiles/<user>.json, so several people sharing a machine,
+// each with a --user of their own, don't get in each other's way.
+
+// the launcher's dashboard shows it all, and recommends the lesson to take
+// next, the first one not done yet, with all of its prerequisites done.
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use context::Context;
+use json::{self, Value};
+use lessons::{Lesson, LESSONS};
+use store;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Quiz {
+    pub last: usize,
+    pub best: usize,
+    pub total: usize,
+    pub attempts: usize,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LessonProgress {
+    pub id: String,
+    // run from start to finish at least once.
+    pub finished: bool,
+    // the titles of the sections gone through.
+    pub sections: Vec<String>,
+    pub quiz: Option<Quiz>,
+    // each exercise tried, and how far it got, "fixed", "reproduced", or
+    // "tried", only ever moving forward.
+    pub exercises: Vec<(String, String)>,
+}
+
+impl LessonProgress {
+    // run from start to finish, or every section gone through, one at a time.
+    pub fn is_done(&self, lesson: &dyn Lesson) -> bool {
+        self.finished || (!lesson.sections().is_empty() && self.sections_done(lesson) == lesson.sections().len())
+    }
+
+    pub fn sections_done(&self, lesson: &dyn Lesson) -> usize {
+        lesson
+            .sections()
+            .iter()
+            .filter(|section| self.sections.iter().any(|title| title == section.title))
+            .count()
+    }
+
+    pub fn exercises_fixed(&self) -> usize {
+        self.exercises.iter().filter(|exercise| exercise.1 == "fixed").count()
+    }
+
+    pub fn see_section(&mut self, title: &str) {
+        if !self.sections.iter().any(|seen| seen == title) {
+            self.sections.push(title.to_string());
+        }
+    }
+
+    pub fn score_quiz(&mut self, score: usize, total: usize) {
+        let quiz = self.quiz.get_or_insert_with(Quiz::default);
+        quiz.last = score;
+        quiz.best = if quiz.total == total { quiz.best.max(score) } else { score };
+        quiz.total = total;
+        quiz.attempts += 1;
+    }
+
+    // an exercise that was fixed once stays fixed, whatever happens to it later.
+    pub fn try_exercise(&mut self, name: &str, outcome: &str) {
+        let rank = |outcome: &str| match outcome {
+            "fixed" => 2,
+            "reproduced" => 1,
+            _ => 0,
+        };
+        let outcome = if rank(outcome) > 0 { outcome } else { "tried" };
+
+        match self.exercises.iter_mut().find(|exercise| exercise.0 == name) {
+            Some(exercise) => {
+                if rank(outcome) > rank(&exercise.1) {
+                    exercise.1 = outcome.to_string();
+                }
+            }
+            None => self.exercises.push((name.to_string(), outcome.to_string())),
+        }
+    }
+
+    fn to_json(&self) -> Value {
+        Value::object(vec![
+            ("id", Value::Str(self.id.clone())),
+            ("finished", Value::Bool(self.finished)),
+            ("sections", Value::Array(self.sections.iter().map(|title| Value::Str(title.clone())).collect())),
+            ("quiz", self.quiz.as_ref().map_or(Value::Null, |quiz| Value::object(vec![
+                ("last", Value::UInt(quiz.last as u128)),
+                ("best", Value::UInt(quiz.best as u128)),
+                ("total", Value::UInt(quiz.total as u128)),
+                ("attempts", Value::UInt(quiz.attempts as u128)),
+            ]))),
+            ("exercises", Value::Array(self.exercises.iter().map(|exercise| Value::object(vec![
+                ("name", Value::Str(exercise.0.clone())),
+                ("outcome", Value::Str(exercise.1.clone())),
+            ])).collect())),
+        ])
+    }
+
+    // anything missing, or of the wrong type, is taken as not done yet.
+    fn from_json(value: &Value) -> Option<LessonProgress> {
+        let number = |quiz: &Value, key| quiz.get(key).and_then(Value::as_i128).unwrap_or(0) as usize;
+
+        Some(LessonProgress {
+            id: value.get("id")?.as_str()?.to_string(),
+            finished: value.get("finished").and_then(Value::as_bool).unwrap_or(false),
+            sections: value
+                .get("sections")
+                .and_then(Value::as_array)
+                .unwrap_or(&[])
+                .iter()
+                .filter_map(Value::as_str)
+                .map(String::from)
+                .collect(),
+            quiz: value.get("quiz").filter(|quiz| **quiz != Value::Null).map(|quiz| Quiz {
+                last: number(quiz, "last"),
+                best: number(quiz, "best"),
+                total: number(quiz, "total"),
+                attempts: number(quiz, "attempts"),
+            }),
+            exercises: value
+                .get("exercises")
+                .and_then(Value::as_array)
+                .unwrap_or(&[])
+                .iter()
+                .filter_map(|exercise| {
+                    let name = exercise.get("name")?.as_str()?;
+                    let outcome = exercise.get("outcome")?.as_str()?;
+                    Some((name.to_string(), outcome.to_string()))
+                })
+                .collect(),
+        })
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Profile {
+    pub user: String,
+    pub lessons: Vec<LessonProgress>,
+}
+
+fn path(home: &Path, user: &str) -> PathBuf {
+    home.join("profiles").join(format!("{}.json", user))
+}
+
+impl Profile {
+    // the user's profile, an empty one if they haven't got one yet.
+    pub fn load(home: &Path, user: &str) -> io::Result<Profile> {
+        let text = match fs::read_to_string(path(home, user)) {
+            Ok(text) => text,
+            Err(ref e) if e.kind() == io::ErrorKind::NotFound => String::new(),
+            Err(e) => return Err(e),
+        };
+        if text.trim().is_empty() {
+            return Ok(Profile { user: user.to_string(), lessons: vec![] });
+        }
+
+        let value = json::parse(&text).map_err(|e| {
+            io::Error::new(io::ErrorKind::InvalidData, format!("{}: {}", path(home, user).display(), e))
+        })?;
+        Ok(Profile {
+            user: user.to_string(),
+            lessons: value
+                .get("lessons")
+                .and_then(Value::as_array)
+                .unwrap_or(&[])
+                .iter()
+                .filter_map(LessonProgress::from_json)
+                .collect(),
+        })
+    }
+
+    // written to a file next to it first, and then moved over it, so a
+    // profile is never left half written.
+    pub fn save(&self, home: &Path) -> io::Result<()> {
+        let path = path(home, &self.user);
+        let temp = path.with_extension("json.tmp");
+        fs::create_dir_all(path.parent().unwrap())?;
+        fs::write(&temp, format!("{}\n", self.to_json()))?;
+        fs::rename(&temp, &path)
+    }
+
+    fn to_json(&self) -> Value {
+        Value::object(vec![
+            ("user", Value::Str(self.user.clone())),
+            ("lessons", Value::Array(self.lessons.iter().map(LessonProgress::to_json).collect())),
+        ])
+    }
+
+    // the progress in a lesson, nothing done if it hasn't been started.
+    pub fn lesson(&self, id: &str) -> LessonProgress {
+        self.lessons
+            .iter()
+            .find(|lesson| lesson.id == id)
+            .cloned()
+            .unwrap_or_else(|| LessonProgress { id: id.to_string(), ..LessonProgress::default() })
+    }
+
+    pub fn lesson_mut(&mut self, id: &str) -> &mut LessonProgress {
+        match self.lessons.iter().position(|lesson| lesson.id == id) {
+            Some(i) => &mut self.lessons[i],
+            None => {
+                self.lessons.push(LessonProgress { id: id.to_string(), ..LessonProgress::default() });
+                self.lessons.last_mut().unwrap()
+            }
+        }
+    }
+
+    pub fn is_done(&self, lesson: &dyn Lesson) -> bool {
+        self.lesson(lesson.id()).is_done(lesson)
+    }
+
+    // the first lesson not done yet, with all of its prerequisites done, or
+    // None once they're all done.
+    pub fn next(&self) -> Option<&'static dyn Lesson> {
+        LESSONS.iter().cloned().find(|lesson| {
+            !self.is_done(*lesson)
+                && lesson.prerequisites().iter().all(|id| {
+                    LESSONS.iter().any(|other| other.id() == *id && self.is_done(*other))
+                })
+        })
+    }
+}
+
+// the names of everyone with a profile.
+pub fn users(home: &Path) -> Vec<String> {
+    let mut users: Vec<String> = fs::read_dir(home.join("profiles"))
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok())
+                .filter_map(|entry| entry.file_name().into_string().ok())
+                .filter_map(|name| name.strip_suffix(".json").map(String::from))
+                .filter(|name| store::check_user(name).is_ok())
+                .collect()
+        })
+        .unwrap_or_default();
+    users.sort();
+    users
+}
+
+// updates the profile of the context's user in the lesson, if there is
+// somewhere to keep it, failing to save is reported, but doesn't stop the
+// lesson.
+pub fn record<F: FnOnce(&mut LessonProgress)>(ctx: &Context, lesson: &dyn Lesson, update: F) {
+    let home = match ctx.home {
+        Some(ref home) => home,
+        None => return,
+    };
+    let user = ctx.user();
+    let saved = Profile::load(home, &user).and_then(|mut profile| {
+        update(profile.lesson_mut(lesson.id()));
+        profile.save(home)
+    });
+    if let Err(e) = saved {
+        eprintln!("failed to save {}'s progress: {}", user, e);
+    }
+}
+
+// the context user's profile, an empty one if there's nowhere it's kept,
+// or it can't be read, which is reported.
+pub fn load(ctx: &Context) -> Profile {
+    let user = ctx.user();
+    let loaded = match ctx.home {
+        Some(ref home) => Profile::load(home, &user),
+        None => Ok(Profile::default()),
+    };
+    loaded.unwrap_or_else(|e| {
+        eprintln!("failed to read {}'s progress: {}", user, e);
+        Profile::default()
+    })
+}
+
+// where the user is at in every lesson, and what to take next.
+pub fn dashboard(ctx: &mut Context) {
+    let profile = load(ctx);
+
+    let user = ctx.user();
+    outln!(ctx, "{}'s progress\n", user);
+    outln!(ctx, "  {:<14} {:<5} {:<9} {:<6} {}", "lesson", "done", "sections", "quiz", "exercises fixed");
+    for lesson in LESSONS.iter() {
+        let progress = profile.lesson(lesson.id());
+        let done = progress.is_done(*lesson);
+        let sections = format!("{}/{}", progress.sections_done(*lesson), lesson.sections().len());
+        let quiz = progress.quiz.as_ref().map_or("-".to_string(), |quiz| format!("{}/{}", quiz.best, quiz.total));
+        let exercises = if lesson.exercises().is_empty() {
+            "-".to_string()
+        } else {
+            format!("{}/{}", progress.exercises_fixed(), lesson.exercises().len())
+        };
+
+        outln!(ctx, "  {:<14} {:<5} {:<9} {:<6} {}", lesson.id(), if done { "yes" } else { "no" }, sections, quiz, exercises);
+        ctx.event("progress", vec![
+            ("lesson", Value::Str(lesson.id().to_string())),
+            ("done", Value::Bool(done)),
+            ("sections_done", Value::UInt(progress.sections_done(*lesson) as u128)),
+            ("sections", Value::UInt(lesson.sections().len() as u128)),
+            ("quiz_best", progress.quiz.as_ref().map_or(Value::Null, |quiz| Value::UInt(quiz.best as u128))),
+            ("quiz_total", progress.quiz.as_ref().map_or(Value::Null, |quiz| Value::UInt(quiz.total as u128))),
+            ("quiz_attempts", Value::UInt(progress.quiz.as_ref().map_or(0, |quiz| quiz.attempts) as u128)),
+            ("exercises_fixed", Value::UInt(progress.exercises_fixed() as u128)),
+            ("exercises", Value::UInt(lesson.exercises().len() as u128)),
+        ]);
+    }
+    outln!(ctx);
+    next_up(ctx, &profile);
+}
+
+// recommends the next lesson.
+pub fn next_up(ctx: &mut Context, profile: &Profile) {
+    let next = profile.next();
+    let user = ctx.user();
+    match next {
+        Some(lesson) => outln!(ctx, "next up: {}, {}", lesson.id(), lesson.title()),
+        None if LESSONS.iter().all(|lesson| profile.is_done(*lesson)) => {
+            outln!(ctx, "all the lessons are done, well done!")
+        }
+        // the lessons left all need something that isn't done, which only
+        // happens if the prerequisites go round in circles.
+        None => outln!(ctx, "nothing to take next, the prerequisites of the lessons left aren't done"),
+    }
+    ctx.event("next", vec![
+        ("user", Value::Str(user)),
+        ("lesson", next.map_or(Value::Null, |lesson| Value::Str(lesson.id().to_string()))),
+    ]);
+}
+
+// everyone with a profile, the current user marked with a *.
+pub fn profiles(ctx: &mut Context) {
+    let users = match ctx.home {
+        Some(ref home) => users(home),
+        None => vec![],
+    };
+    let current = ctx.user();
+
+    if users.is_empty() {
+        outln!(ctx, "there are no profiles yet, they are made as lessons are taken.");
+    }
+    for user in users {
+        let marker = if user == current { "*" } else { " " };
+        outln!(ctx, "{} {}", marker, user);
+        ctx.event("profile", vec![
+            ("user", Value::Str(user.clone())),
+            ("current", Value::Bool(user == current)),
+        ]);
+    }
+}
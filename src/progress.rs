@@ -0,0 +1,148 @@
+// progress reporting for long loops
+
+// printing a line for every single step of a loop floods the terminal, and
+// slows the loop down to the speed of the terminal. a progress reporter
+// keeps count of the steps instead, and every so often reports how far
+// along we are, how fast it's going, and how long it will probably take.
+
+// when the output is a terminal, the report is redrawn in place, on one
+// line, using "\r" to jump back to its start. when the output is piped into
+// a file or another program, "\r" would only make a mess, so instead a plain
+// line is printed each time another tenth of the work is done.
+
+use std::fmt::Display;
+use std::time::{Duration, Instant};
+
+use libc;
+
+use cli::Verbosity;
+use output::Output;
+
+// how often the in-place report is redrawn on a terminal.
+const REDRAW_EVERY: Duration = Duration::from_millis(100);
+
+const BAR_WIDTH: u64 = 20;
+
+pub struct Progress {
+    label: String,
+    total: u64,
+    done: u64,
+    verbosity: Verbosity,
+    tty: bool,
+    start: Instant,
+    last_draw: Option<Instant>,
+    // the last tenth reported when piped, 0 to 10.
+    reported: u64,
+}
+
+// true if stdout is a terminal, rather than a pipe or a file.
+pub fn stdout_is_tty() -> bool {
+    unsafe { libc::isatty(libc::STDOUT_FILENO) == 1 }
+}
+
+impl Progress {
+    // the report goes to out, and is redrawn in place if out is a terminal,
+    // in json mode the report is left out, it's prose, and not a result.
+    pub fn new(out: &Output, label: &str, total: u64, verbosity: Verbosity) -> Progress {
+        Progress {
+            label: label.to_string(),
+            total,
+            done: 0,
+            verbosity: if out.is_json() { Verbosity::Quiet } else { verbosity },
+            tty: out.is_tty(),
+            start: Instant::now(),
+            last_draw: None,
+            reported: 0,
+        }
+    }
+
+    // one more step is done, in verbose mode every step is printed, using the
+    // detail given, otherwise the detail is only built if it's needed.
+    pub fn inc<D: Display>(&mut self, out: &mut Output, detail: D) {
+        self.done += 1;
+        match self.verbosity {
+            Verbosity::Verbose => outln!(out, "{}", detail),
+            Verbosity::Normal => self.update(out),
+            Verbosity::Quiet => {}
+        }
+    }
+
+    // jumps to an absolute number of finished steps, for when the work is
+    // done elsewhere, e.g. by worker threads, and only the count is known.
+    pub fn set(&mut self, out: &mut Output, done: u64) {
+        self.done = done.min(self.total);
+        if self.verbosity == Verbosity::Normal {
+            self.update(out);
+        }
+    }
+
+    pub fn finish(mut self, out: &mut Output) {
+        if self.verbosity != Verbosity::Normal {
+            return;
+        }
+        self.done = self.total;
+        if self.tty {
+            self.redraw(out);
+            outln!(out);
+        } else {
+            self.update(out);
+        }
+    }
+
+    fn update(&mut self, out: &mut Output) {
+        if self.tty {
+            let due = match self.last_draw {
+                Some(at) => at.elapsed() >= REDRAW_EVERY,
+                None => true,
+            };
+            if due {
+                self.redraw(out);
+            }
+        } else {
+            // every tenth that was crossed gets its own line, even if several
+            // were crossed at once, that way the lines printed are always the
+            // same, no matter how the steps were counted. for short loops a
+            // tenth may be less than a step, those tenths are skipped.
+            let tenth = (self.done * 10).checked_div(self.total).unwrap_or(10);
+            while self.reported < tenth {
+                let before = (self.total * self.reported).div_ceil(10);
+                self.reported += 1;
+                let done = (self.total * self.reported).div_ceil(10);
+                if done != before {
+                    outln!(out, "{}: {}", self.label, self.status(done * 100 / self.total, done));
+                }
+            }
+        }
+    }
+
+    fn redraw(&mut self, out: &mut Output) {
+        let percent = self.percent();
+        let filled = percent * BAR_WIDTH / 100;
+        out!(
+            out,
+            "\r{}: [{}{}] {}",
+            self.label,
+            "#".repeat(filled as usize),
+            ".".repeat((BAR_WIDTH - filled) as usize),
+            self.status(percent, self.done)
+        );
+        self.last_draw = Some(Instant::now());
+    }
+
+    fn percent(&self) -> u64 {
+        (self.done * 100).checked_div(self.total).unwrap_or(100)
+    }
+
+    // e.g. "50% (500/1000), 2000/s, eta 0.3s"
+    fn status(&self, percent: u64, done: u64) -> String {
+        let elapsed = self.start.elapsed().as_secs_f64();
+        let rate = if elapsed > 0.0 { done as f64 / elapsed } else { 0.0 };
+        let eta = if rate > 0.0 {
+            format!("{:.1}s", (self.total - done) as f64 / rate)
+        } else {
+            "?".to_string()
+        };
+
+        format!("{}% ({}/{}), {:.0}/s, eta {}", percent, done, self.total, rate, eta)
+    }
+}
@@ -1,5 +1,132 @@
-//variables
+//rust commons
 
-fn main(){
-    println!("rusty_commons");
-}
\ No newline at end of file
+// the launcher for all the rust_commons lessons, "rust_commons variables"
+// runs the variables lesson, "rust_commons list" lists them all, and with
+// no lesson given, a menu asks which one to run. "rust_commons dashboard"
+// shows how far along we are, see profile.rs, and "rust_commons export"
+// prints all the lessons as a book, see export.rs.
+
+#[macro_use]
+extern crate rusty;
+
+use rusty::cli::{self, Args};
+use rusty::context::{Context, Options, OPTIONS};
+use rusty::json::Value;
+use rusty::lessons::{self, export, Lesson, LESSONS};
+use rusty::profile;
+
+// counts what goes on on the heap, for the ownership lesson, see heap.rs.
+#[global_allocator]
+static COUNTING: rusty::heap::Counting = rusty::heap::Counting;
+
+fn usage() -> String {
+    format!(
+        "usage: rust_commons [options]                    pick a lesson from a menu
+       rust_commons <lesson> [command] [options]  run a lesson
+       rust_commons list                          list the lessons
+       rust_commons dashboard                     show our progress, and what to take next
+       rust_commons profiles                      list the learners with a profile
+       rust_commons export [markdown|html]        print all the lessons as a book
+
+{}",
+        OPTIONS
+    )
+}
+
+fn main() {
+    let usage = usage();
+    let mut args = Args::from_env();
+    let format = args.format().unwrap_or_else(|e| cli::exit_with_usage(&e, &usage));
+    // the options go first, so in "rust_commons --user bob", bob isn't
+    // taken for a lesson.
+    let mut options = Options::read(&mut args).unwrap_or_else(|e| cli::exit_with_usage(&e, &usage));
+    let target = args.positional();
+
+    // a lesson may have commands of its own, like "controlflow bench".
+    let lesson = match target.as_deref() {
+        None | Some("list") | Some("dashboard") | Some("profiles") | Some("export") => None,
+        Some(id) => match lessons::find(id) {
+            Some(lesson) => Some(lesson),
+            None => cli::exit_with_usage(&format!("no lesson called `{}`", id), &usage),
+        },
+    };
+    let commands = match (target.as_deref(), lesson) {
+        (Some("export"), _) => vec!["markdown", "html"],
+        (_, Some(lesson)) => lessons::commands(lesson),
+        (_, None) => vec![],
+    };
+    options.command = args.positional();
+    options.check_command(&commands).unwrap_or_else(|e| cli::exit_with_usage(&e, &usage));
+    args.finish().unwrap_or_else(|e| cli::exit_with_usage(&e, &usage));
+
+    let mut ctx = Context::stdio("rust_commons", format, options);
+
+    match (target.as_deref(), lesson) {
+        (Some("list"), _) => list(&mut ctx),
+        (Some("dashboard"), _) => profile::dashboard(&mut ctx),
+        (Some("profiles"), _) => profile::profiles(&mut ctx),
+        (Some("export"), _) => export::run(&mut ctx, LESSONS),
+        (_, Some(lesson)) => run(&mut ctx, lesson),
+        (_, None) => menu(&mut ctx),
+    }
+
+    ctx.finish();
+}
+
+fn list(ctx: &mut Context) {
+    for (i, lesson) in LESSONS.iter().enumerate() {
+        outln!(ctx, "{:>3}) {:<14} {}", i + 1, lesson.id(), lesson.title());
+        ctx.event("lesson", vec![
+            ("id", Value::Str(lesson.id().to_string())),
+            ("title", Value::Str(lesson.title().to_string())),
+            ("summary", Value::Str(lesson.summary().to_string())),
+            ("prerequisites", Value::Array(
+                lesson.prerequisites().iter().map(|id| Value::Str(id.to_string())).collect()
+            )),
+        ]);
+    }
+}
+
+fn run(ctx: &mut Context, lesson: &dyn Lesson) {
+    ctx.event("run", vec![("lesson", Value::Str(lesson.id().to_string()))]);
+    lessons::present(ctx, lesson);
+}
+
+// keeps asking which lesson to run next, until we're told to quit, or the
+// input runs out.
+fn menu(ctx: &mut Context) {
+    loop {
+        outln!(ctx, "rusty_commons");
+        list(ctx);
+        if ctx.home.is_some() {
+            let profile = profile::load(ctx);
+            outln!(ctx);
+            profile::next_up(ctx, &profile);
+        }
+        outln!(ctx, "pick a lesson by number or name, or q to quit ..");
+
+        let mut choice = String::new();
+        let read = ctx.read_line(&mut choice)
+            .expect("failed to read line");
+
+        let choice = choice.trim();
+        if read == 0 || choice == "q" || choice == "quit" || ctx.aborted() {
+            break;
+        }
+
+        // either the number shown in the list, or the lesson's name.
+        let lesson = match choice.parse::<usize>() {
+            Ok(n) if n >= 1 => LESSONS.get(n - 1).cloned(),
+            Ok(_) => None,
+            Err(_) => lessons::find(choice),
+        };
+
+        match lesson {
+            Some(lesson) => {
+                run(ctx, lesson);
+                outln!(ctx);
+            }
+            None => outln!(ctx, "there is no lesson `{}`, try again!\n", choice),
+        }
+    }
+}
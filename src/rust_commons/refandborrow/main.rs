@@ -1,4 +1,10 @@
+// references and borrowing
 
-fn main() {
+// the lesson itself lives in src/lessons/refandborrow.rs, this binary is only a
+// shortcut for "rust_commons refandborrow".
+
+extern crate rusty;
 
-}
\ No newline at end of file
+fn main() {
+    rusty::lessons::main("refandborrow");
+}
@@ -0,0 +1,190 @@
+// asking rustc
+
+// to find out whether some code compiles, there's nothing better than the
+// compiler itself, the code is written into a crate of its own, in a temp
+// directory, and checked by whatever rustc is installed, $RUSTC if it's
+// set, the one on the PATH otherwise. with --error-format=json, rustc
+// prints each diagnostic as a line of json, which is read back in here.
+
+// some things only the compiler knows, like how it lays out a struct, for
+// those, the code is built, and run, and what it prints is read back.
+
+use std::collections::hash_map::RandomState;
+use std::env;
+use std::ffi::OsString;
+use std::fs::{self, DirBuilder};
+use std::hash::BuildHasher;
+use std::io;
+#[cfg(unix)]
+use std::os::unix::fs::DirBuilderExt;
+use std::path::{Path, PathBuf};
+use std::process::{self, Command};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use json::{self, Value};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    // "error" or "warning".
+    pub level: String,
+    // like "E0382", not every error has one.
+    pub code: Option<String>,
+    pub message: String,
+    // where the diagnostic points to, 1 based.
+    pub line: Option<usize>,
+    pub column: Option<usize>,
+}
+
+impl Diagnostic {
+    pub fn is_error(&self) -> bool {
+        self.level == "error"
+    }
+
+    // "error[E0382]: borrow of moved value: `s1`", the way rustc starts it.
+    pub fn headline(&self) -> String {
+        match self.code {
+            Some(ref code) => format!("{}[{}]: {}", self.level, code, self.message),
+            None => format!("{}: {}", self.level, self.message),
+        }
+    }
+
+    // reads one line of rustc's json output, anything that isn't an error
+    // or a warning, like the "aborting due to" summary, is left out.
+    fn from_json(line: &str) -> Option<Diagnostic> {
+        let value = json::parse(line).ok()?;
+        let level = value.get("level")?.as_str()?;
+        let message = value.get("message")?.as_str()?;
+        let spans = value.get("spans")?.as_array()?;
+        if (level != "error" && level != "warning") || message.starts_with("aborting due to") {
+            return None;
+        }
+
+        let code = value.get("code").and_then(|code| code.get("code")).and_then(Value::as_str);
+        let primary = spans
+            .iter()
+            .find(|span| span.get("is_primary").and_then(Value::as_bool) == Some(true));
+        let position = |key| primary.and_then(|span| span.get(key)).and_then(Value::as_i128).map(|n| n as usize);
+
+        Some(Diagnostic {
+            level: level.to_string(),
+            code: code.map(|code| code.to_string()),
+            message: message.to_string(),
+            line: position("line_start"),
+            column: position("column_start"),
+        })
+    }
+}
+
+fn rustc() -> OsString {
+    env::var_os("RUSTC").unwrap_or_else(|| OsString::from("rustc"))
+}
+
+// a directory of our own, in the temp directory, for a crate to be written
+// into, every check gets one, even when several run at once. the temp
+// directory is shared with everyone else on the machine, so it's always a
+// new one, made by us, that nobody else can get into. if the name is taken,
+// someone else's directory is never used, another name is tried instead.
+// it's removed when dropped, however we leave.
+struct Scratch {
+    dir: PathBuf,
+}
+
+impl Scratch {
+    fn new() -> io::Result<Scratch> {
+        static COUNT: AtomicUsize = AtomicUsize::new(0);
+        let random = RandomState::new();
+        for _ in 0..100 {
+            let count = COUNT.fetch_add(1, Ordering::SeqCst);
+            let dir = env::temp_dir().join(format!(
+                "rusty-check-{}-{}-{:016x}",
+                process::id(),
+                count,
+                random.hash_one(count)
+            ));
+            // create, not create_all, fails if anything, a directory, a file,
+            // or a symlink, is already there.
+            let mut builder = DirBuilder::new();
+            #[cfg(unix)]
+            builder.mode(0o700);
+            match builder.create(&dir) {
+                Ok(()) => return Ok(Scratch { dir }),
+                Err(ref e) if e.kind() == io::ErrorKind::AlreadyExists => continue,
+                Err(e) => return Err(e),
+            }
+        }
+        Err(io::Error::other("could not make a directory of our own in the temp directory"))
+    }
+}
+
+impl Drop for Scratch {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.dir);
+    }
+}
+
+// checks the source of a binary crate, and returns what rustc had to say
+// about it, an error means rustc could not be run at all. the code is only
+// checked, not built, and lints are turned off, only real errors count.
+pub fn check(source: &str) -> io::Result<Vec<Diagnostic>> {
+    let scratch = Scratch::new()?;
+    compile(&scratch.dir, source, &["--emit=metadata"])
+}
+
+// builds the source of a binary crate, and runs it, and returns what it
+// printed, or, when it doesn't build, the errors rustc gave.
+pub fn run(source: &str) -> io::Result<Result<String, Vec<Diagnostic>>> {
+    let scratch = Scratch::new()?;
+    let exe = match build(&scratch, source)? {
+        Ok(exe) => exe,
+        Err(diagnostics) => return Ok(Err(diagnostics)),
+    };
+    let output = Command::new(exe).current_dir(&scratch.dir).output()?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(io::Error::other(format!("the program failed: {}", stderr.trim())));
+    }
+    Ok(Ok(String::from_utf8_lossy(&output.stdout).into_owned()))
+}
+
+// builds the crate in scratch, and hands back the program rustc made, or
+// the errors it gave. the only program ever run is the one rustc was just
+// asked to make, in a directory nobody else can get into, and only when
+// rustc says it made it, as a file of its own, not a link to somewhere else.
+fn build(scratch: &Scratch, source: &str) -> io::Result<Result<PathBuf, Vec<Diagnostic>>> {
+    let diagnostics = compile(&scratch.dir, source, &[])?;
+    if diagnostics.iter().any(Diagnostic::is_error) {
+        return Ok(Err(diagnostics));
+    }
+    let exe = scratch.dir.join("target").join(format!("exercise{}", env::consts::EXE_SUFFIX));
+    if !fs::symlink_metadata(&exe)?.is_file() {
+        return Err(io::Error::other("rustc didn't make the program it was asked to"));
+    }
+    Ok(Ok(exe))
+}
+
+// writes the crate into dir, and has rustc build it, the extra args say
+// what to emit, everything ends up in dir/target.
+fn compile(dir: &Path, source: &str, args: &[&str]) -> io::Result<Vec<Diagnostic>> {
+    let main = dir.join("src").join("main.rs");
+    fs::create_dir(main.parent().unwrap())?;
+    fs::write(&main, source)?;
+
+    let output = Command::new(rustc())
+        .current_dir(dir)
+        .args(["--edition", "2021", "--crate-type", "bin", "--crate-name", "exercise"])
+        .args(["--error-format=json", "--cap-lints", "allow"])
+        .args(args)
+        .arg("--out-dir")
+        .arg(dir.join("target"))
+        .arg("src/main.rs")
+        .output()?;
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let diagnostics: Vec<Diagnostic> = stderr.lines().filter_map(Diagnostic::from_json).collect();
+
+    // rustc failing without saying why is not something we can explain.
+    if !output.status.success() && !diagnostics.iter().any(Diagnostic::is_error) {
+        return Err(io::Error::other(format!("rustc failed: {}", stderr.trim())));
+    }
+    Ok(diagnostics)
+}
@@ -0,0 +1,177 @@
+// a rust repl
+
+// type a line of rust, like "let x = 5u8;" or "(1, 2.5) .1 * 2.0", and see
+// its value, and its type. the lines are run by the small interpreter in
+// src/interp.rs, which only knows the parts of rust the lessons cover, but
+// checks them the way rustc would, so "let x: u8 = 256;" or "x = 6" on a
+// variable that isn't mut are refused, with rustc's error codes.
+
+// variables stay around from one line to the next, so they can be shadowed,
+// just like in the variables lesson. a line that isn't finished, like one
+// with an open "{", carries on to the next, a blank line gives up on it.
+
+#[macro_use]
+extern crate rusty;
+
+use rusty::cli::{self, Args};
+use rusty::context::{Context, Options};
+use rusty::interp::{Binding, Error, ErrorKind, Outcome, Session};
+use rusty::json::Value;
+
+const USAGE: &str = "usage: rusty_repl [--format human|json]";
+
+const HELP: &str = "type a line of rust, like `let x = 5;` or `x * 2`, and see its value and type
+  :type <expr>, :t   show the type of an expression, without running it
+  :vars              list the variables in scope
+  :reset             forget all the variables
+  :help              show this again
+  :quit, :q          leave, so does closing the input";
+
+fn main() {
+    let mut args = Args::from_env();
+    let format = args.format().unwrap_or_else(|e| cli::exit_with_usage(&e, USAGE));
+    args.finish().unwrap_or_else(|e| cli::exit_with_usage(&e, USAGE));
+
+    let mut ctx = Context::stdio("rusty_repl", format, Options::default());
+    let mut session = Session::new();
+
+    outln!(ctx, "rusty repl, :help for help, :quit to leave");
+
+    while let Some(input) = read(&mut ctx, &session) {
+        let line = input.trim();
+
+        if line.is_empty() {
+            continue;
+        } else if line == ":quit" || line == ":q" {
+            break;
+        } else if line == ":help" {
+            outln!(ctx, "{}", HELP);
+        } else if line == ":vars" {
+            vars(&mut ctx, &session);
+        } else if line == ":reset" {
+            session = Session::new();
+            outln!(ctx, "all the variables are gone");
+            ctx.event("reset", vec![]);
+        } else if line.starts_with(":type ") || line.starts_with(":t ") {
+            let expr = line.split_once(' ').unwrap().1.trim();
+            match session.type_of(expr) {
+                Ok(ty) => {
+                    outln!(ctx, "{}: {}", expr, ty);
+                    ctx.event("type", vec![
+                        ("input", Value::Str(expr.to_string())),
+                        ("type", Value::Str(ty.to_string())),
+                    ]);
+                }
+                Err(e) => error(&mut ctx, expr, &e),
+            }
+        } else if line.starts_with(':') {
+            outln!(ctx, "there is no command `{}`, :help lists them", line);
+        } else {
+            match session.run(&input) {
+                Ok(outcomes) => {
+                    for outcome in outcomes {
+                        show(&mut ctx, outcome);
+                    }
+                }
+                Err(e) => error(&mut ctx, &input, &e),
+            }
+        }
+    }
+
+    ctx.finish();
+}
+
+// reads a line, and the lines after it, for as long as what we have so far
+// isn't finished, None once there's no more input.
+fn read(ctx: &mut Context, session: &Session) -> Option<String> {
+    let mut input = String::new();
+    loop {
+        out!(ctx, "{}", if input.is_empty() { ">> " } else { ".. " });
+        let mut line = String::new();
+        let read = ctx.read_line(&mut line).expect("failed to read line");
+        if read == 0 {
+            outln!(ctx);
+            return if input.is_empty() { None } else { Some(input.trim_end().to_string()) };
+        }
+
+        // a blank line lets go of an unfinished one, which then shows why
+        // it wasn't finished.
+        if !input.is_empty() && line.trim().is_empty() {
+            return Some(input.trim_end().to_string());
+        }
+        input.push_str(&line);
+
+        let unfinished = !input.trim_start().starts_with(':')
+            && session.clone().run(&input).err().map(|e| e.kind) == Some(ErrorKind::Incomplete);
+        if !unfinished {
+            return Some(input.trim_end().to_string());
+        }
+    }
+}
+
+fn binding(binding: &Binding) -> Vec<(&'static str, Value)> {
+    vec![
+        ("name", Value::Str(binding.name.clone())),
+        ("type", Value::Str(binding.ty.to_string())),
+        ("mutable", Value::Bool(binding.mutable)),
+        ("value", Value::Str(binding.value.to_string())),
+    ]
+}
+
+fn show(ctx: &mut Context, outcome: Outcome) {
+    match outcome {
+        Outcome::Bound(bound, shadowed) => {
+            let mutable = if bound.mutable { "mut " } else { "" };
+            match shadowed {
+                Some(ref ty) => outln!(
+                    ctx,
+                    "{}{}: {} = {}, shadowing the earlier `{}: {}`",
+                    mutable, bound.name, bound.ty, bound.value, bound.name, ty
+                ),
+                None => outln!(ctx, "{}{}: {} = {}", mutable, bound.name, bound.ty, bound.value),
+            }
+            let mut fields = binding(&bound);
+            fields.push(("shadows", shadowed.map_or(Value::Null, |ty| Value::Str(ty.to_string()))));
+            ctx.event("binding", fields);
+        }
+        Outcome::Assigned(assigned) => {
+            outln!(ctx, "{}: {} = {}", assigned.name, assigned.ty, assigned.value);
+            ctx.event("assign", binding(&assigned));
+        }
+        Outcome::Value(ty, value) => {
+            outln!(ctx, "{}: {}", value, ty);
+            ctx.event("result", vec![
+                ("type", Value::Str(ty.to_string())),
+                ("value", Value::Str(value.to_string())),
+            ]);
+        }
+    }
+}
+
+fn vars(ctx: &mut Context, session: &Session) {
+    let variables = session.variables();
+    if variables.is_empty() {
+        outln!(ctx, "there are no variables yet");
+    }
+    for variable in variables {
+        let mutable = if variable.mutable { "mut " } else { "" };
+        outln!(ctx, "{}{}: {} = {}", mutable, variable.name, variable.ty, variable.value);
+        ctx.event("variable", binding(variable));
+    }
+}
+
+fn error(ctx: &mut Context, input: &str, e: &Error) {
+    outln!(ctx, "{}", e.render(input));
+    let start = input[..e.span.start.min(input.len())].rfind('\n').map_or(0, |i| i + 1);
+    ctx.event("error", vec![
+        ("kind", Value::Str(match e.kind {
+            ErrorKind::Incomplete => "incomplete",
+            ErrorKind::Compile => "compile",
+            ErrorKind::Panic => "panic",
+        }.to_string())),
+        ("code", e.code.map_or(Value::Null, |code| Value::Str(code.to_string()))),
+        ("message", Value::Str(e.message.clone())),
+        ("line", Value::UInt(e.line(input) as u128)),
+        ("column", Value::UInt(input[start..e.span.start.min(input.len())].chars().count() as u128 + 1)),
+    ]);
+}
@@ -0,0 +1,57 @@
+// keeping things between runs
+
+// whatever the binaries want to remember, like quiz results, is kept under
+// one directory, $RUSTY_HOME if it's set, ~/.rusty otherwise, so it's easy
+// to find, back up, or throw away, and tests can point it somewhere else.
+
+use std::env;
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+pub fn home() -> io::Result<PathBuf> {
+    if let Some(home) = env::var_os("RUSTY_HOME") {
+        return Ok(PathBuf::from(home));
+    }
+    match env::var_os("HOME") {
+        Some(home) => Ok(PathBuf::from(home).join(".rusty")),
+        None => Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            "neither RUSTY_HOME nor HOME is set",
+        )),
+    }
+}
+
+// the user we're keeping things for, unless told otherwise, whoever is
+// logged in. on a machine shared by several learners, each one can have a
+// profile of their own, with --user, or by setting $RUSTY_USER.
+pub fn default_user() -> String {
+    env::var("RUSTY_USER")
+        .or_else(|_| env::var("USER"))
+        .ok()
+        .filter(|user| check_user(user).is_ok())
+        .unwrap_or_else(|| "anonymous".to_string())
+}
+
+// user names end up in file names, so they are kept to letters, digits,
+// and a few harmless characters.
+pub fn check_user(user: &str) -> Result<(), String> {
+    let harmless = |c: char| c.is_ascii_alphanumeric() || c == '_' || c == '-' || c == '.';
+    if user.is_empty() || user.starts_with('.') || !user.chars().all(harmless) {
+        return Err(format!(
+            "invalid user name `{}`, use letters, digits, `_`, `-` and `.`",
+            user
+        ));
+    }
+    Ok(())
+}
+
+// appends a line to the file, creating it, and the directories it's in,
+// if they don't exist yet.
+pub fn append_line(path: &Path, line: &str) -> io::Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    writeln!(file, "{}", line)
+}
@@ -0,0 +1,123 @@
+// a String, on the stack and on the heap
+
+// a String is three numbers on the stack, a pointer to a buffer on the heap,
+// how many bytes of that buffer are in use, its len, and how big the buffer
+// is, its capacity. look() reads those three off a live String, and draw()
+// lays a few of them out side by side, with where each one sits on the
+// stack, and the bytes its pointer points at, so the ownership lesson can
+// show what a move, a clone, or a push_str that runs out of room does.
+
+// a String that was moved out of can't be looked at anymore, the compiler
+// won't let us, so it's drawn from how it looked before the move, with only
+// where it was on the stack left.
+
+// the addresses are real, so they're different on every run.
+
+// how many bytes of a buffer are drawn, the rest are only counted.
+const SHOWN: usize = 32;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Look {
+    Live {
+        name: String,
+        // where the String itself, the three numbers, is.
+        stack: usize,
+        ptr: usize,
+        len: usize,
+        capacity: usize,
+        bytes: Vec<u8>,
+    },
+    // the String is still where it was on the stack, it just can't be used.
+    Moved {
+        name: String,
+        stack: usize,
+        into: String,
+    },
+}
+
+impl Look {
+    pub fn name(&self) -> &str {
+        match *self {
+            Look::Live { ref name, .. } | Look::Moved { ref name, .. } => name,
+        }
+    }
+}
+
+#[allow(clippy::ptr_arg)]
+pub fn look(name: &str, s: &String) -> Look {
+    Look::Live {
+        name: name.to_string(),
+        stack: s as *const String as usize,
+        ptr: s.as_ptr() as usize,
+        len: s.len(),
+        capacity: s.capacity(),
+        bytes: s.as_bytes().to_vec(),
+    }
+}
+
+// the String looked at before, after it was moved into something else.
+pub fn moved(before: &Look, into: &str) -> Look {
+    let stack = match *before {
+        Look::Live { stack, .. } | Look::Moved { stack, .. } => stack,
+    };
+    Look::Moved {
+        name: before.name().to_string(),
+        stack,
+        into: into.to_string(),
+    }
+}
+
+pub fn address(at: usize) -> String {
+    format!("{:#014x}", at)
+}
+
+// the buffer, a byte to a cell, with the spare capacity left blank,
+// "|h|e|l|l|o| | | |" for "hello" in a buffer of 8.
+pub fn buffer(bytes: &[u8], capacity: usize) -> String {
+    let mut out = String::from("|");
+    for i in 0..capacity.min(SHOWN) {
+        let c = match bytes.get(i) {
+            Some(&b) if b.is_ascii_graphic() => b as char,
+            Some(&b' ') => '_',
+            Some(_) => '?',
+            None => ' ',
+        };
+        out.push(c);
+        out.push('|');
+    }
+    if capacity > SHOWN {
+        out.push_str(&format!(" .. {} more", capacity - SHOWN));
+    }
+    out
+}
+
+// one line for each String, the stack on the left, the heap on the right.
+pub fn draw(looks: &[Look]) -> Vec<String> {
+    let width = looks.iter().map(|look| look.name().len()).max().unwrap_or(0);
+    let mut lines = vec![format!("  {:<w$}  {:<60}heap", "", "stack", w = width)];
+
+    for (i, look) in looks.iter().enumerate() {
+        let line = match *look {
+            Look::Live { ref name, stack, ptr, len, capacity, ref bytes } => {
+                let numbers = format!("at {}, ptr {}, len {:>2}, cap {:>2}", address(stack), address(ptr), len, capacity);
+                // a buffer two Strings point at is only drawn once.
+                let shared = looks[..i].iter().find(|earlier| match **earlier {
+                    Look::Live { ptr: other, capacity: other_capacity, .. } => other == ptr && capacity > 0 && other_capacity > 0,
+                    _ => false,
+                });
+                let heap = match shared {
+                    Some(earlier) => format!("the same buffer as {}", earlier.name()),
+                    None if capacity == 0 => "nothing, it's empty".to_string(),
+                    None => buffer(bytes, capacity),
+                };
+                format!("  {:<w$}  {:<56}--> {}", name, numbers, heap, w = width)
+            }
+            Look::Moved { ref name, stack, ref into } => {
+                let moved = format!("at {}, moved into {}, it can't be used anymore", address(stack), into);
+                format!("  {:<w$}  {}", name, moved, w = width)
+            }
+        };
+        lines.push(line);
+    }
+    lines
+}
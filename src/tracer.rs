@@ -0,0 +1,231 @@
+// when values come and go
+
+// a Traced value is a value with a name, that writes down in a journal when
+// it's created, cloned, moved, and dropped, so the ownership lesson can show
+// exactly when each one goes away. the journal keeps track of how deep in
+// scopes we are, so what happens inside a block, or a function, is indented.
+
+// a move is only written down when it goes through hand_to, a plain
+// `let s2 = s1;` moves the value just the same, but nothing can see it.
+
+// the journal doesn't print anything itself, a value being dropped has no
+// context to print to, so the lesson takes the entries out, and prints
+// them, every now and then, see take().
+
+// nothing in here allocates once the journal is made, as long as it isn't
+// left to fill up, the names are all &'static str, so the heap counts of
+// the ownership lesson, see heap.rs, still only count the Strings.
+
+use std::cell::RefCell;
+use std::fmt;
+use std::ops::{Deref, DerefMut};
+use std::rc::Rc;
+
+// how many entries fit before the journal has to grow.
+const ROOM: usize = 64;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kind {
+    Created,
+    Moved(&'static str),
+    Cloned(&'static str),
+    Dropped,
+    Entered,
+    Left,
+}
+
+impl Kind {
+    pub fn name(&self) -> &'static str {
+        match *self {
+            Kind::Created => "created",
+            Kind::Moved(_) => "moved",
+            Kind::Cloned(_) => "cloned",
+            Kind::Dropped => "dropped",
+            Kind::Entered => "entered",
+            Kind::Left => "left",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Entry {
+    pub depth: usize,
+    pub kind: Kind,
+    // the value, or for a scope, what the scope is.
+    pub name: &'static str,
+}
+
+impl fmt::Display for Entry {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", "  ".repeat(self.depth))?;
+        match self.kind {
+            Kind::Created => write!(f, "{} is created", self.name),
+            Kind::Moved(into) => write!(f, "{} moves into {}", self.name, into),
+            Kind::Cloned(into) => write!(f, "{} is cloned into {}", self.name, into),
+            Kind::Dropped => write!(f, "{} is dropped", self.name),
+            Kind::Entered => write!(f, "{{ {}", self.name),
+            Kind::Left => write!(f, "}} {}", self.name),
+        }
+    }
+}
+
+struct Log {
+    depth: usize,
+    entries: Vec<Entry>,
+}
+
+// a journal is shared by every value traced in it, cloning it hands out
+// another handle to the same one.
+#[derive(Clone)]
+pub struct Journal {
+    log: Rc<RefCell<Log>>,
+}
+
+impl Default for Journal {
+    fn default() -> Journal {
+        Journal::new()
+    }
+}
+
+impl Journal {
+    pub fn new() -> Journal {
+        Journal {
+            log: Rc::new(RefCell::new(Log {
+                depth: 0,
+                entries: Vec::with_capacity(ROOM),
+            })),
+        }
+    }
+
+    fn write(&self, kind: Kind, name: &'static str) {
+        let mut log = self.log.borrow_mut();
+        let depth = log.depth;
+        log.entries.push(Entry { depth, kind, name });
+    }
+
+    // starts tracing value, under the name given.
+    pub fn make<T>(&self, name: &'static str, value: T) -> Traced<T> {
+        self.write(Kind::Created, name);
+        Traced {
+            name,
+            value: Some(value),
+            journal: self.clone(),
+        }
+    }
+
+    // a scope, everything written down until the guard is dropped is
+    // indented one step further.
+    pub fn scope(&self, name: &'static str) -> Scope {
+        self.write(Kind::Entered, name);
+        self.log.borrow_mut().depth += 1;
+        Scope {
+            name,
+            journal: self.clone(),
+        }
+    }
+
+    // runs f in a scope of its own, like a function call, which, unlike a
+    // guard at the top of the function, also covers the arguments, as
+    // they're dropped after everything declared inside the function.
+    pub fn call<R, F: FnOnce() -> R>(&self, name: &'static str, f: F) -> R {
+        let _scope = self.scope(name);
+        f()
+    }
+
+    // everything written down since the last time, keeping the room to
+    // write more.
+    pub fn take(&self) -> Vec<Entry> {
+        self.log.borrow_mut().entries.drain(..).collect()
+    }
+}
+
+pub struct Scope {
+    name: &'static str,
+    journal: Journal,
+}
+
+impl Drop for Scope {
+    fn drop(&mut self) {
+        self.journal.log.borrow_mut().depth -= 1;
+        self.journal.write(Kind::Left, self.name);
+    }
+}
+
+// the value is only ever None after it was handed to someone else, so the
+// Traced left behind, about to be dropped, knows not to say so.
+pub struct Traced<T> {
+    name: &'static str,
+    value: Option<T>,
+    journal: Journal,
+}
+
+impl<T> Traced<T> {
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    pub fn journal(&self) -> &Journal {
+        &self.journal
+    }
+
+    // moves the value into a new name, like `let s2 = s1;`, or passing s1
+    // to a function that takes a String.
+    pub fn hand_to(mut self, into: &'static str) -> Traced<T> {
+        self.journal.write(Kind::Moved(into), self.name);
+        Traced {
+            name: into,
+            value: self.value.take(),
+            journal: self.journal.clone(),
+        }
+    }
+
+    // takes the value out, it's no longer traced, and goes away quietly.
+    pub fn into_inner(mut self) -> T {
+        self.value.take().expect("a traced value is always there")
+    }
+}
+
+impl<T: Clone> Traced<T> {
+    pub fn clone_as(&self, into: &'static str) -> Traced<T> {
+        self.journal.write(Kind::Cloned(into), self.name);
+        Traced {
+            name: into,
+            value: self.value.clone(),
+            journal: self.journal.clone(),
+        }
+    }
+}
+
+impl<T> Drop for Traced<T> {
+    fn drop(&mut self) {
+        if self.value.is_some() {
+            self.journal.write(Kind::Dropped, self.name);
+        }
+    }
+}
+
+impl<T> Deref for Traced<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        self.value.as_ref().expect("a traced value is always there")
+    }
+}
+
+impl<T> DerefMut for Traced<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        self.value.as_mut().expect("a traced value is always there")
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Traced<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(&**self, f)
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for Traced<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
@@ -0,0 +1,345 @@
+// chars, bytes and graphemes
+
+// a rust char is a unicode scalar value, a code point, 4 bytes in memory,
+// whatever it is. a String isn't a list of chars though, it's the utf-8
+// encoding of them, where a char takes 1 to 4 bytes, which is why
+// "    ".len() is 4, but "😻".len() is 4 too. and what someone reading the
+// text would call a character, a grapheme cluster, can be more than one
+// char, an e and a combining accent, or a hand and its skin tone.
+
+// this takes a string apart, char by char, with the code point, the utf-8
+// bytes, the utf-16 units, the way windows and javascript store text, and
+// the general category, and splits it into grapheme clusters, following
+// the rules in unicode's UAX #29.
+
+// the full unicode tables are big, std only has a few of them, so the
+// ranges below are the ones for the scripts, marks, punctuation and emoji
+// people are likely to type, they get those right, a char outside of them
+// std can't place is shown as not in the tables, and taken to be a
+// grapheme of its own.
+
+// GENERAL CATEGORIES
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Category {
+    // like "Lu", or "??" when it's not in the tables.
+    pub code: &'static str,
+    pub name: &'static str,
+}
+
+fn name(code: &str) -> &'static str {
+    match code {
+        "Lu" => "uppercase letter",
+        "Ll" => "lowercase letter",
+        "Lt" => "titlecase letter",
+        "Lo" => "other letter",
+        "Mn" => "nonspacing mark",
+        "Mc" => "spacing mark",
+        "Me" => "enclosing mark",
+        "Nd" => "decimal digit",
+        "Nl" => "letter number",
+        "No" => "other number",
+        "Pc" => "connector punctuation",
+        "Pd" => "dash punctuation",
+        "Ps" => "open punctuation",
+        "Pe" => "close punctuation",
+        "Pi" => "initial quote",
+        "Pf" => "final quote",
+        "Po" => "other punctuation",
+        "Sm" => "math symbol",
+        "Sc" => "currency symbol",
+        "Sk" => "modifier symbol",
+        "So" => "other symbol",
+        "Zs" => "space separator",
+        "Zl" => "line separator",
+        "Zp" => "paragraph separator",
+        "Cc" => "control",
+        "Cf" => "format",
+        "Co" => "private use",
+        _ => "not in the tables here",
+    }
+}
+
+// the chars std can't tell apart, first match wins.
+const CATEGORIES: &[(u32, u32, &str)] = &[
+    // ascii punctuation and symbols.
+    (0x21, 0x23, "Po"), (0x24, 0x24, "Sc"), (0x25, 0x27, "Po"), (0x28, 0x28, "Ps"), (0x29, 0x29, "Pe"),
+    (0x2a, 0x2a, "Po"), (0x2b, 0x2b, "Sm"), (0x2c, 0x2c, "Po"), (0x2d, 0x2d, "Pd"), (0x2e, 0x2f, "Po"),
+    (0x30, 0x39, "Nd"), (0x3a, 0x3b, "Po"), (0x3c, 0x3e, "Sm"), (0x3f, 0x40, "Po"), (0x5b, 0x5b, "Ps"),
+    (0x5c, 0x5c, "Po"), (0x5d, 0x5d, "Pe"), (0x5e, 0x5e, "Sk"), (0x5f, 0x5f, "Pc"), (0x60, 0x60, "Sk"),
+    (0x7b, 0x7b, "Ps"), (0x7c, 0x7c, "Sm"), (0x7d, 0x7d, "Pe"), (0x7e, 0x7e, "Sm"),
+    // latin-1.
+    (0xa1, 0xa1, "Po"), (0xa2, 0xa5, "Sc"), (0xa6, 0xa6, "So"), (0xa7, 0xa7, "Po"), (0xa8, 0xa8, "Sk"),
+    (0xa9, 0xa9, "So"), (0xab, 0xab, "Pi"), (0xac, 0xac, "Sm"), (0xad, 0xad, "Cf"), (0xae, 0xae, "So"),
+    (0xaf, 0xaf, "Sk"), (0xb0, 0xb0, "So"), (0xb1, 0xb1, "Sm"), (0xb4, 0xb4, "Sk"), (0xb6, 0xb7, "Po"),
+    (0xb8, 0xb8, "Sk"), (0xbb, 0xbb, "Pf"), (0xbf, 0xbf, "Po"), (0xd7, 0xd7, "Sm"), (0xf7, 0xf7, "Sm"),
+    (0x1c5, 0x1c5, "Lt"), (0x1c8, 0x1c8, "Lt"), (0x1cb, 0x1cb, "Lt"), (0x1f2, 0x1f2, "Lt"),
+    // combining marks, hebrew, arabic, devanagari and thai vowel signs.
+    (0x300, 0x36f, "Mn"), (0x483, 0x487, "Mn"), (0x488, 0x489, "Me"), (0x591, 0x5bd, "Mn"), (0x5bf, 0x5bf, "Mn"),
+    (0x5c1, 0x5c2, "Mn"), (0x5c4, 0x5c5, "Mn"), (0x5c7, 0x5c7, "Mn"), (0x600, 0x605, "Cf"), (0x610, 0x61a, "Mn"),
+    (0x61c, 0x61c, "Cf"), (0x64b, 0x65f, "Mn"), (0x660, 0x669, "Nd"), (0x670, 0x670, "Mn"), (0x6d6, 0x6dc, "Mn"),
+    (0x6dd, 0x6dd, "Cf"), (0x6df, 0x6e4, "Mn"), (0x6e7, 0x6e8, "Mn"), (0x6ea, 0x6ed, "Mn"), (0x6f0, 0x6f9, "Nd"),
+    (0x900, 0x902, "Mn"), (0x903, 0x903, "Mc"), (0x93a, 0x93a, "Mn"), (0x93b, 0x93b, "Mc"), (0x93c, 0x93c, "Mn"),
+    (0x93e, 0x940, "Mc"), (0x941, 0x948, "Mn"), (0x949, 0x94c, "Mc"), (0x94d, 0x94d, "Mn"), (0x94e, 0x94f, "Mc"),
+    (0x951, 0x957, "Mn"), (0x962, 0x963, "Mn"), (0x964, 0x965, "Po"), (0x966, 0x96f, "Nd"), (0xe31, 0xe31, "Mn"),
+    (0xe34, 0xe3a, "Mn"), (0xe47, 0xe4e, "Mn"), (0xe50, 0xe59, "Nd"),
+    (0x1ab0, 0x1abd, "Mn"), (0x1abe, 0x1abe, "Me"), (0x1abf, 0x1ace, "Mn"), (0x1dc0, 0x1dff, "Mn"),
+    // general punctuation, and invisible formatting.
+    (0x200b, 0x200f, "Cf"), (0x2010, 0x2015, "Pd"), (0x2016, 0x2017, "Po"), (0x2018, 0x2018, "Pi"), (0x2019, 0x2019, "Pf"),
+    (0x201a, 0x201a, "Ps"), (0x201b, 0x201c, "Pi"), (0x201d, 0x201d, "Pf"), (0x201e, 0x201e, "Ps"), (0x201f, 0x201f, "Pi"),
+    (0x2020, 0x2027, "Po"), (0x2028, 0x2028, "Zl"), (0x2029, 0x2029, "Zp"), (0x202a, 0x202e, "Cf"), (0x2030, 0x2038, "Po"),
+    (0x2039, 0x2039, "Pi"), (0x203a, 0x203a, "Pf"), (0x203b, 0x203e, "Po"), (0x203f, 0x2040, "Pc"), (0x2041, 0x2043, "Po"),
+    (0x2044, 0x2044, "Sm"), (0x2047, 0x2051, "Po"), (0x2060, 0x2064, "Cf"), (0x2066, 0x206f, "Cf"),
+    (0x20a0, 0x20c0, "Sc"), (0x20d0, 0x20dc, "Mn"), (0x20dd, 0x20e0, "Me"), (0x20e1, 0x20e1, "Mn"), (0x20e2, 0x20e4, "Me"),
+    (0x20e5, 0x20f0, "Mn"), (0x2160, 0x2182, "Nl"), (0x2185, 0x2188, "Nl"), (0x2190, 0x2194, "Sm"), (0x2195, 0x21ff, "So"),
+    (0x2200, 0x22ff, "Sm"), (0x2300, 0x2307, "So"), (0x2308, 0x230b, "Ps"), (0x230c, 0x231f, "So"), (0x2500, 0x25ff, "So"),
+    (0x2600, 0x2767, "So"), (0x2794, 0x27bf, "So"), (0x2b00, 0x2b2f, "So"), (0x2b50, 0x2b55, "So"),
+    // cjk punctuation, and the private use area.
+    (0x3001, 0x3003, "Po"), (0x3007, 0x3007, "Nl"), (0x3008, 0x3008, "Ps"), (0x3009, 0x3009, "Pe"), (0x300a, 0x300a, "Ps"),
+    (0x300b, 0x300b, "Pe"), (0x300c, 0x300c, "Ps"), (0x300d, 0x300d, "Pe"), (0x3021, 0x3029, "Nl"), (0x302a, 0x302d, "Mn"),
+    (0x3030, 0x3030, "Pd"), (0x303d, 0x303d, "Po"), (0x3099, 0x309a, "Mn"), (0xe000, 0xf8ff, "Co"),
+    (0xfe00, 0xfe0f, "Mn"), (0xfe20, 0xfe2f, "Mn"), (0xfeff, 0xfeff, "Cf"), (0xff01, 0xff03, "Po"), (0xff10, 0xff19, "Nd"),
+    (0xfff9, 0xfffb, "Cf"), (0xfffc, 0xfffd, "So"),
+    // emoji, and what goes with them.
+    (0x1d7ce, 0x1d7ff, "Nd"), (0x1f000, 0x1f0ff, "So"), (0x1f100, 0x1f10c, "No"), (0x1f10d, 0x1f1ff, "So"),
+    (0x1f200, 0x1f2ff, "So"), (0x1f300, 0x1f3fa, "So"), (0x1f3fb, 0x1f3ff, "Sk"), (0x1f400, 0x1faff, "So"),
+    (0xe0001, 0xe0001, "Cf"), (0xe0020, 0xe007f, "Cf"), (0xe0100, 0xe01ef, "Mn"),
+    (0xf0000, 0xffffd, "Co"), (0x100000, 0x10fffd, "Co"),
+];
+
+fn lookup(table: &[(u32, u32, &'static str)], c: char) -> Option<&'static str> {
+    let c = c as u32;
+    table.iter().find(|&&(start, end, _)| start <= c && c <= end).map(|&(_, _, value)| value)
+}
+
+// the general category, from the table, or from what std knows about the
+// char, a letter that's neither upper or lower case is taken to be Lo.
+pub fn category(c: char) -> Category {
+    let code = lookup(CATEGORIES, c).unwrap_or(if c.is_control() {
+        "Cc"
+    } else if c.is_whitespace() {
+        "Zs"
+    } else if c.is_uppercase() {
+        "Lu"
+    } else if c.is_lowercase() {
+        "Ll"
+    } else if c.is_alphabetic() {
+        "Lo"
+    } else if c.is_numeric() {
+        "No"
+    } else {
+        "??"
+    });
+    Category { code, name: name(code) }
+}
+
+// GRAPHEME CLUSTERS
+
+// what a char is, as far as splitting into grapheme clusters goes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Break {
+    Cr,
+    Lf,
+    Control,
+    // marks, and the like, that join the char before them.
+    Extend,
+    Zwj,
+    RegionalIndicator,
+    // the parts of a hangul syllable.
+    L,
+    V,
+    T,
+    Lv,
+    Lvt,
+    Pictographic,
+    Other,
+}
+
+// emoji, and other pictures, that a zero width joiner can join together.
+const PICTOGRAPHIC: &[(u32, u32)] = &[
+    (0xa9, 0xa9), (0xae, 0xae), (0x203c, 0x203c), (0x2049, 0x2049), (0x2122, 0x2122), (0x2139, 0x2139),
+    (0x2194, 0x2199), (0x21a9, 0x21aa), (0x231a, 0x231b), (0x2328, 0x2328), (0x23cf, 0x23cf), (0x23e9, 0x23f3),
+    (0x23f8, 0x23fa), (0x24c2, 0x24c2), (0x25aa, 0x25ab), (0x25b6, 0x25b6), (0x25c0, 0x25c0), (0x25fb, 0x25fe),
+    (0x2600, 0x27bf), (0x2934, 0x2935), (0x2b05, 0x2b07), (0x2b1b, 0x2b1c), (0x2b50, 0x2b50), (0x2b55, 0x2b55),
+    (0x3030, 0x3030), (0x303d, 0x303d), (0x3297, 0x3297), (0x3299, 0x3299), (0x1f000, 0x1f0ff), (0x1f10d, 0x1f10f),
+    (0x1f12f, 0x1f12f), (0x1f16c, 0x1f171), (0x1f17e, 0x1f17f), (0x1f18e, 0x1f18e), (0x1f191, 0x1f19a),
+    (0x1f1ad, 0x1f1e5), (0x1f201, 0x1f20f), (0x1f21a, 0x1f21a), (0x1f22f, 0x1f22f), (0x1f232, 0x1f23a),
+    (0x1f23c, 0x1f23f), (0x1f249, 0x1f3fa), (0x1f400, 0x1f53d), (0x1f546, 0x1f64f), (0x1f680, 0x1f6ff),
+    (0x1f774, 0x1f77f), (0x1f7d5, 0x1f7ff), (0x1f80c, 0x1f80f), (0x1f848, 0x1f84f), (0x1f85a, 0x1f85f),
+    (0x1f888, 0x1f88f), (0x1f8ae, 0x1f8ff), (0x1f90c, 0x1f93a), (0x1f93c, 0x1f945), (0x1f947, 0x1faff),
+    (0x1fc00, 0x1fffd),
+];
+
+fn within(ranges: &[(u32, u32)], c: u32) -> bool {
+    ranges.iter().any(|&(start, end)| start <= c && c <= end)
+}
+
+fn property(c: char) -> Break {
+    let code = c as u32;
+    match c {
+        '\r' => return Break::Cr,
+        '\n' => return Break::Lf,
+        '\u{200d}' => return Break::Zwj,
+        // a zero width non-joiner, emoji skin tones and tags join the char
+        // before, even though they aren't marks.
+        '\u{200c}' | '\u{1f3fb}'..='\u{1f3ff}' | '\u{e0020}'..='\u{e007f}' => return Break::Extend,
+        '\u{1f1e6}'..='\u{1f1ff}' => return Break::RegionalIndicator,
+        _ => {}
+    }
+    if within(&[(0x1100, 0x115f), (0xa960, 0xa97c)], code) {
+        return Break::L;
+    }
+    if within(&[(0x1160, 0x11a7), (0xd7b0, 0xd7c6)], code) {
+        return Break::V;
+    }
+    if within(&[(0x11a8, 0x11ff), (0xd7cb, 0xd7fb)], code) {
+        return Break::T;
+    }
+    if (0xac00..=0xd7a3).contains(&code) {
+        // every 28th syllable has no final consonant.
+        return if (code - 0xac00).is_multiple_of(28) { Break::Lv } else { Break::Lvt };
+    }
+    if within(PICTOGRAPHIC, code) {
+        return Break::Pictographic;
+    }
+    match category(c).code {
+        "Mn" | "Mc" | "Me" => Break::Extend,
+        "Cc" | "Cf" | "Zl" | "Zp" => Break::Control,
+        _ => Break::Other,
+    }
+}
+
+// splits the text into grapheme clusters, what a reader would count as
+// characters, by the rules of UAX #29, all but the prepend rule, which
+// only a handful of scripts need.
+pub fn graphemes(text: &str) -> Vec<&str> {
+    let mut clusters = Vec::new();
+    let mut start = 0;
+    let mut previous: Option<Break> = None;
+    // whether an emoji, and maybe some marks, came right before a joiner.
+    let mut after_pictographic = false;
+    // how many flag letters in a row came before, two make a flag.
+    let mut indicators = 0;
+
+    for (i, c) in text.char_indices() {
+        let this = property(c);
+        if let Some(previous) = previous {
+            let joins = match (previous, this) {
+                (Break::Cr, Break::Lf) => true,
+                (Break::Cr, _) | (Break::Lf, _) | (Break::Control, _) => false,
+                (_, Break::Cr) | (_, Break::Lf) | (_, Break::Control) => false,
+                (Break::L, Break::L) | (Break::L, Break::V) | (Break::L, Break::Lv) | (Break::L, Break::Lvt) => true,
+                (Break::Lv, Break::V) | (Break::Lv, Break::T) | (Break::V, Break::V) | (Break::V, Break::T) => true,
+                (Break::Lvt, Break::T) | (Break::T, Break::T) => true,
+                (_, Break::Extend) | (_, Break::Zwj) => true,
+                (Break::Zwj, Break::Pictographic) => after_pictographic,
+                (Break::RegionalIndicator, Break::RegionalIndicator) => indicators % 2 == 1,
+                _ => false,
+            };
+            if !joins {
+                clusters.push(&text[start..i]);
+                start = i;
+            }
+        }
+
+        after_pictographic = match this {
+            Break::Pictographic => true,
+            Break::Extend | Break::Zwj => after_pictographic && previous != Some(Break::Zwj),
+            _ => false,
+        };
+        indicators = if this == Break::RegionalIndicator { indicators + 1 } else { 0 };
+        previous = Some(this);
+    }
+    if start < text.len() {
+        clusters.push(&text[start..]);
+    }
+    clusters
+}
+
+// TAKING A STRING APART
+
+pub struct Char {
+    // where its bytes start, what &text[offset..] would need.
+    pub offset: usize,
+    pub c: char,
+    pub utf8: Vec<u8>,
+    pub utf16: Vec<u16>,
+    pub category: Category,
+}
+
+impl Char {
+    // U+1F63B, the way code points are written.
+    pub fn code_point(&self) -> String {
+        format!("U+{:04X}", self.c as u32)
+    }
+
+    // the char, in a way that can be seen, a mark goes on a dotted circle,
+    // and what's invisible is escaped.
+    pub fn shown(&self) -> String {
+        match self.category.code {
+            "Mn" | "Mc" | "Me" => format!("\u{25cc}{}", self.c),
+            "Cc" | "Cf" | "Zl" | "Zp" => self.c.escape_default().to_string(),
+            "Zs" => self.c.escape_unicode().to_string(),
+            _ => self.c.to_string(),
+        }
+    }
+}
+
+pub struct Report {
+    pub chars: Vec<Char>,
+    pub graphemes: Vec<String>,
+    // .len(), the utf-8 bytes.
+    pub bytes: usize,
+    // how long it would be in javascript, or in a windows api.
+    pub utf16: usize,
+}
+
+pub fn explore(text: &str) -> Report {
+    let chars: Vec<Char> = text
+        .char_indices()
+        .map(|(offset, c)| {
+            let mut utf8 = [0; 4];
+            let mut utf16 = [0; 2];
+            Char {
+                offset,
+                c,
+                utf8: c.encode_utf8(&mut utf8).as_bytes().to_vec(),
+                utf16: c.encode_utf16(&mut utf16).to_vec(),
+                category: category(c),
+            }
+        })
+        .collect();
+    Report {
+        utf16: chars.iter().map(|c| c.utf16.len()).sum(),
+        chars,
+        graphemes: graphemes(text).into_iter().map(str::to_string).collect(),
+        bytes: text.len(),
+    }
+}
+
+// chars that are hard to type, like a combining accent, can be written the
+// way rust writes them, \u{301}, anything else is left as it is.
+pub fn unescape(text: &str) -> String {
+    let mut unescaped = String::new();
+    let mut rest = text;
+    while let Some(start) = rest.find("\\u{") {
+        unescaped.push_str(&rest[..start]);
+        let escape = &rest[start..];
+        let c = escape.find('}').and_then(|end| {
+            let c = u32::from_str_radix(&escape[3..end], 16).ok().and_then(char::from_u32)?;
+            Some((c, end + 1))
+        });
+        match c {
+            Some((c, len)) => {
+                unescaped.push(c);
+                rest = &escape[len..];
+            }
+            None => {
+                unescaped.push_str("\\u{");
+                rest = &escape[3..];
+            }
+        }
+    }
+    unescaped.push_str(rest);
+    unescaped
+}
@@ -0,0 +1,50 @@
+// borrowing, checked as the code runs
+
+// the table has to follow the borrows exactly, and a borrow that breaks the
+// rules has to be refused the same way a plain RefCell refuses it.
+
+extern crate rusty;
+
+use rusty::borrows::{Kind, Tracked};
+use rusty::panics;
+
+fn names(tracked: &Tracked<Vec<i32>>) -> Vec<(&'static str, Kind)> {
+    tracked.table().iter().map(|borrow| (borrow.name, borrow.kind)).collect()
+}
+
+#[test]
+fn the_table_follows_the_borrows() {
+    let v = Tracked::new("v", vec![1, 2]);
+    {
+        let a = v.borrow("a");
+        let b = v.borrow("a");
+        assert_eq!(names(&v), vec![("a", Kind::Shared), ("a", Kind::Shared)]);
+        drop(a);
+        assert_eq!(names(&v), vec![("a", Kind::Shared)]);
+        assert_eq!(b.len(), 2);
+
+        let conflict = v.try_borrow_mut("c").err().unwrap();
+        assert_eq!(conflict.to_string(), "c can't borrow v as mutable, a is borrowing it");
+    }
+    assert!(v.table().is_empty());
+
+    let mut c = v.borrow_mut("c");
+    c.push(3);
+    assert_eq!(names(&v), vec![("c", Kind::Mutable)]);
+    let conflict = v.try_borrow("d").err().unwrap();
+    assert_eq!(conflict.to_string(), "d can't borrow v as shared, c is borrowing it mutably");
+    drop(c);
+    assert_eq!(*v.try_borrow("e").ok().unwrap(), vec![1, 2, 3]);
+}
+
+#[test]
+fn a_conflicting_borrow_panics_like_a_refcell() {
+    let v = Tracked::new("v", vec![1]);
+    let _a = v.borrow("a");
+    let _b = v.borrow("b");
+    assert_eq!(panics::catch(|| drop(v.borrow_mut("c"))).err().unwrap(), "RefCell already borrowed");
+    assert_eq!(v.try_borrow_mut("c").err().unwrap().to_string(), "c can't borrow v as mutable, a and b are all borrowing it");
+    // the borrow that panicked never made it into the table.
+    assert_eq!(names(&v).len(), 2);
+    assert_eq!(panics::catch(|| 1 + 1), Ok(2));
+}
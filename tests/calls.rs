@@ -0,0 +1,137 @@
+// recording calls
+
+// the timings are different on every run, so only the shape of the tree,
+// the arguments, and what came back, are checked.
+
+#[macro_use]
+extern crate rusty;
+
+use rusty::panics;
+use rusty::calls::{self, Call};
+use rusty::context::{Context, Options};
+use rusty::lessons;
+use rusty::output::Format;
+
+fn simple_return() -> i32 {
+    5
+}
+
+fn return_sth() -> i32 {
+    call!(simple_return())
+}
+
+fn add(a: i32, b: i32) -> i32 {
+    a + b
+}
+
+fn push(out: &mut Vec<i32>, x: i32) {
+    out.push(call!(add(x, 1)));
+}
+
+fn names(calls: &[Call]) -> Vec<&'static str> {
+    calls.iter().map(|call| call.name).collect()
+}
+
+#[test]
+fn calls_are_recorded_with_what_they_were_given_and_returned() {
+    let mut out = vec![];
+    let x = 41;
+    let (returned, made) = calls::record(|| {
+        call!(push(&mut out; x));
+        call!(return_sth())
+    });
+
+    assert_eq!(returned, 5);
+    assert_eq!(out, vec![42]);
+    assert_eq!(names(&made), vec!["push", "return_sth"]);
+
+    assert_eq!(made[0].args, vec![("x", "41".to_string())]);
+    assert_eq!(made[0].returned.as_deref(), Some("()"));
+    assert_eq!(made[0].calls[0].args, vec![("x", "41".to_string()), ("1", "1".to_string())]);
+    assert_eq!(made[0].calls[0].to_string(), "add(x = 41, 1) -> 42");
+    assert_eq!(made[0].to_string(), "push(x = 41)");
+
+    assert_eq!(names(&made[1].calls), vec!["simple_return"]);
+    assert!(made[1].time >= made[1].calls[0].time);
+}
+
+#[test]
+fn nothing_is_recorded_outside_a_recording() {
+    assert_eq!(call!(return_sth()), 5);
+    let ((), made) = calls::record(|| ());
+    assert!(made.is_empty());
+}
+
+#[test]
+fn the_tree_is_indented_under_the_caller() {
+    let ((), made) = calls::record(|| {
+        call!(return_sth());
+        call!(add(1, 2));
+    });
+    let lines: Vec<String> = calls::tree(&made).iter()
+        .map(|line| line[..line.rfind("  [").unwrap()].to_string())
+        .collect();
+    assert_eq!(lines, vec![
+        "|- return_sth() -> 5",
+        "|  `- simple_return() -> 5",
+        "`- add(1, 2) -> 3",
+    ]);
+}
+
+// the functions lesson is one chain of calls, each made from the one
+// before it, so it's one tree, a level deeper with every call.
+#[test]
+fn the_functions_lesson_is_one_tree() {
+    let functions = lessons::find("functions").unwrap();
+    let (mut ctx, _) = Context::capture(Format::Human, Options::default(), "");
+    let ((), made) = calls::record(|| functions.run(&mut ctx));
+
+    let lines: Vec<String> = calls::tree(&made).iter()
+        .map(|line| line[..line.rfind("  [").unwrap()].to_string())
+        .collect();
+    assert_eq!(lines, vec![
+        "`- main()",
+        "   `- not_main()",
+        "      `- do_sth(999)",
+        "         |- show_and_express()",
+        "         `- return_sth() -> 5",
+        "            `- simple_return() -> 5",
+    ]);
+}
+
+#[test]
+fn a_nested_recording_is_in_both() {
+    let (inner, outer) = calls::record(|| {
+        call!(simple_return());
+        calls::record(|| call!(add(2, 2))).1
+    });
+    assert_eq!(names(&inner), vec!["add"]);
+    assert_eq!(names(&outer), vec!["simple_return", "add"]);
+}
+
+fn boom() -> i32 {
+    panic!("boom")
+}
+
+#[test]
+fn a_call_that_panics_never_returns() {
+    let ((), made) = calls::record(|| {
+        assert!(panics::catch(|| call!(boom())).is_err());
+        call!(simple_return());
+    });
+    assert_eq!(names(&made), vec!["boom", "simple_return"]);
+    assert_eq!(made[0].to_string(), "boom() never returned");
+
+    // and a recording that panics leaves nothing behind.
+    assert!(panics::catch(|| calls::record(boom)).is_err());
+    assert_eq!(calls::record(|| call!(simple_return())).1.len(), 1);
+}
+
+#[test]
+fn durations_are_short() {
+    use std::time::Duration;
+    assert_eq!(calls::duration(Duration::from_nanos(622)), "622ns");
+    assert_eq!(calls::duration(Duration::from_nanos(4_200)), "4.2µs");
+    assert_eq!(calls::duration(Duration::from_micros(1_500)), "1.500ms");
+    assert_eq!(calls::duration(Duration::from_millis(2_000)), "2.000s");
+}
@@ -0,0 +1,43 @@
+// what the tests have in common
+
+// things end up somewhere else in memory every time a lesson runs, so the
+// addresses in what it prints are masked before comparing.
+
+// "ptr 0x55d0c9f0a080" => "ptr <addr 3>", numbered in the order they're first
+// seen, so two things at the same address still are in the snapshot.
+pub fn mask_addresses(line: &str, addresses: &mut Vec<String>) -> String {
+    let mut out = String::new();
+    let mut rest = line;
+    while let Some(at) = rest.find("0x") {
+        let digits = rest[at + 2..].find(|c: char| !c.is_ascii_hexdigit()).unwrap_or(rest.len() - at - 2);
+        let end = at + 2 + digits;
+        if digits < 8 {
+            out.push_str(&rest[..end]);
+        } else {
+            let address = rest[at..end].to_string();
+            let n = match addresses.iter().position(|seen| *seen == address) {
+                Some(i) => i + 1,
+                None => {
+                    addresses.push(address);
+                    addresses.len()
+                }
+            };
+            out.push_str(&rest[..at]);
+            out.push_str(&format!("<addr {}>", n));
+        }
+        rest = &rest[end..];
+    }
+    out.push_str(rest);
+    out
+}
+
+// "ptr <addr 3>" => "ptr <addr>", for when only where an address is counts,
+// not which one it is.
+pub fn unnumbered(line: &str) -> String {
+    match line.find("<addr ") {
+        Some(_) => line.split("<addr ").enumerate().map(|(i, part)| {
+            if i == 0 { part.to_string() } else { format!("<addr{}", &part[part.find('>').unwrap()..]) }
+        }).collect(),
+        None => line.to_string(),
+    }
+}
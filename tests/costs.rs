@@ -0,0 +1,56 @@
+// what moves, clones and borrows cost
+
+// only the allocations are checked, they're the same on every run, the
+// times aren't, so all that's checked about them is that they're there.
+
+extern crate rusty;
+
+use rusty::costs::{self, Strategy};
+use rusty::heap::Counting;
+
+#[global_allocator]
+static COUNTING: Counting = Counting;
+
+#[test]
+fn a_move_and_a_borrow_are_free_and_a_clone_is_not() {
+    for &size in &[10, 1000] {
+        let moved = costs::strategy::<String>(Strategy::Move, size);
+        assert_eq!((moved.allocs, moved.bytes), (Some(0), Some(0)));
+
+        let cloned = costs::strategy::<String>(Strategy::Clone, size);
+        assert_eq!((cloned.allocs, cloned.bytes), (Some(1), Some(size)));
+
+        let borrowed = costs::strategy::<String>(Strategy::Borrow, size);
+        assert_eq!((borrowed.allocs, borrowed.bytes), (Some(0), Some(0)));
+    }
+
+    let cloned = costs::strategy::<Vec<u64>>(Strategy::Clone, 100);
+    assert_eq!(cloned.payload, "Vec<u64>");
+    assert_eq!((cloned.allocs, cloned.bytes), (Some(1), Some(800)));
+}
+
+#[test]
+fn takes_and_gives_back_needs_no_clones() {
+    // two clones, and the push_str onto the first one has to grow it.
+    let two = costs::variant("two clones", 100);
+    assert_eq!(two.allocs, Some(3));
+
+    // the String handed in only has to grow.
+    let none = costs::variant("no clones", 100);
+    assert_eq!(none.allocs, Some(1));
+    assert!(none.bytes < two.bytes);
+
+    let (s, length) = costs::without_clones(String::from("hello"));
+    assert_eq!((s.as_str(), length), ("hello, and shadowed from the outside!", 37));
+    assert_eq!(costs::with_two_clones(String::from("hello")), (s.clone(), length));
+    assert_eq!(costs::borrowed("hello"), (s, length));
+}
+
+#[test]
+fn sizes_grow_tenfold_up_to_max() {
+    assert_eq!(costs::sizes(1000), vec![10, 100, 1000]);
+    assert_eq!(costs::sizes(999), vec![10, 100]);
+    assert_eq!(costs::sizes(0), vec![10]);
+    assert_eq!(costs::rounds(1), 1000);
+    assert_eq!(costs::rounds(1_000_000), 1);
+}
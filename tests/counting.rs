@@ -0,0 +1,26 @@
+// the counting loop behind the controlflow bench
+
+// the loop, its threaded version and the closed form all have to agree,
+// and the closed form has to say so when the sum is too big for an i128,
+// rather than overflowing.
+
+extern crate rusty;
+
+use rusty::counting;
+
+#[test]
+fn every_way_of_counting_agrees() {
+    for &mynum in &[0, 1, -1, 10, -10, 1234, -1234, 20000] {
+        let serial = counting::sum_serial(mynum);
+        assert_eq!(counting::sum_threaded(mynum, 3), serial, "threaded, {}", mynum);
+        assert_eq!(counting::sum_closed_form(mynum), Some(serial), "closed form, {}", mynum);
+    }
+}
+
+#[test]
+fn the_closed_form_knows_when_it_does_not_fit() {
+    assert_eq!(counting::sum_closed_form(1_000_000_000_000_000_000),
+               Some(45 * 500_000_000_000_000_000_500_000_000_000_000_000));
+    assert_eq!(counting::sum_closed_form(i64::MAX), None);
+    assert_eq!(counting::sum_closed_form(i64::MIN), None);
+}
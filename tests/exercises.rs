@@ -0,0 +1,111 @@
+// the exercises
+
+// every exercise is checked with the real rustc, as it's handed out, with
+// the error reproduced, and with a fix, so we know each one can be done.
+
+extern crate rusty;
+
+use std::fs;
+use std::path::PathBuf;
+
+use rusty::context::{Context, Options};
+use rusty::lessons::exercise::Outcome;
+use rusty::lessons::{self, LESSONS};
+use rusty::output::Format;
+use rusty::rustc;
+
+// a way to reproduce the error, and a way to fix it, for every exercise,
+// as (lesson, exercise, what to replace, with what), applied to its code.
+const REPRODUCE: &[(&str, &str, &str, &str)] = &[
+    ("variables", "assign_twice", "// x = 6;", "x = 6;"),
+    ("ownership", "use_after_move", "// println!(\"{}, world!\", s1);", "println!(\"{}, world!\", s1);"),
+    ("ownership", "moved_into_function", "// println!(\"{}\", s);", "println!(\"{}\", s);"),
+    ("refandborrow", "two_mutable_borrows", "// let r2 = &mut s;", "let r2 = &mut s;"),
+    ("refandborrow", "two_mutable_borrows", "// println!(\"{}, {}\", r1, r2);", "println!(\"{}, {}\", r1, r2);"),
+];
+
+const FIX: &[(&str, &str, &str, &str)] = &[
+    ("variables", "assign_twice", "let x = 5;", "let mut x = 5;"),
+    ("datatypes", "array_length", "[1, 2, 3]", "[1, 2, 3, 4]"),
+    ("functions", "let_is_a_statement", "(let y = 6)", "{ let y = 6; y }"),
+    ("functions", "missing_return", "x + 1;", "x + 1"),
+    ("controlflow", "if_needs_bool", "if number {", "if number != 0 {"),
+    ("ownership", "use_after_move", "let s2 = s1;", "let s2 = s1.clone();"),
+    ("ownership", "moved_into_function", "takes_ownership(s);", "takes_ownership(s.clone());"),
+    ("refandborrow", "two_mutable_borrows", "let r1 = &mut s;", "let r1 = &s;"),
+    ("refandborrow", "two_mutable_borrows", "let r2 = &mut s;", "let r2 = &s;"),
+    ("refandborrow", "dangling_reference", "fn dangle() -> &String {", "fn dangle() -> String {"),
+    ("refandborrow", "dangling_reference", "    &s\n", "    s\n"),
+];
+
+fn edit(code: &str, edits: &[(&str, &str, &str, &str)], lesson: &str, exercise: &str) -> String {
+    let mut code = code.to_string();
+    for &(l, e, from, to) in edits.iter() {
+        if l == lesson && e == exercise {
+            assert!(code.contains(from), "{}/{} has no `{}`", lesson, exercise, from);
+            code = code.replace(from, to);
+        }
+    }
+    code
+}
+
+#[test]
+fn rustc_reports_errors_with_their_code_and_position() {
+    let diagnostics = rustc::check("fn main() {\n    let x: i32 = \"five\";\n}\n").unwrap();
+    let errors: Vec<_> = diagnostics.iter().filter(|d| d.is_error()).collect();
+
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].code.as_deref(), Some("E0308"));
+    assert_eq!(errors[0].line, Some(2));
+    assert!(errors[0].headline().starts_with("error[E0308]: mismatched types"));
+
+    assert_eq!(rustc::check("fn main() {}\n").unwrap(), vec![]);
+}
+
+#[test]
+fn every_exercise_can_be_reproduced_and_fixed() {
+    for lesson in LESSONS.iter() {
+        for exercise in lesson.exercises() {
+            let name = format!("{}/{}", lesson.id(), exercise.name);
+
+            // as handed out, the error is either already there, or the line
+            // causing it is still commented out.
+            let start = exercise.check(exercise.code).unwrap();
+            match exercise.keep {
+                Some(keep) if !exercise.code.lines().any(|line| line.trim() == keep) => {
+                    assert_eq!(start, Outcome::Missing(keep), "{}", name)
+                }
+                _ => assert_eq!(start.name(), "reproduced", "{}", name),
+            }
+
+            let broken = edit(exercise.code, REPRODUCE, lesson.id(), exercise.name);
+            assert_eq!(exercise.check(&broken).unwrap().name(), "reproduced", "{}", name);
+
+            let fixed = edit(&broken, FIX, lesson.id(), exercise.name);
+            assert_eq!(exercise.check(&fixed).unwrap(), Outcome::Fixed, "{}", name);
+        }
+    }
+}
+
+#[test]
+fn exercises_are_kept_between_runs() {
+    let home = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("exercises_are_kept_between_runs");
+    let _ = fs::remove_dir_all(&home);
+
+    let datatypes = lessons::find("datatypes").unwrap();
+    let options = Options { command: Some("exercise".to_string()), ..Options::default() };
+    let path = home.join("exercises").join("datatypes").join("array_length.rs");
+
+    let input = "paste\nfn main() {\n    let arr = [1, 2];\n}\n.\nq\n";
+    let (mut ctx, _) = Context::capture(Format::Human, options.clone(), input);
+    ctx.home = Some(home.clone());
+    lessons::present(&mut ctx, datatypes);
+    assert!(!ctx.aborted());
+
+    // the second time, the pasted code is still there, and compiles.
+    let (mut ctx, capture) = Context::capture(Format::Human, options, "\n");
+    ctx.home = Some(home.clone());
+    lessons::present(&mut ctx, datatypes);
+    assert_eq!(fs::read_to_string(&path).unwrap(), "fn main() {\n    let arr = [1, 2];\n}\n");
+    assert!(capture.contents().contains("it compiles, well done"));
+}
@@ -0,0 +1,92 @@
+// arithmetic expressions
+
+// the prompts' goldens cover what a learner sees, these check the evaluator
+// itself, the precedence, and the errors, and where they point.
+
+extern crate rusty;
+
+use rusty::expr::{self, Span};
+
+fn error(input: &str) -> (Span, String) {
+    let error = expr::eval_i128(input).unwrap_err();
+    (error.span, error.message)
+}
+
+#[test]
+fn operators_bind_as_in_rust() {
+    assert_eq!(expr::eval_i128("1 + 2 * 3"), Ok(7));
+    assert_eq!(expr::eval_i128("(1 + 2) * 3"), Ok(9));
+    assert_eq!(expr::eval_i128("10 - 4 - 3"), Ok(3));
+    assert_eq!(expr::eval_i128("100 / 10 / 5"), Ok(2));
+    assert_eq!(expr::eval_i128("7 % 4 * 2"), Ok(6));
+    assert_eq!(expr::eval_i128("-7 / 2"), Ok(-3));
+    assert_eq!(expr::eval_i128("-7 % 2"), Ok(-1));
+}
+
+#[test]
+fn powers_bind_tighter_than_signs_and_to_the_right() {
+    assert_eq!(expr::eval_i128("-2**2"), Ok(-4));
+    assert_eq!(expr::eval_i128("(-2)**2"), Ok(4));
+    assert_eq!(expr::eval_i128("2**3**2"), Ok(512));
+    assert_eq!(expr::eval_i128("2**-1").unwrap_err().message, "exponent must not be negative");
+}
+
+#[test]
+fn literals_take_prefixes_and_separators() {
+    assert_eq!(expr::eval_i128("0x10 + 0o10 + 0b10"), Ok(26));
+    assert_eq!(expr::eval_i128("1_000_000"), Ok(1_000_000));
+    assert_eq!(error("0x"), (Span::new(0, 2), "expected digits after the prefix".to_string()));
+    assert_eq!(error("0b102"), (Span::new(4, 5), "invalid digit `2` in base 2 number".to_string()));
+}
+
+#[test]
+fn overflow_points_at_the_operator() {
+    let max = i128::MAX.to_string();
+    assert_eq!(error(&format!("{} + 1", max)), (Span::new(max.len() + 1, max.len() + 2), "result of `+` overflows".to_string()));
+    assert_eq!(error("2**127"), (Span::new(1, 3), "result of `**` overflows".to_string()));
+    assert_eq!(error("2**5000000000").1, "result of `**` overflows");
+    assert_eq!(error("1000000000000000000000000000000000000000").1, "number is too large");
+}
+
+#[test]
+fn division_by_zero_points_at_the_divisor() {
+    assert_eq!(error("10/(5-5)"), (Span::new(4, 7), "division by zero".to_string()));
+    assert_eq!(error("10 % 0"), (Span::new(5, 6), "division by zero".to_string()));
+}
+
+#[test]
+fn results_have_to_fit_the_type_asked_for() {
+    assert_eq!(expr::eval_as::<u8>("255"), Ok(255u8));
+    assert_eq!(expr::eval_as::<i8>("-128"), Ok(-128i8));
+    assert_eq!(expr::eval_i64("2**62 + (2**62 - 1)"), Ok(i64::MAX));
+
+    let error = expr::eval_as::<u8>("200 + 56").unwrap_err();
+    assert_eq!((error.span, error.message.as_str()), (Span::new(0, 8), "256 does not fit into a u8"));
+    assert_eq!(expr::eval_as::<u32>("-1").unwrap_err().message, "-1 does not fit into a u32");
+    assert_eq!(expr::eval_i64("2**63").unwrap_err().message, "9223372036854775808 does not fit into an i64");
+}
+
+#[test]
+fn mistakes_are_reported_with_a_caret() {
+    let input = "(3+4)*5)";
+    let caret = expr::eval_i128(input).unwrap_err();
+    assert_eq!(caret.render(input), "(3+4)*5)\n       ^ unexpected `)`");
+    assert_eq!(error("(1 + 2").1, "this `(` is never closed");
+    assert_eq!(error("2 ^ 3").1, "unexpected character `^`, use `**` for powers");
+}
+
+#[test]
+fn nesting_too_deep_is_an_error_not_a_crash() {
+    let ok = format!("{}1{}", "(".repeat(256), ")".repeat(256));
+    assert_eq!(expr::eval_i128(&ok), Ok(1));
+
+    let deep = format!("{}1{}", "(".repeat(5000), ")".repeat(5000));
+    assert_eq!(error(&deep), (Span::new(256, 257), "too deeply nested, at most 256 levels are allowed".to_string()));
+
+    let signs = format!("{}1", "-".repeat(5000));
+    assert_eq!(error(&signs).0, Span::new(256, 257));
+
+    let long = vec!["1"; 100_000].join("+");
+    assert_eq!(error(&long), (Span::new(513, 514), "too deeply nested, at most 256 levels are allowed".to_string()));
+    assert_eq!(expr::eval_i128(&vec!["1"; 257].join("+")), Ok(257));
+}
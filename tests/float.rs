@@ -0,0 +1,132 @@
+// floats, up close
+
+// the exact values are checked against what's known about IEEE-754, 0.1 as
+// an f64, the smallest subnormal f32, and the roundings everyone runs into.
+
+extern crate rusty;
+
+use rusty::float::{self, Class, Decimal, Float};
+
+#[test]
+fn a_float_comes_apart_into_sign_exponent_and_mantissa() {
+    let anatomy = float::anatomy(Float::F32(23.32));
+    assert_eq!(anatomy.bits(), "0 10000011 01110101000111101011100");
+    assert_eq!((anatomy.sign, anatomy.exponent, anatomy.power), (0, 131, 4));
+    assert_eq!(anatomy.class, Class::Normal);
+    assert_eq!(anatomy.significand.to_string(), "1.457499980926513671875");
+    assert_eq!(anatomy.exact.unwrap().to_string(), "23.31999969482421875");
+    let (below, gap) = anatomy.below.unwrap();
+    assert_eq!((below, gap.to_string()), (Float::F32(23.319998), "0.0000019073486328125".to_string()));
+
+    let anatomy = float::anatomy(Float::F64(-0.1));
+    assert_eq!(anatomy.exact.unwrap().to_string(), "-0.1000000000000000055511151231257827021181583404541015625");
+    assert_eq!(anatomy.power, -4);
+
+    // the smallest f32 there is, past the normal ones, the exponent is all
+    // zeros, and there's no hidden 1.
+    let anatomy = float::anatomy(Float::F32(f32::from_bits(1)));
+    assert_eq!(anatomy.class, Class::Subnormal);
+    assert_eq!(anatomy.power, -126);
+    assert_eq!(anatomy.exact.unwrap().scientific(), "1.40129846432481707092372958328991613128026194187651577175706828388979108268586060148663818836212158203125e-45");
+
+    assert_eq!(float::anatomy(Float::F64(f64::INFINITY)).class, Class::Infinite);
+    assert!(float::anatomy(Float::F64(f64::MAX)).above.is_none());
+    assert_eq!(float::anatomy(Float::F32(f32::NAN)).class, Class::Nan);
+    assert_eq!(float::anatomy(Float::F64(-0.0)).class, Class::Zero);
+}
+
+#[test]
+fn decimals_do_exact_arithmetic() {
+    let d = |text: &str| Decimal::parse(text).unwrap();
+    assert_eq!(d("0.1").plus(&d("0.2")).to_string(), "0.3");
+    assert_eq!(d("1_000").minus(&d("1000.001")).to_string(), "-0.001");
+    assert_eq!(d("2.5e-4").times(&d("-4e3")).to_string(), "-1");
+    assert_eq!(d("1").divided(&d("3"), 5), (d("0.33333"), false));
+    assert_eq!(d("1").divided(&d("8"), 5), (d("0.125"), true));
+    assert_eq!(d("7.5").whole_divided(&d("2")).to_string(), "3");
+    assert_eq!(d("-0.000250").scientific(), "-2.5e-4");
+    assert_eq!(d("12e3").to_string(), "12000");
+    assert!(Decimal::parse("1.2.3").is_none());
+}
+
+#[test]
+fn every_rounding_is_within_half_an_ulp() {
+    let rounding = float::explore("0.1 + 0.2").unwrap();
+    assert_eq!(rounding.result, Float::F64(0.30000000000000004));
+    assert_eq!(rounding.exact.unwrap().to_string(), "0.3000000000000000166533453693773481063544750213623046875");
+    assert_eq!(rounding.error.unwrap().scientific(), "2.77555756156289135105907917022705078125e-17");
+    assert_eq!(rounding.ulps, Some(0.5));
+
+    let rounding = float::explore("16777217 as f32").unwrap();
+    assert_eq!((rounding.result, rounding.error.unwrap().to_string()), (Float::F32(16777216.0), "-1".to_string()));
+
+    // the remainder never rounds, going up to an f64 never does either.
+    assert!(float::explore("23.32f32 % 2.0").unwrap().error.unwrap().is_zero());
+    assert!(float::explore("23.32f32 as f64").unwrap().error.unwrap().is_zero());
+
+    let rounding = float::explore("1 / 3.0").err().unwrap();
+    assert_eq!(rounding, "`1` is an integer, and rust doesn't mix integers and floats, write it as 1.0");
+    let rounding = float::explore("1.0 / 3.0").unwrap();
+    assert!(!rounding.terminates);
+
+    for line in &["0.1", "2.5e-4f32", "1e-45f32", "0.1 as f32", "9007199254740993u64 as f64",
+                  "1.0 / 3.0", "1e38f32 * 3.0", "0.7f32 - 0.6", "-1.5 * 1e-300", "1e308 + 1e308"] {
+        let rounding = float::explore(line).unwrap();
+        if let Some(ulps) = rounding.ulps {
+            assert!(ulps.abs() <= 0.5, "{} is {} ulps out", line, ulps);
+        }
+    }
+    assert!(float::explore("1.0 / 0.0").unwrap().exact.is_none());
+}
+
+#[test]
+fn nan_infinities_and_constants_can_be_taken_apart() {
+    let rounding = float::explore("NaN").unwrap();
+    assert!(rounding.result.to_f64().is_nan());
+    assert!(rounding.exact.is_none());
+    assert_eq!(float::anatomy(float::explore("f64::NAN").unwrap().result).class, Class::Nan);
+
+    let rounding = float::explore("-inf").unwrap();
+    assert_eq!(rounding.result, Float::F64(f64::NEG_INFINITY));
+    assert_eq!(float::anatomy(rounding.result).sign, 1);
+    assert_eq!(float::explore("f32::INFINITY").unwrap().result, Float::F32(f32::INFINITY));
+    assert_eq!(float::explore("inf as f32").unwrap().result, Float::F32(f32::INFINITY));
+    assert!(float::explore("inf - inf").unwrap().result.to_f64().is_nan());
+    // inf has no suffix, so it takes the type of the other side.
+    assert_eq!(float::explore("1.5f32 * inf").unwrap().result, Float::F32(f32::INFINITY));
+
+    let rounding = float::explore("f32::MAX").unwrap();
+    assert_eq!(rounding.result, Float::F32(f32::MAX));
+    assert_eq!(rounding.exact.unwrap().to_string(), "340282346638528859811704183484516925440");
+    assert_eq!(float::explore("-f64::MIN_POSITIVE").unwrap().result, Float::F64(-f64::MIN_POSITIVE));
+
+    assert_eq!(
+        float::explore("f64::BIG").err().unwrap(),
+        "`f64::BIG` isn't one of f64's constants, try NAN, INFINITY, NEG_INFINITY, MAX, MIN, MIN_POSITIVE, EPSILON"
+    );
+}
+
+#[test]
+fn negative_zero_keeps_its_sign() {
+    let rounding = float::explore("-0.0").unwrap();
+    assert_eq!(rounding.result.to_f64().to_bits(), (-0.0f64).to_bits());
+    assert_eq!(rounding.exact.unwrap().to_string(), "-0");
+    assert!(rounding.error.unwrap().is_zero());
+
+    let anatomy = float::anatomy(Float::F32(-0.0));
+    assert_eq!((anatomy.sign, anatomy.class), (1, Class::Zero));
+    assert_eq!(anatomy.exact.unwrap().to_string(), "-0");
+
+    assert_eq!(float::explore("-0.0 * 2.0").unwrap().exact.unwrap().to_string(), "-0");
+    assert_eq!(float::explore("-0.0 + 0.0").unwrap().exact.unwrap().to_string(), "0");
+    assert_eq!(float::anatomy(Float::F64(0.0)).exact.unwrap().to_string(), "0");
+}
+
+#[test]
+fn bad_floats_say_what_is_wrong() {
+    assert_eq!(float::explore("1.5f32 + 2f64").err().unwrap(), "mismatched types, expected `f32`, found `f64`");
+    assert_eq!(float::explore("5").err().unwrap(), "`5` is an integer, try 5.0, or 5 as f32");
+    assert_eq!(float::explore("1.0 ^ 2.0").err().unwrap(), "`^` isn't an operator, try +, -, *, / or %");
+    assert_eq!(float::explore("1.0 as u8").err().unwrap(), "`u8` isn't a float type, only f32 and f64 are");
+    assert!(float::explore("1e40f32").is_err());
+}
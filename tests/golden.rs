@@ -0,0 +1,360 @@
+// golden output tests
+
+// every binary is run with scripted input, and everything it prints, along
+// with its exit status, is compared against a snapshot checked in under
+// tests/snapshots, so any change to what a lesson prints shows up here.
+
+// when a change in output is intended, the snapshots can be rewritten with
+//
+//   UPDATE_SNAPSHOTS=1 cargo test --test golden
+//
+// and the difference reviewed with git diff, like any other change.
+
+use std::env;
+use std::fs;
+use std::io::{ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+mod common;
+
+use common::{mask_addresses, unnumbered};
+
+struct Case {
+    name: &'static str,
+    bin: &'static str,
+    args: &'static [&'static str],
+    stdin: &'static str,
+}
+
+fn bin_path(bin: &str) -> PathBuf {
+    // cargo tells integration tests where it put each binary, through an
+    // environment variable per binary, but only at compile time, so the
+    // lookup has to be spelled out for every one of them.
+    PathBuf::from(match bin {
+        "hello_world" => env!("CARGO_BIN_EXE_hello_world"),
+        "guessing_game" => env!("CARGO_BIN_EXE_guessing_game"),
+        "rust_commons" => env!("CARGO_BIN_EXE_rust_commons"),
+        "rust_commons_variables" => env!("CARGO_BIN_EXE_rust_commons_variables"),
+        "rust_commons_datatypes" => env!("CARGO_BIN_EXE_rust_commons_datatypes"),
+        "rust_commons_functions" => env!("CARGO_BIN_EXE_rust_commons_functions"),
+        "rust_commons_controlflow" => env!("CARGO_BIN_EXE_rust_commons_controlflow"),
+        "rust_commons_ownership" => env!("CARGO_BIN_EXE_rust_commons_ownership"),
+        "rust_commons_refandborrow" => env!("CARGO_BIN_EXE_rust_commons_refandborrow"),
+        "rusty_repl" => env!("CARGO_BIN_EXE_rusty_repl"),
+        _ => panic!("unknown binary {}", bin),
+    })
+}
+
+fn run(case: &Case) -> String {
+    // anything a binary saves, like quiz results, goes somewhere of its own,
+    // and is saved for the same user, whoever runs the tests.
+    let home = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("home").join(case.name);
+    let _ = fs::remove_dir_all(&home);
+
+    let mut child = Command::new(bin_path(case.bin))
+        .args(case.args)
+        .env("RUSTY_HOME", &home)
+        .env("USER", "learner")
+        .env_remove("RUSTY_USER")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("failed to start binary");
+
+    // a binary that never reads its input may well be gone before we're done
+    // writing it, that's fine, as long as it wasn't our fault.
+    if let Err(e) = child.stdin.take().unwrap().write_all(case.stdin.as_bytes()) {
+        assert_eq!(e.kind(), ErrorKind::BrokenPipe, "failed to write stdin");
+    }
+
+    let output = child.wait_with_output().expect("failed to run binary");
+
+    format!(
+        "bin: {}\nargs: {}\nstatus: {}\n--- stdin\n{}--- stdout\n{}--- stderr\n{}",
+        case.bin,
+        case.args.join(" "),
+        output.status.code().map_or("signal".to_string(), |code| code.to_string()),
+        case.stdin,
+        normalize(&String::from_utf8_lossy(&output.stdout), &home),
+        normalize(&String::from_utf8_lossy(&output.stderr), &home),
+    )
+}
+
+// some of the output depends on how fast the machine is, like the rate and
+// eta of the progress reports, on where the tests are run, like the paths
+// of saved files, on where things end up in memory, like the addresses
+// of the Strings in the ownership lesson, or on where in the source they
+// happen, those parts are masked before comparing.
+fn normalize(text: &str, home: &Path) -> String {
+    let home = home.display().to_string();
+    let mut addresses = Vec::new();
+    let mut out = String::new();
+    for line in text.lines() {
+        let line = mask_lines(&mask_time(&mask_rate(&line.replace(&home, "$RUSTY_HOME"))));
+        out.push_str(&mask_addresses(&line, &mut addresses));
+        out.push('\n');
+    }
+    out
+}
+
+// "counting: 50% (3/6), 212706/s, eta 0.0s" => "counting: 50% (3/6), <rate>/s, eta <eta>"
+fn mask_rate(line: &str) -> String {
+    let at = match line.find("/s, eta ") {
+        Some(at) => at,
+        None => return line.to_string(),
+    };
+    let start = line[..at]
+        .rfind(|c: char| !c.is_ascii_digit())
+        .map_or(0, |i| i + 1);
+    let rest = &line[at + "/s, eta ".len()..];
+    let end = rest.find(|c: char| c.is_whitespace()).unwrap_or(rest.len());
+
+    format!("{}<rate>/s, eta <eta>{}", &line[..start], &rest[end..])
+}
+
+// "`- do_sth(999)  [4.2µs]" => "`- do_sth(999)  [<time>]", how long a call
+// took, at the end of a line of the --calls tree.
+fn mask_time(line: &str) -> String {
+    let at = match line.rfind("  [") {
+        Some(at) if line.ends_with("s]") => at,
+        _ => return line.to_string(),
+    };
+    let time = &line[at + 3..line.len() - 1];
+    if !time.starts_with(|c: char| c.is_ascii_digit()) {
+        return line.to_string();
+    }
+    format!("{}  [<time>]", &line[..at])
+}
+
+// "at src/lessons/ownership.rs:329: 1 alloc" => "at src/lessons/ownership.rs:<line>: 1 alloc",
+// where in the source something happened, which moves with every edit above it.
+fn mask_lines(line: &str) -> String {
+    let mut out = String::new();
+    let mut rest = line;
+    while let Some(at) = rest.find(".rs:") {
+        let end = at + ".rs:".len();
+        let digits = rest[end..].find(|c: char| !c.is_ascii_digit()).unwrap_or(rest.len() - end);
+        out.push_str(&rest[..end]);
+        out.push_str(if digits == 0 { "" } else { "<line>" });
+        rest = &rest[end + digits..];
+    }
+    out.push_str(rest);
+    out
+}
+
+fn check(case: Case) {
+    let actual = run(&case);
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("snapshots")
+        .join(format!("{}.snap", case.name));
+
+    if env::var_os("UPDATE_SNAPSHOTS").is_some() {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, &actual).expect("failed to write snapshot");
+        return;
+    }
+
+    let expected = fs::read_to_string(&path).unwrap_or_else(|_| {
+        panic!(
+            "no snapshot at {}, run with UPDATE_SNAPSHOTS=1 to create it\n\n{}",
+            path.display(),
+            actual
+        )
+    });
+
+    if expected != actual {
+        panic!(
+            "output of {} differs from {}\n\n{}\nrun with UPDATE_SNAPSHOTS=1 if this is intended",
+            case.name,
+            path.display(),
+            diff(&expected, &actual)
+        );
+    }
+}
+
+// a plain line by line diff, good enough to spot what changed.
+fn diff(expected: &str, actual: &str) -> String {
+    let expected: Vec<&str> = expected.lines().collect();
+    let actual: Vec<&str> = actual.lines().collect();
+    let mut out = String::new();
+
+    for i in 0..expected.len().max(actual.len()) {
+        match (expected.get(i), actual.get(i)) {
+            (Some(e), Some(a)) if e == a => out.push_str(&format!("  {}\n", e)),
+            (e, a) => {
+                if let Some(e) = e {
+                    out.push_str(&format!("- {}\n", e));
+                }
+                if let Some(a) = a {
+                    out.push_str(&format!("+ {}\n", a));
+                }
+            }
+        }
+    }
+    out
+}
+
+macro_rules! golden {
+    ($name:ident, $bin:expr, [$($arg:expr),*], $stdin:expr) => {
+        #[test]
+        fn $name() {
+            check(Case {
+                name: stringify!($name),
+                bin: $bin,
+                args: &[$($arg),*],
+                stdin: $stdin,
+            });
+        }
+    };
+}
+
+golden!(hello_world, "hello_world", [], "");
+golden!(hello_world_json, "hello_world", ["--format", "json"], "");
+golden!(hello_world_bad_args, "hello_world", ["--nope"], "");
+
+golden!(guessing_game, "guessing_game", ["--seed", "42"], "50\n25\n12\n2**4\n");
+golden!(guessing_game_invalid, "guessing_game", ["--seed", "42"], "fifty\n(3+4\n2^4\n4294967296\n16\n");
+golden!(guessing_game_json, "guessing_game", ["--seed", "42", "--format", "json"], "x\n50\n16\n");
+golden!(guessing_game_eof, "guessing_game", ["--seed", "42"], "50\n");
+
+golden!(rust_commons, "rust_commons", [], "");
+golden!(rust_commons_list, "rust_commons", ["list"], "");
+golden!(rust_commons_list_json, "rust_commons", ["list", "--format", "json"], "");
+golden!(rust_commons_menu, "rust_commons", [], "1\nnope\n0\ncontrolflow\n-2\nq\n");
+golden!(rust_commons_options_before_lesson, "rust_commons", ["--threads", "2", "controlflow"], "2**5\n");
+golden!(rust_commons_menu_for_user, "rust_commons", ["--user", "bob"], "q\n");
+golden!(rust_commons_options_before_command, "rust_commons", ["--seed", "2", "functions", "quiz"], "");
+golden!(rust_commons_run_functions_json, "rust_commons", ["functions", "--format", "json"], "");
+golden!(rust_commons_unknown_lesson, "rust_commons", ["nope"], "");
+golden!(rust_commons_unknown_command, "rust_commons", ["variables", "bench"], "");
+golden!(rust_commons_variables, "rust_commons_variables", [], "");
+golden!(rust_commons_variables_json, "rust_commons_variables", ["--format", "json"], "");
+golden!(rust_commons_datatypes, "rust_commons_datatypes", [], "");
+golden!(rust_commons_datatypes_json, "rust_commons_datatypes", ["--format", "json"], "");
+golden!(rust_commons_datatypes_overflow, "rust_commons_datatypes", ["overflow"], "250u8 + 10\n7 % 0\n1u8 << 9\n2i32 pow 31\nneg 5u8\n300u8 + 1\n1u8 + 2i32\n3_000_000_000u32 - 4_000_000_000\nq\n");
+golden!(rust_commons_datatypes_overflow_json, "rust_commons", ["datatypes", "overflow", "--format", "json"], "neg -128i64\n5 ^ 3\n");
+golden!(rust_commons_datatypes_literal, "rust_commons_datatypes", ["literal"], "0b1111_0000\n0b102\n1.5u8\nb'A'\n-1u8\n1e40f32\n0x8000_0000i32\n\nq\n");
+golden!(rust_commons_datatypes_literal_json, "rust_commons", ["datatypes", "literal", "--format", "json"], "0xffi8\n2.5E-4\n");
+golden!(rust_commons_datatypes_float, "rust_commons_datatypes", ["float"], "0.1\n16777217 as f32\n1.0 / 3.0\n1e-45f32\n1e308 + 1e308\n-0.0\n1.5f32 + 2f64\nq\n");
+golden!(rust_commons_datatypes_float_json, "rust_commons", ["datatypes", "float", "--format", "json"], "0.7f32 - 0.6\n5\n");
+golden!(rust_commons_datatypes_layout, "rust_commons_datatypes", ["layout"], "(u8, u32, u16, u64)\n[(u8, u16); 3]\nVec<i32>\n#[repr(C)] struct Point { x: u8, y: u32, z: u16 }\nstruct Point { x: u8, y: u32, z: u16 }\nstruct A { x: Missing }\nstruct W<T> { x: T }\nu128\nq\n");
+golden!(rust_commons_datatypes_layout_json, "rust_commons", ["datatypes", "layout", "--format", "json"], "(bool, char)\nstruct Empty;\n");
+golden!(rust_commons_datatypes_unicode, "rust_commons_datatypes", ["unicode"], "cafe\\u{301}\n\n👨\u{200d}👩\u{200d}👧 🇯🇵\n한 \u{1112}\u{1161}\u{11ab}\nq\n");
+golden!(rust_commons_datatypes_unicode_json, "rust_commons", ["datatypes", "unicode", "--format", "json"], "½€\n");
+golden!(rust_commons_functions, "rust_commons_functions", [], "");
+golden!(rust_commons_functions_json, "rust_commons_functions", ["--format", "json"], "");
+golden!(rust_commons_functions_calls, "rust_commons_functions", ["--calls"], "");
+golden!(rust_commons_variables_calls, "rust_commons", ["variables", "--calls", "--quiet"], "");
+golden!(rust_commons_ownership, "rust_commons_ownership", [], "");
+golden!(rust_commons_ownership_json, "rust_commons_ownership", ["--format", "json"], "");
+golden!(rust_commons_refandborrow, "rust_commons_refandborrow", [], "");
+golden!(rust_commons_refandborrow_json, "rust_commons_refandborrow", ["--format", "json"], "");
+golden!(rust_commons_refandborrow_quiz, "rust_commons_refandborrow", ["quiz", "--seed", "3"], "yes\nd\nb\nd\nno\nhello\nmaybe\nyes\n");
+golden!(rust_commons_refandborrow_exercise, "rust_commons", ["refandborrow", "exercise"], "dangling_reference\ncheck\n");
+golden!(rust_commons_ownership_step, "rust_commons", ["ownership", "--step"], "\n\nl\nr\np\nx\n9\n7\n\n\n");
+golden!(rust_commons_functions_step_json, "rust_commons_functions", ["--step", "--format", "json"], "\n\n\nq\n");
+golden!(rust_commons_variables_quiz, "rust_commons_variables", ["quiz", "--seed", "1"], "n\nmaybe\na\nx\n4\nno\nd\n");
+golden!(rust_commons_ownership_quiz_json, "rust_commons", ["ownership", "quiz", "--seed", "7", "--format", "json"], "no\nd\nhello hello\nno\na\nyes\nb\n");
+golden!(rust_commons_functions_quiz_eof, "rust_commons_functions", ["quiz", "--seed", "2", "--user", "alice"], "50\n");
+golden!(rust_commons_quiz_bad_user, "rust_commons", ["functions", "quiz", "--user", "../alice"], "");
+golden!(rust_commons_ownership_exercise, "rust_commons", ["ownership", "exercise"], "use_after_move\n\npaste\nfn main() {\n    let s1 = String::from(\"hello\");\n    let s2 = s1;\n    println!(\"{}, world!\", s1);\n}\n.\n\npaste\nfn main() {\n    let s1 = String::from(\"hello\");\n    let s2 = s1.clone();\n    println!(\"{}, world!\", s1);\n}\n.\ncheck\n");
+golden!(rust_commons_functions_exercise_json, "rust_commons_functions", ["exercise", "--format", "json"], "2\nshow\n\n");
+golden!(rust_commons_dashboard, "rust_commons", ["dashboard"], "");
+golden!(rust_commons_dashboard_json, "rust_commons", ["dashboard", "--user", "alice", "--format", "json"], "");
+golden!(rust_commons_profiles, "rust_commons", ["profiles"], "");
+golden!(rust_commons_export, "rust_commons", ["export"], "");
+golden!(rust_commons_export_unknown_format, "rust_commons", ["export", "pdf"], "");
+golden!(rusty_repl, "rusty_repl", [], "let x = 5;\nlet x = x * 2;\nlet y: u8 = x;\nlet mut n = 250u8;\nn += 10;\nlet t = (n, 2.5, 'c');\n{\n    let z = t.1 * 2.0;\n    [z; 2]\n}\n:t x as f32 / 2.0\n:vars\n:quit\n");
+golden!(rusty_repl_json, "rusty_repl", ["--format", "json"], "let a = [1, 2, 3];\na[1] + a[2]\nlet a = -5u32;\n:reset\nif a[0] > 1 { \"big\" } else { \"small\" }\n");
+golden!(rust_commons_controlflow_step_eof, "rust_commons_controlflow", ["--step"], "0\n");
+
+golden!(rust_commons_controlflow, "rust_commons_controlflow", [], "0\n(3+4)*5)\n10/(5-5)\n2**5\n");
+golden!(rust_commons_controlflow_negative, "rust_commons_controlflow", [], "-0x10\n");
+golden!(rust_commons_controlflow_verbose, "rust_commons_controlflow", ["--verbose"], "-3\n");
+golden!(rust_commons_controlflow_quiet, "rust_commons_controlflow", ["--quiet"], "100\n");
+golden!(rust_commons_controlflow_threads, "rust_commons_controlflow", ["--threads", "3"], "2**5\n");
+golden!(rust_commons_controlflow_json, "rust_commons_controlflow", ["--format", "json"], "x\n0\n7\n");
+golden!(rust_commons_controlflow_eof, "rust_commons_controlflow", [], "0\n");
+golden!(rust_commons_controlflow_bad_args, "rust_commons_controlflow", ["--threads", "0"], "");
+
+// running a lesson through the launcher, or through its own binary, has to
+// print the same thing.
+#[test]
+fn rust_commons_lessons_match_their_binaries() {
+    let lessons: [(&'static [&'static str], &'static str); 6] = [
+        (&["variables"], "rust_commons_variables"),
+        (&["datatypes"], "rust_commons_datatypes"),
+        (&["functions"], "rust_commons_functions"),
+        (&["controlflow"], "rust_commons_controlflow"),
+        (&["ownership"], "rust_commons_ownership"),
+        (&["refandborrow"], "rust_commons_refandborrow"),
+    ];
+
+    // whether the allocator hands a freed buffer out again depends on what
+    // was allocated before the lesson started, which isn't the same for the
+    // two, so only where an address is counts, not which one it is.
+    for &(args, bin) in lessons.iter() {
+        let stdout = |output: String| output.split("--- stdout").nth(1).unwrap().lines().map(unnumbered).collect::<Vec<_>>();
+        let launched = run(&Case { name: "launched", bin: "rust_commons", args, stdin: "12\n" });
+        let direct = run(&Case { name: "direct", bin, args: &[], stdin: "12\n" });
+        assert_eq!(stdout(launched), stdout(direct), "{} differs", bin);
+    }
+}
+
+// the threaded counting has to print exactly what the single threaded one
+// does, however the work happens to be split up.
+#[test]
+fn rust_commons_controlflow_threads_are_invisible() {
+    let thread_counts: [&'static [&'static str]; 3] = [
+        &["--threads", "1"],
+        &["--threads", "2"],
+        &["--threads", "5"],
+    ];
+
+    let outputs: Vec<String> = thread_counts
+        .iter()
+        .map(|args| {
+            let output = run(&Case {
+                name: "threads",
+                bin: "rust_commons_controlflow",
+                args,
+                stdin: "-1234\n",
+            });
+            // everything but the echoed arguments has to match.
+            output.lines().skip(2).collect::<Vec<_>>().join("\n")
+        })
+        .collect();
+
+    assert_eq!(outputs[0], outputs[1]);
+    assert_eq!(outputs[0], outputs[2]);
+}
+
+#[test]
+fn rust_commons_controlflow_verbose_threads_count_in_order() {
+    // enough counters for every worker to report more than once, in both
+    // directions.
+    for stdin in &["-20000\n", "20000\n"] {
+        let thread_counts: [&'static [&'static str]; 2] = [
+            &["--threads", "1", "--verbose"],
+            &["--threads", "3", "--verbose"],
+        ];
+        let outputs: Vec<String> = thread_counts
+            .iter()
+            .map(|args| {
+                let output = run(&Case {
+                    name: "verbose threads",
+                    bin: "rust_commons_controlflow",
+                    args,
+                    stdin,
+                });
+                output.lines().skip(2).collect::<Vec<_>>().join("\n")
+            })
+            .collect();
+
+        assert!(outputs[0].contains("currently at"));
+        assert_eq!(outputs[0], outputs[1]);
+    }
+}
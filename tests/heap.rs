@@ -0,0 +1,57 @@
+// counting allocations
+
+// the counting allocator is installed here the same way the rust_commons
+// binaries do it, and every trace is checked against what a String is known
+// to do on the heap, the tests run side by side, but each thread has its own
+// log, so they don't see each other's allocations.
+
+extern crate rusty;
+
+use rusty::heap::{self, Counting, Event};
+
+#[global_allocator]
+static COUNTING: Counting = Counting;
+
+#[test]
+fn a_move_is_free_and_a_clone_is_not() {
+    let (s1, trace) = heap::trace(|| String::from("hello"));
+    assert!(heap::installed());
+    assert_eq!(trace.events, vec![Event::Alloc(5)]);
+    assert_eq!(trace.site.file(), file!());
+
+    let (s2, trace) = heap::trace(move || s1);
+    assert!(trace.events.is_empty());
+    assert_eq!(trace.summary(), "nothing");
+
+    let (s3, trace) = heap::trace(|| s2.clone());
+    assert_eq!(trace.events, vec![Event::Alloc(5)]);
+
+    let (_, trace) = heap::trace(move || {
+        let mut s = s3;
+        s.push_str(", world!");
+        drop(s);
+        drop(s2);
+    });
+    assert_eq!(trace.events, vec![Event::Realloc(5, 13), Event::Dealloc(13), Event::Dealloc(5)]);
+    assert_eq!(trace.summary(), "1 realloc, 2 deallocs");
+}
+
+#[test]
+fn a_trace_keeps_what_fits_and_counts_the_rest() {
+    let (_, trace) = heap::trace(|| {
+        for i in 0..300 {
+            drop(vec![0u8; i + 1]);
+        }
+    });
+    assert_eq!(trace.events.len() + trace.lost, 600);
+    assert_eq!((trace.allocs(), trace.deallocs()), (128, 128));
+    assert_eq!(trace.summary(), "128 allocs, 128 deallocs, and 344 more");
+
+    // a trace inside another one takes its events with it.
+    let ((_, inner), outer) = heap::trace(|| {
+        let _outer = Box::new(1u32);
+        heap::trace(|| drop(Box::new(1u64)))
+    });
+    assert_eq!(inner.events, vec![Event::Alloc(8), Event::Dealloc(8)]);
+    assert_eq!(outer.events, vec![Event::Alloc(4), Event::Dealloc(4)]);
+}
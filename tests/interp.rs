@@ -0,0 +1,179 @@
+// the interpreter behind rusty_repl
+
+// every line is checked the way rustc would check it, and run the way a
+// debug build would run it, so what it says has to match what rust says,
+// values, types, error codes, and panics alike.
+
+extern crate rusty;
+
+use rusty::interp::{ErrorKind, Outcome, Session};
+
+// runs the lines one after the other, and returns what the last one gave,
+// as "value: type", or "name: type = value" for the last variable it made.
+fn run(lines: &[&str]) -> String {
+    let mut session = Session::new();
+    let mut last = String::new();
+    for line in lines {
+        let outcomes = session.run(line).unwrap_or_else(|e| panic!("{}\n{}", line, e.render(line)));
+        last = match outcomes.last() {
+            Some(&Outcome::Bound(ref b, _)) | Some(&Outcome::Assigned(ref b)) => format!("{}: {} = {}", b.name, b.ty, b.value),
+            Some(Outcome::Value(ty, value)) => format!("{}: {}", value, ty),
+            None => String::new(),
+        };
+    }
+    last
+}
+
+// the error the last line gives, as rustc, or a panic, would start it.
+fn error(lines: &[&str]) -> String {
+    let mut session = Session::new();
+    let (last, earlier) = lines.split_last().unwrap();
+    for line in earlier {
+        session.run(line).unwrap();
+    }
+    session.run(last).unwrap_err().headline()
+}
+
+#[test]
+fn values_come_with_their_types() {
+    assert_eq!(run(&["1 + 2 * 3"]), "7: i32");
+    assert_eq!(run(&["7 / 2"]), "3: i32");
+    assert_eq!(run(&["7.0 / 2.0"]), "3.5: f64");
+    assert_eq!(run(&["0.1f32 + 0.2f32"]), "0.3: f32");
+    assert_eq!(run(&["0xff_u8"]), "255: u8");
+    assert_eq!(run(&["1_000_000u64 * 1_000_000"]), "1000000000000: u64");
+    assert_eq!(run(&["'z'"]), "'z': char");
+    assert_eq!(run(&["\"hi\\n\""]), "\"hi\\n\": &str");
+    assert_eq!(run(&["!true || 3 >= 3"]), "true: bool");
+    assert_eq!(run(&["-1i32 as u32"]), "4294967295: u32");
+    assert_eq!(run(&["300 as u8"]), "44: u8");
+    assert_eq!(run(&["-2.9f64 as u8"]), "0: u8");
+    assert_eq!(run(&["'a' as u8 + 1"]), "98: u8");
+    assert_eq!(run(&["-128i8"]), "-128: i8");
+}
+
+#[test]
+fn literals_take_the_type_they_are_used_as() {
+    // a literal on its own is an i32, or an f64.
+    assert_eq!(run(&["let x = 5;"]), "x: i32 = 5");
+    assert_eq!(run(&["let x = 5.0;"]), "x: f64 = 5.0");
+    // but the other side of an operator, or an annotation, can change that.
+    assert_eq!(run(&["let x = 5 + 1u8;"]), "x: u8 = 6");
+    assert_eq!(run(&["let x: i64 = 5;"]), "x: i64 = 5");
+    assert_eq!(run(&["let x: (u8, f32) = (1, 2.5);"]), "x: (u8, f32) = (1, 2.5)");
+    assert_eq!(run(&["let x = [1, 2u16, 3];"]), "x: [u16; 3] = [1, 2, 3]");
+    assert_eq!(run(&["let x = 2u8;", "x * 100"]), "200: u8");
+}
+
+#[test]
+fn shadowing_makes_a_new_variable() {
+    let mut session = Session::new();
+    session.run("let x = 5;").unwrap();
+    let outcomes = session.run("let x = x > 3;").unwrap();
+    match outcomes[0] {
+        Outcome::Bound(ref binding, Some(ref shadowed)) => {
+            assert_eq!(binding.ty.to_string(), "bool");
+            assert_eq!(shadowed.to_string(), "i32");
+        }
+        ref other => panic!("expected a shadowing binding, got {:?}", other),
+    }
+    assert_eq!(session.variables().len(), 1);
+
+    // shadowing in a block lasts until the end of the block.
+    assert_eq!(run(&["let x = 5;", "let y = { let x = x * 2; x + 1 };", "x + y"]), "16: i32");
+}
+
+#[test]
+fn tuples_arrays_and_blocks_are_expressions() {
+    assert_eq!(run(&["let t = (1, (2.5, 'c'));", "t.1.1"]), "'c': char");
+    assert_eq!(run(&["let (a, mut b) = (1, 2);", "b += a;", "b"]), "3: i32");
+    assert_eq!(run(&["[0u8; 3]"]), "[0, 0, 0]: [u8; 3]");
+    assert_eq!(run(&["let a = [10, 20, 30];", "a[2] - a[0]"]), "20: i32");
+    assert_eq!(run(&["{ let y = 3; y * y }"]), "9: i32");
+    assert_eq!(run(&["{ 1; }"]), "(): ()");
+    assert_eq!(run(&["if 1 > 2 { \"yes\" } else if 2 > 1 { \"maybe\" } else { \"no\" }"]), "\"maybe\": &str");
+    assert_eq!(run(&["(5,)"]), "(5,): (i32,)");
+}
+
+#[test]
+fn what_rustc_rejects_is_rejected() {
+    assert_eq!(error(&["let x: u8 = 5i32;"]), "error[E0308]: mismatched types, expected `u8`, found `i32`");
+    assert_eq!(error(&["5u8 + 5i32"]), "error[E0277]: cannot add `i32` to `u8`");
+    assert_eq!(error(&["1 + 2.0"]), "error[E0277]: cannot add `{float}` to `{integer}`");
+    assert_eq!(error(&["\"a\" + \"b\""]), "error[E0369]: cannot add `&str` to `&str`");
+    assert_eq!(error(&["let x = 5;", "x = 6;"]), "error[E0384]: cannot assign twice to immutable variable `x`, it needs a `let mut`");
+    assert_eq!(error(&["y"]), "error[E0425]: cannot find value `y` in this scope");
+    assert_eq!(error(&["let x: Foo = 1;"]), "error[E0412]: cannot find type `Foo` in this scope");
+    assert_eq!(error(&["-1u32"]), "error[E0600]: cannot apply unary operator `-` to type `u32`, unsigned values cannot be negated");
+    assert_eq!(error(&["65u32 as char"]), "error[E0604]: only `u8` can be cast as `char`, not `u32`");
+    assert_eq!(error(&["1 as bool"]), "error[E0054]: cannot cast `i32` as `bool`");
+    assert_eq!(error(&["(1, 2).2"]), "error[E0609]: no field `2` on type `({integer}, {integer})`");
+    assert_eq!(error(&["let a = [];"]), "error[E0282]: type annotations needed for `[_; 0]`");
+    assert_eq!(error(&["if true { 1 }"]), "error[E0317]: `if` may be missing an `else` clause, expected `()`, found `{integer}`");
+    assert_eq!(error(&["let x: u8 = 256;"]), "error: literal out of range for `u8`, whose range is `0..=255`");
+    assert_eq!(error(&["1 < 2 < 3"]), "error: comparison operators cannot be chained");
+    assert_eq!(error(&["1e400"]), "error: literal out of range for `f64`, it would be `f64::INFINITY`");
+    assert_eq!(error(&["let x: f32 = 1e39;"]), "error: literal out of range for `f32`, it would be `f32::INFINITY`");
+    assert_eq!(run(&["-1e308"]), "-1e308: f64");
+}
+
+#[test]
+fn lines_only_nest_so_deep() {
+    // deep, but not too deep, still runs, on a test thread's small stack.
+    let deep = format!("{}1{}", "(".repeat(60), ")".repeat(60));
+    assert_eq!(run(&[&deep]), "1: i32");
+    assert_eq!(run(&[&format!("{}1{}", "{".repeat(60), "}".repeat(60))]), "1: i32");
+    assert_eq!(run(&[&vec!["1"; 60].join(" + ")]), "60: i32");
+
+    let too_deep = "error: too deeply nested, at most 64 levels are allowed here";
+    let parens = "(".repeat(5000);
+    let e = Session::new().run(&parens).unwrap_err();
+    assert_eq!((e.kind, e.headline()), (ErrorKind::Compile, too_deep.to_string()));
+    assert_eq!(e.span.start, 64);
+
+    assert_eq!(error(&[&format!("{}1", "-".repeat(5000))]), too_deep);
+    assert_eq!(error(&[&vec!["1"; 5000].join(" + ")]), too_deep);
+    assert_eq!(error(&[&format!("1{}", " as i64".repeat(5000))]), too_deep);
+    assert_eq!(error(&["let a = [1];", &format!("a{}", "[0]".repeat(5000))]), too_deep);
+    assert_eq!(error(&[&format!("if true {{ 1 }}{} else {{ 2 }}", " else if true { 1 }".repeat(5000))]), too_deep);
+    assert_eq!(error(&[&format!("let x: {}i32 = 1;", "(".repeat(5000))]), too_deep);
+    assert_eq!(error(&[&format!("let {}a = 1;", "(".repeat(5000))]), too_deep);
+    assert_eq!(error(&[&"{".repeat(5000)]), too_deep);
+}
+
+#[test]
+fn arithmetic_is_checked_like_in_a_debug_build() {
+    assert_eq!(error(&["255u8 + 1"]), "panicked: attempt to add with overflow");
+    assert_eq!(error(&["0u32 - 1"]), "panicked: attempt to subtract with overflow");
+    assert_eq!(error(&["i32::MAX"]), "error: paths, like `i32::..`, aren't supported here, only values, operators, tuples, arrays and blocks");
+    assert_eq!(error(&["let x = -128i8;", "-x"]), "panicked: attempt to negate with overflow");
+    assert_eq!(error(&["1 / 0"]), "panicked: attempt to divide by zero");
+    assert_eq!(error(&["[1, 2, 3][5]"]), "panicked: index out of bounds: the len is 3 but the index is 5");
+
+    // a line that panics halfway leaves the variables as they were.
+    let mut session = Session::new();
+    session.run("let mut x = 250u8;").unwrap();
+    assert!(session.run("let y = 1; x += 10;").is_err());
+    assert_eq!(session.variables().len(), 1);
+    assert_eq!(session.variables()[0].value.to_string(), "250");
+}
+
+#[test]
+fn unfinished_input_is_told_apart_from_wrong_input() {
+    let session = Session::new();
+    for input in &["1 +", "{ let x = 1;", "let x =", "(1, 2", "\"abc"] {
+        assert_eq!(session.clone().run(input).unwrap_err().kind, ErrorKind::Incomplete, "{}", input);
+    }
+    for input in &["1 + )", "let = 5;", "5 5"] {
+        assert_eq!(session.clone().run(input).unwrap_err().kind, ErrorKind::Compile, "{}", input);
+    }
+    assert_eq!(run(&["{\n    let x = 1;\n    x + 1\n}"]), "2: i32");
+}
+
+#[test]
+fn errors_point_at_the_offending_line() {
+    let input = "{\n    let x = 1u8;\n    x + 1i32\n}";
+    let e = Session::new().run(input).unwrap_err();
+    assert_eq!(e.line(input), 3);
+    assert_eq!(e.render(input), "    x + 1i32\n    ^^^^^^^^ error[E0277]: cannot add `i32` to `u8`");
+}
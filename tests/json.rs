@@ -0,0 +1,53 @@
+// reading json back in
+
+// whatever the json module writes, it has to be able to read back, and it
+// has to read what others write, like rustc's diagnostics.
+
+extern crate rusty;
+
+use rusty::json::{self, Value};
+
+#[test]
+fn values_survive_a_round_trip() {
+    let value = Value::object(vec![
+        ("null", Value::Null),
+        ("bool", Value::Bool(true)),
+        ("int", Value::Int(-170141183460469231731687303715884105728)),
+        ("uint", Value::UInt(340282366920938463463374607431768211455)),
+        ("float", Value::Float(23.32)),
+        ("str", Value::Str("a \"quoted\" \\ line\nwith a tab\t, a \u{1} and a 😻".to_string())),
+        ("array", Value::Array(vec![Value::Array(vec![]), Value::Object(vec![])])),
+    ]);
+
+    assert_eq!(json::parse(&value.to_string()), Ok(value));
+}
+
+#[test]
+fn json_written_by_others_is_read() {
+    let value = json::parse(" {\"a\" : [1, -2, 3.5e2, \"\\u00e9\\ud83d\\ude3b\\/\"],\n \"b\": {\"c\": null}} ").unwrap();
+
+    assert_eq!(value.get("a").and_then(Value::as_array).map(|a| a.len()), Some(4));
+    assert_eq!(value.get("a").unwrap().as_array().unwrap()[1].as_i128(), Some(-2));
+    assert_eq!(value.get("a").unwrap().as_array().unwrap()[2], Value::Float(350.0));
+    assert_eq!(value.get("a").unwrap().as_array().unwrap()[3].as_str(), Some("é😻/"));
+    assert_eq!(value.get("b").and_then(|b| b.get("c")), Some(&Value::Null));
+    assert_eq!(value.get("nope"), None);
+}
+
+#[test]
+fn broken_json_is_reported_with_its_position() {
+    let cases = [
+        ("", "unexpected end of input at byte 0"),
+        ("[1, 2", "expected `,` or `]` at byte 5"),
+        ("{\"a\" 1}", "expected `:` at byte 5"),
+        ("\"abc", "unterminated string at byte 4"),
+        ("tru", "expected `true` at byte 0"),
+        ("1 2", "expected the end of the input at byte 2"),
+        ("\"\\x\"", "invalid escape at byte 3"),
+        ("-", "invalid number `-` at byte 0"),
+    ];
+
+    for &(text, expected) in cases.iter() {
+        assert_eq!(json::parse(text), Err(expected.to_string()), "{:?}", text);
+    }
+}
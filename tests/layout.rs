@@ -0,0 +1,88 @@
+// memory layouts
+
+// the known layouts are measured, so they're checked against the rules
+// every layout follows, whatever order rustc picks, and a struct that's
+// typed in is measured by rustc, so a #[repr(C)] one has to come out the
+// way C lays it out.
+
+extern crate rusty;
+
+use rusty::layout::{self, Layout};
+
+// every field is aligned, none of them overlap, and with the padding, they
+// add up to the size, which is a multiple of the alignment.
+fn check(layout: &Layout) {
+    assert_eq!(layout.size % layout.align, 0, "{}", layout.ty);
+    let mut end = 0;
+    for (_, field) in layout.in_memory() {
+        assert_eq!(field.offset % field.align, 0, "{}{}", layout.ty, field.name);
+        assert!(field.offset >= end, "{} overlaps at {}", layout.ty, field.name);
+        end = field.offset + field.size;
+    }
+    let padding: usize = layout.padding().iter().map(|&(_, len)| len).sum();
+    let fields: usize = layout.fields.iter().map(|field| field.size).sum();
+    if layout.insides.is_none() {
+        assert_eq!(fields + padding, layout.size, "{}", layout.ty);
+    }
+    assert_eq!(layout.diagram().chars().filter(|&c| c != ' ').count(), layout.size);
+}
+
+#[test]
+fn the_known_layouts_follow_the_rules() {
+    for layout in layout::known() {
+        check(&layout);
+    }
+
+    let tuple = layout::find("(i32,f64,u8)").unwrap();
+    assert_eq!((tuple.size, tuple.align), (16, 8));
+    assert_eq!(tuple.padding(), vec![(13, 3)]);
+    let array = layout::find("[i32; 4]").unwrap();
+    assert_eq!(array.diagram(), "00001111 22223333");
+    assert!(layout::find("(u64, u64)").is_none());
+
+    let niches = layout::niches();
+    let size = |ty: &str| niches.iter().find(|niche| niche.ty == ty).map(|niche| (niche.size, niche.option_size)).unwrap();
+    assert_eq!(size("&i32"), (8, 8));
+    assert_eq!(size("u32"), (4, 8));
+}
+
+#[test]
+fn a_struct_is_measured_by_rustc() {
+    let definition = layout::parse("#[repr(C)] struct Point { x: u8, pub y: u32, z: (u8, u16) }").unwrap();
+    assert_eq!(definition.name, "Point");
+    let point = match layout::measure(&definition) {
+        Ok(point) => point,
+        Err(layout::Error::Rustc(e)) => return eprintln!("skipped, rustc isn't there: {}", e),
+        Err(layout::Error::Rejected(errors)) => panic!("{:?}", errors),
+    };
+    check(&point);
+    assert_eq!((point.size, point.align), (12, 4));
+    assert_eq!(point.diagram(), "0...1111 2222");
+
+    // rustc is free to reorder a struct that isn't #[repr(C)].
+    let definition = layout::parse("struct Pair(u8, u64, u8);").unwrap();
+    let pair = layout::measure(&definition).ok().unwrap();
+    check(&pair);
+    assert_eq!(pair.size, 16);
+
+    let definition = layout::parse("struct Broken { x: Missing }").unwrap();
+    match layout::measure(&definition) {
+        Err(layout::Error::Rejected(errors)) => assert_eq!(errors.len(), 1),
+        _ => panic!("Missing isn't a type"),
+    }
+}
+
+#[test]
+fn only_a_struct_gets_through() {
+    let error = |source: &str| layout::parse(source).err().unwrap();
+    assert!(error("enum E { A }").starts_with("a struct starts with"));
+    assert!(error("struct W<T> { x: T }").starts_with("a generic struct"));
+    assert_eq!(error("struct A { x: u8 } fn main() {} struct B { y: u8 }"), "the fields can't have braces in them");
+    assert_eq!(error("struct A(u8); static B: (u8) = (1)"), "the brackets in the fields don't match up");
+    assert_eq!(error("struct A(u8), u16)"), "the brackets in the fields don't match up");
+    assert_eq!(error("#[no_mangle] struct A;"), "`#[no_mangle]` doesn't change the layout, only #[repr(..)] does");
+
+    let definition = layout::parse("pub struct F { f: fn(u8) -> u8, g: [u16; 2] }").unwrap();
+    assert_eq!(definition.fields, vec![("f".to_string(), "fn(u8) -> u8".to_string()), ("g".to_string(), "[u16; 2]".to_string())]);
+    assert_eq!(layout::parse("struct Unit").unwrap().source, "struct Unit;");
+}
@@ -0,0 +1,219 @@
+// the lesson registry
+
+// every lesson is run through the Lesson trait, with its input given as a
+// string, and its output captured, no binaries or terminals involved.
+
+extern crate rusty;
+
+use rusty::context::{Context, Options};
+use rusty::cli::Verbosity;
+use rusty::lessons::{self, export, step, LESSONS};
+use rusty::output::Format;
+
+mod common;
+
+// enough input for any lesson that asks for a number.
+const INPUT: &str = "12\n";
+
+#[test]
+fn ids_are_unique() {
+    for (i, lesson) in LESSONS.iter().enumerate() {
+        for other in LESSONS[i + 1..].iter() {
+            assert_ne!(lesson.id(), other.id());
+        }
+    }
+}
+
+#[test]
+fn prerequisites_come_first() {
+    for (i, lesson) in LESSONS.iter().enumerate() {
+        for id in lesson.prerequisites() {
+            let at = LESSONS.iter().position(|other| other.id() == *id);
+            assert!(at.is_some(), "{} requires unknown lesson {}", lesson.id(), id);
+            assert!(at.unwrap() < i, "{} requires the later lesson {}", lesson.id(), id);
+        }
+    }
+}
+
+#[test]
+fn lessons_can_be_found_by_id() {
+    for lesson in LESSONS.iter() {
+        assert_eq!(lessons::find(lesson.id()).unwrap().title(), lesson.title());
+    }
+    assert!(lessons::find("nope").is_none());
+}
+
+#[test]
+fn lessons_run_with_injected_io() {
+    for lesson in LESSONS.iter() {
+        let (mut ctx, capture) = Context::capture(Format::Human, Options::default(), INPUT);
+        lesson.run(&mut ctx);
+        assert!(!ctx.aborted(), "{} ran out of input", lesson.id());
+        assert!(!capture.contents().is_empty(), "{} printed nothing", lesson.id());
+    }
+}
+
+#[test]
+fn lessons_emit_only_json_in_json_mode() {
+    for lesson in LESSONS.iter() {
+        let (mut ctx, capture) = Context::capture(Format::Json, Options::default(), INPUT);
+        lesson.run(&mut ctx);
+
+        for line in capture.contents().lines() {
+            assert!(
+                line.starts_with("{\"schema\":\"rusty.v1\",\"bin\":\"rust_commons\",\"event\":"),
+                "{} printed a line that is not an event: {}",
+                lesson.id(),
+                line
+            );
+        }
+    }
+}
+
+#[test]
+fn lessons_stop_when_input_runs_out() {
+    let controlflow = lessons::find("controlflow").unwrap();
+    let (mut ctx, capture) = Context::capture(Format::Human, Options::default(), "0\n");
+    controlflow.run(&mut ctx);
+
+    assert!(ctx.aborted());
+    assert!(capture.contents().ends_with("no more input, giving up!\n"));
+}
+
+#[test]
+fn errors_are_events_and_fail_the_run() {
+    let (mut ctx, capture) = Context::capture(Format::Json, Options::default(), "");
+    ctx.error("mismatch at 10!");
+
+    assert!(ctx.aborted());
+    assert!(capture.contents().contains("\"event\":\"error\",\"message\":\"mismatch at 10!\""));
+}
+
+#[test]
+fn every_section_has_notes() {
+    for lesson in LESSONS.iter() {
+        for section in lesson.sections() {
+            for function in section.functions {
+                assert!(
+                    lesson.source().contains(&format!("fn {}(", function)),
+                    "{} has no function {}",
+                    lesson.id(),
+                    function
+                );
+            }
+            assert!(
+                !step::section_notes(*lesson, section).is_empty(),
+                "{} has nothing to say about {}",
+                lesson.id(),
+                section.title
+            );
+        }
+    }
+}
+
+#[test]
+fn notes_are_the_comments_in_a_function() {
+    let source = "fn one() {\n    // first\n    // paragraph\n    let x = 1; // left out\n\n    { // a scope\n        // second\n    }\n}\n\nfn two() {\n    // third\n}\n";
+
+    assert_eq!(step::notes(source, &["one"]), vec!["first\nparagraph", "a scope\nsecond"]);
+    assert_eq!(step::notes(source, &["two", "one"]), vec!["third", "first\nparagraph", "a scope\nsecond"]);
+    assert!(step::notes(source, &["three"]).is_empty());
+}
+
+#[test]
+fn notes_can_be_under_a_heading() {
+    let source = "//FIRST\n\n// one\n// note\n\nconst X: u32 = 1;\n\n// SECOND\n// two\n\n// three\n\nfn four() {\n    // four\n}\n";
+    let titles = ["FIRST", "SECOND"];
+
+    assert_eq!(step::heading_notes(source, "FIRST", &titles), vec!["one\nnote"]);
+    assert_eq!(step::heading_code(source, "FIRST", &titles), "const X: u32 = 1;");
+    assert_eq!(step::heading_notes(source, "SECOND", &titles), vec!["two", "three"]);
+    assert_eq!(step::heading_code(source, "SECOND", &titles), "");
+    assert!(step::heading_notes(source, "THIRD", &titles).is_empty());
+}
+
+#[test]
+fn code_is_a_function_without_its_comments() {
+    let source = "fn one() {\n\n    // a note\n    let x = 1; // kept\n\n\n    let y = 2;\n\n}\n\nfn two() {\n    two();\n}\n";
+
+    assert_eq!(step::code(source, &["one"]), "fn one() {\n    let x = 1; // kept\n\n    let y = 2;\n}");
+    assert_eq!(step::code(source, &["two", "three"]), "fn two() {\n    two();\n}");
+}
+
+// the book is made by running the sections, one at a time, which has to
+// print the same as running the whole lesson.
+#[test]
+fn the_book_prints_what_the_lessons_print() {
+    let options = Options { verbosity: Verbosity::Quiet, ..Options::default() };
+    let printed = |text: &str| -> Vec<String> {
+        // the book and the lesson don't allocate the same things, so only
+        // where an address is counts.
+        let mut addresses = Vec::new();
+        text.lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| common::unnumbered(&common::mask_addresses(line, &mut addresses)))
+            .collect()
+    };
+
+    for lesson in LESSONS.iter() {
+        let (mut ctx, capture) = Context::capture(Format::Human, options.clone(), lesson.example_input());
+        lesson.run(&mut ctx);
+        assert!(!ctx.aborted(), "{} needs more example input", lesson.id());
+
+        let chapter = export::chapter(*lesson);
+        let pages: Vec<&str> = chapter.pages.iter().map(|page| page.output.as_str()).collect();
+        assert_eq!(printed(&pages.join("\n")), printed(&capture.contents()), "{}", lesson.id());
+    }
+}
+
+#[test]
+fn the_html_book_links_every_page() {
+    let chapters: Vec<export::Chapter> = LESSONS.iter().map(|lesson| export::chapter(*lesson)).collect();
+    let html = export::html("a <book>", &chapters);
+
+    assert!(html.contains("<title>a &lt;book&gt;</title>"));
+    for chapter in chapters.iter() {
+        for (i, page) in chapter.pages.iter().enumerate() {
+            let anchor = format!("{}-{}", chapter.lesson.id(), i + 1);
+            assert!(html.contains(&format!("<a href=\"#{}\">{}</a>", anchor, page.title)));
+            assert!(html.contains(&format!("<h3 id=\"{}\">", anchor)));
+        }
+    }
+    // code like String::from("hello") has its quotes escaped.
+    assert!(!html.contains("String::from(\"hello\")"));
+    assert!(html.contains("String::from(&quot;hello&quot;)"));
+}
+
+#[test]
+fn stepping_through_a_lesson() {
+    let variables = lessons::find("variables").unwrap();
+    let options = Options { step: true, ..Options::default() };
+
+    // next, replay, jump back to the first, then next until the end.
+    let (mut ctx, capture) = Context::capture(Format::Human, options, "\nr\n1\n\n\n\n");
+    lessons::present(&mut ctx, variables);
+
+    let headers: Vec<String> = capture
+        .contents()
+        .lines()
+        .filter(|line| line.starts_with("--- ["))
+        .map(|line| line.to_string())
+        .collect();
+    assert_eq!(headers, vec![
+        "--- [1/3] VARIABLES ---",
+        "--- [2/3] CONSTANTS ---",
+        "--- [2/3] CONSTANTS ---",
+        "--- [1/3] VARIABLES ---",
+        "--- [2/3] CONSTANTS ---",
+        "--- [3/3] SHADOWING ---",
+    ]);
+    assert!(capture.contents().ends_with("that was the last section, all done!\n"));
+
+    // stepping through a lesson shows the same output as running it, only
+    // with the notes, and the prompts, in between.
+    let (mut ctx, run) = Context::capture(Format::Human, Options::default(), "");
+    variables.run(&mut ctx);
+    for line in run.contents().lines() {
+        assert!(capture.contents().contains(line), "missing {}", line);
+    }
+}
@@ -0,0 +1,81 @@
+// number literals
+
+// every form a number can be written in, and the reasons rustc gives for
+// refusing one.
+
+extern crate rusty;
+
+use rusty::literal::{self, Base, Number};
+
+// "type value" for a literal, or the reason it's refused.
+fn inspect(text: &str) -> String {
+    match literal::inspect(text) {
+        Ok(inspection) => format!("{} {}", inspection.ty, inspection.value),
+        Err(e) => e.message,
+    }
+}
+
+#[test]
+fn every_form_of_integer_literal() {
+    assert_eq!(inspect("98_222"), "i32 98222");
+    assert_eq!(inspect("0xff"), "i32 255");
+    assert_eq!(inspect("0xFF_u8"), "u8 255");
+    assert_eq!(inspect("0o77"), "i32 63");
+    assert_eq!(inspect("0b1111_0000"), "i32 240");
+    assert_eq!(inspect("b'A'"), "u8 65");
+    assert_eq!(inspect("b'\\n'"), "u8 10");
+    assert_eq!(inspect("b'\\xff'"), "u8 255");
+    assert_eq!(inspect("255u8"), "u8 255");
+    assert_eq!(inspect("-128i8"), "i8 -128");
+    assert_eq!(inspect("340282366920938463463374607431768211455u128"), "u128 340282366920938463463374607431768211455");
+    // in hex, f is a digit, so this is an integer, not an f32.
+    assert_eq!(inspect("0x1f32"), "i32 7986");
+}
+
+#[test]
+fn every_form_of_float_literal() {
+    assert_eq!(inspect("2.5"), "f64 2.5");
+    assert_eq!(inspect("1."), "f64 1.0");
+    assert_eq!(inspect("1e3"), "f64 1000.0");
+    assert_eq!(inspect("2.5E-4"), "f64 0.00025");
+    assert_eq!(inspect("1e-3f32"), "f32 0.001");
+    assert_eq!(inspect("1f32"), "f32 1.0");
+    assert_eq!(inspect("1_000.000_1"), "f64 1000.0001");
+    assert_eq!(inspect("0.1f32"), "f32 0.1");
+}
+
+#[test]
+fn out_of_range_literals_are_refused_like_rustc_does() {
+    assert_eq!(inspect("256u8"), "literal out of range for `u8`, the literal `256u8` does not fit into the type `u8` whose range is `0..=255`");
+    assert_eq!(inspect("128i8"), "literal out of range for `i8`, the literal `128i8` does not fit into the type `i8` whose range is `-128..=127`");
+    assert_eq!(inspect("-129i8"), "literal out of range for `i8`, the literal `-129i8` does not fit into the type `i8` whose range is `-128..=127`");
+    assert_eq!(inspect("0xffi8"), "literal out of range for `i8`, the literal `0xffi8` (decimal `255`) does not fit into the type `i8` and will become `-1i8`, consider using the type `u8` instead");
+    assert_eq!(inspect("3_000_000_000"), "literal out of range for `i32`, the literal `3_000_000_000` does not fit into the type `i32` whose range is `-2147483648..=2147483647`");
+    assert_eq!(inspect("1e40f32"), "literal out of range for `f32`, the literal `1e40f32` does not fit into the type `f32` and will be converted to `f32::INFINITY`");
+    assert_eq!(inspect("-1u8"), "error[E0600]: cannot apply unary operator `-` to type `u8`, unsigned values cannot be negated");
+    assert!(inspect("340282366920938463463374607431768211456").starts_with("integer literal is too large"));
+}
+
+#[test]
+fn malformed_literals_point_at_the_problem() {
+    let e = literal::inspect("0b102").unwrap_err();
+    assert_eq!(e.render("0b102"), "0b102\n    ^ invalid digit for a base 2 literal");
+    assert_eq!(inspect("1.5u8"), "invalid suffix `u8` for float literal, valid suffixes are `f32` and `f64`");
+    assert_eq!(inspect("0b1f32"), "binary float literal is not supported");
+    assert_eq!(inspect("1e"), "expected at least one digit in exponent");
+    assert_eq!(inspect("0x"), "no valid digits found for number");
+    assert_eq!(inspect("b'ab'"), "a byte literal holds exactly one character");
+    assert!(inspect("b'é'").starts_with("non-ASCII character in byte literal"));
+    assert!(inspect("12abc").starts_with("invalid suffix `abc` for number literal"));
+}
+
+#[test]
+fn lexing_stops_where_the_literal_does() {
+    // 1..2 is a range, and 1.max(2) a method call, neither has a fraction.
+    let (lit, len) = literal::lex("1..2").unwrap();
+    assert_eq!((lit.number, len), (Number::Int(1), 1));
+    let (lit, len) = literal::lex("1.max(2)").unwrap();
+    assert_eq!((lit.number, len), (Number::Int(1), 1));
+    let (lit, len) = literal::lex("0x10u16 + 1").unwrap();
+    assert_eq!((lit.base, lit.number, lit.suffix, len), (Base::Hex, Number::Int(16), Some("u16"), 7));
+}
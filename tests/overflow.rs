@@ -0,0 +1,98 @@
+// the integer overflow explorer
+
+// the explorer works out what the other kind of build would do from the
+// rules, but runs the plain operator for real in this one, so the two can be
+// held against each other, for every type, and every operation.
+
+extern crate rusty;
+
+use rusty::json::Value;
+use rusty::overflow::{self, Op, Report, TYPES};
+
+#[test]
+fn the_ways_of_overflowing_are_side_by_side() {
+    let report = overflow::explore("250u8 + 10").unwrap();
+    assert_eq!(report.expression(), "250u8 + 10");
+    assert_eq!(report.checked, Ok(Value::Null));
+    assert_eq!(report.wrapping, Ok(Value::UInt(4)));
+    assert_eq!(report.saturating, Some(Ok(Value::UInt(255))));
+    assert_eq!(report.overflowing, Ok((Value::UInt(4), true)));
+    assert_eq!(report.debug, Err("panics, attempt to add with overflow".to_string()));
+    assert_eq!(report.release, Ok(Value::UInt(4)));
+
+    // dividing MIN by -1 panics whatever the build, so does dividing by 0.
+    let report = overflow::explore("-128i8 / -1").unwrap();
+    assert_eq!(report.release, Err("panics, attempt to divide with overflow".to_string()));
+    let report = overflow::explore("7 % 0").unwrap();
+    assert_eq!(report.ty, "i32");
+    assert_eq!(report.release, Err("panics, attempt to calculate the remainder with a divisor of zero".to_string()));
+    assert!(report.saturating.is_none());
+}
+
+#[test]
+fn this_build_does_what_the_rules_say() {
+    // T is the type, MIN and MAX its smallest and largest values.
+    let operations = [
+        "1T + MAX", "MIN - 1", "MAX * 2", "MIN / -1", "MIN % -1", "5T / 0",
+        "1T << 200", "1T >> 200", "3T pow 90", "neg MIN", "6T * 7",
+    ];
+    for ty in TYPES {
+        // an unsigned type has no -1 to divide by.
+        for operation in operations.iter().filter(|op| ty.starts_with('i') || !op.contains("-1")) {
+            let operation = operation
+                .replace("MIN", &format!("{}T", min(ty)))
+                .replace("MAX", &format!("{}T", max(ty)))
+                .replace('T', ty);
+            let report = overflow::explore(&operation).unwrap_or_else(|e| panic!("{}: {}", operation, e));
+            assert_eq!(report.ty, *ty);
+
+            // the column for this build was run, the rules agree with it.
+            let (ran, worked_out) = match Report::build() {
+                "debug" => (&report.debug, expected_debug(&report)),
+                _ => (&report.release, expected_release(&report)),
+            };
+            assert_eq!(*ran, worked_out, "{}", report.expression());
+        }
+    }
+}
+
+fn min(ty: &str) -> String {
+    if ty.starts_with('u') { "0".to_string() } else { format!("-{}", max_signed(ty) + 1) }
+}
+
+fn max(ty: &str) -> String {
+    if ty.starts_with('u') { (max_signed(ty) * 2 + 1).to_string() } else { max_signed(ty).to_string() }
+}
+
+fn max_signed(ty: &str) -> u128 {
+    let bits: u32 = match &ty[1..] {
+        "size" => 64,
+        bits => bits.parse().unwrap(),
+    };
+    (1u128 << (bits - 1)) - 1
+}
+
+fn expected_debug(report: &Report) -> Result<Value, String> {
+    if report.op == Op::Neg && report.ty.starts_with('u') {
+        return report.plain.clone();
+    }
+    match report.checked {
+        Ok(Value::Null) => Err(report.plain.clone().unwrap_err()),
+        ref checked => checked.clone(),
+    }
+}
+
+fn expected_release(report: &Report) -> Result<Value, String> {
+    match report.op {
+        Op::Div | Op::Rem => expected_debug(report),
+        _ => report.wrapping.clone(),
+    }
+}
+
+#[test]
+fn bad_operations_say_what_is_wrong() {
+    assert_eq!(overflow::explore("300u8 + 1").err().unwrap(), "`300` isn't a `u8`, which goes from 0 to 255");
+    assert_eq!(overflow::explore("1u8 + 2i32").err().unwrap(), "mismatched types, the right side of `+` has to be `u8`, not `i32`");
+    assert!(overflow::explore("5 ^ 3").is_err());
+    assert!(overflow::explore("5").is_err());
+}
@@ -0,0 +1,37 @@
+// catching panics
+
+extern crate rusty;
+
+use std::thread;
+
+use rusty::panics;
+
+#[test]
+fn a_panic_is_handed_back_as_its_message() {
+    assert_eq!(panics::catch(|| 1 + 1), Ok(2));
+    assert_eq!(panics::catch(|| panic!("plain")), Err::<(), _>("plain".to_string()));
+    assert_eq!(panics::catch(|| panic!("with {}", "arguments")), Err::<(), _>("with arguments".to_string()));
+    assert_eq!(panics::catch(|| std::panic::panic_any(7)), Err::<(), _>("a panic without a message".to_string()));
+
+    // a catch inside a catch.
+    let outer = panics::catch(|| {
+        assert!(panics::catch(|| panic!("inner")).is_err());
+        panic!("outer")
+    });
+    assert_eq!(outer, Err::<(), _>("outer".to_string()));
+}
+
+#[test]
+fn threads_can_catch_at_the_same_time() {
+    let threads: Vec<_> = (0..8).map(|i| thread::spawn(move || {
+        (0..100).map(|j| panics::catch(|| if j % 2 == 0 { panic!("{} {}", i, j) } else { j }))
+            .filter(|result| result.is_err())
+            .count()
+    })).collect();
+    for thread in threads {
+        assert_eq!(thread.join().unwrap(), 50);
+    }
+
+    // a thread that isn't catching still panics the usual way.
+    assert!(thread::spawn(|| panic!("not caught")).join().is_err());
+}
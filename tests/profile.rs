@@ -0,0 +1,113 @@
+// learner profiles
+
+// whatever a learner does, running a lesson, stepping through it, or taking
+// its quiz, ends up in their profile, which is what the dashboard shows, and
+// what the next lesson is picked from.
+
+extern crate rusty;
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use rusty::context::{Context, Options};
+use rusty::lessons::{self, LESSONS};
+use rusty::output::Format;
+use rusty::profile::{self, Profile};
+
+fn home(name: &str) -> PathBuf {
+    let home = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join(name);
+    let _ = fs::remove_dir_all(&home);
+    home
+}
+
+// runs a lesson, or a command of it, for a user.
+fn take(home: &Path, user: &str, lesson: &str, command: Option<&str>, step: bool, input: &str) {
+    let options = Options {
+        command: command.map(|command| command.to_string()),
+        step,
+        seed: Some(3),
+        user: Some(user.to_string()),
+        ..Options::default()
+    };
+    let (mut ctx, _) = Context::capture(Format::Human, options, input);
+    ctx.home = Some(home.to_path_buf());
+    lessons::present(&mut ctx, lessons::find(lesson).unwrap());
+}
+
+#[test]
+fn progress_is_kept_per_user() {
+    let home = home("progress_is_kept_per_user");
+
+    // alice runs the variables lesson all the way through, and takes its
+    // quiz, bob only steps through the first two sections of data types.
+    take(&home, "alice", "variables", None, false, "");
+    take(&home, "alice", "variables", Some("quiz"), false, &"a\nno\n".repeat(5));
+    take(&home, "bob", "datatypes", None, true, "\nq\n");
+
+    let alice = Profile::load(&home, "alice").unwrap();
+    let variables = lessons::find("variables").unwrap();
+    assert!(alice.is_done(variables));
+    let quiz = alice.lesson("variables").quiz.unwrap();
+    assert_eq!(quiz.attempts, 1);
+    assert!(quiz.best <= quiz.total);
+    assert_eq!(alice.next().map(|lesson| lesson.id()), Some("datatypes"));
+
+    let bob = Profile::load(&home, "bob").unwrap();
+    let datatypes = lessons::find("datatypes").unwrap();
+    assert_eq!(bob.lesson("datatypes").sections_done(datatypes), 2);
+    assert!(!bob.is_done(datatypes));
+    assert_eq!(bob.next().map(|lesson| lesson.id()), Some("variables"));
+
+    assert_eq!(profile::users(&home), vec!["alice", "bob"]);
+}
+
+#[test]
+fn a_profile_survives_saving_and_loading() {
+    let home = home("a_profile_survives_saving_and_loading");
+
+    let mut profile = Profile::load(&home, "carol").unwrap();
+    assert!(profile.lessons.is_empty());
+    {
+        let progress = profile.lesson_mut("ownership");
+        progress.see_section("why ownership");
+        progress.score_quiz(3, 7);
+        progress.score_quiz(5, 7);
+        progress.try_exercise("use_after_move", "reproduced");
+        progress.try_exercise("use_after_move", "fixed");
+        progress.try_exercise("use_after_move", "broken");
+    }
+    profile.save(&home).unwrap();
+
+    let loaded = Profile::load(&home, "carol").unwrap();
+    let progress = loaded.lesson("ownership");
+    assert_eq!(progress.sections, vec!["why ownership"]);
+    let quiz = progress.quiz.clone().unwrap();
+    assert_eq!((quiz.last, quiz.best, quiz.total, quiz.attempts), (5, 5, 7, 2));
+    // a fixed exercise stays fixed, whatever comes after.
+    assert_eq!(progress.exercises_fixed(), 1);
+}
+
+#[test]
+fn the_dashboard_shows_every_lesson_and_what_is_next() {
+    let home = home("the_dashboard_shows_every_lesson_and_what_is_next");
+
+    // once every lesson has been run, there's nothing left to take.
+    for lesson in LESSONS.iter() {
+        take(&home, "dave", lesson.id(), None, false, "7\n");
+    }
+
+    let options = Options {
+        user: Some("dave".to_string()),
+        ..Options::default()
+    };
+    let (mut ctx, capture) = Context::capture(Format::Human, options, "");
+    ctx.home = Some(home.clone());
+    profile::dashboard(&mut ctx);
+
+    let dashboard = capture.contents();
+    assert!(dashboard.starts_with("dave's progress\n"));
+    for lesson in LESSONS.iter() {
+        assert!(dashboard.contains(&format!("  {:<14} yes", lesson.id())), "{}", dashboard);
+    }
+    assert!(dashboard.contains("all the lessons are done, well done!"));
+}
@@ -0,0 +1,106 @@
+// the quizzes
+
+// every lesson's quiz file has to parse, and a quiz is taken the way any
+// lesson is run, with its input given as a string, and its output captured.
+
+extern crate rusty;
+
+use std::fs;
+use std::path::PathBuf;
+
+use rusty::context::{Context, Options};
+use rusty::lessons::{self, quiz, LESSONS};
+use rusty::lessons::quiz::Kind;
+use rusty::output::Format;
+
+#[test]
+fn every_quiz_parses() {
+    for lesson in LESSONS.iter() {
+        let name = format!("quizzes/{}.quiz", lesson.id());
+        let questions = quiz::parse(&name, lesson.quiz()).unwrap_or_else(|e| panic!("{}", e));
+        assert_eq!(questions.is_empty(), lesson.quiz().is_empty(), "{} has an empty quiz", lesson.id());
+    }
+}
+
+#[test]
+fn questions_are_read_with_all_their_parts() {
+    let text = "# a comment\n\n[output] what does this print?\n| let x = 5;\n|\n|     println!(\"{}\", x);\n= 5\n> it prints x,\n> which is 5.\n";
+    let questions = quiz::parse("test.quiz", text).unwrap();
+
+    assert_eq!(questions.len(), 1);
+    let question = &questions[0];
+    assert_eq!(question.number, 1);
+    assert_eq!(question.kind, Kind::Output);
+    assert_eq!(question.prompt, "what does this print?");
+    assert_eq!(question.code, vec!["let x = 5;", "", "    println!(\"{}\", x);"]);
+    assert_eq!(question.answer, "5");
+    assert_eq!(question.explanation, vec!["it prints x,", "which is 5."]);
+}
+
+#[test]
+fn mistakes_in_a_quiz_are_reported_with_their_line() {
+    let cases = [
+        ("- a choice\n", "test.quiz:1: expected a question"),
+        ("[essay] why?\n", "test.quiz:1: unknown kind `essay`"),
+        ("[choice] which?\n- a\n- b\n> none of them\n", "test.quiz:1: the question needs exactly one right choice"),
+        ("[output] what?\n= 1\n", "test.quiz:1: the question has no explanation"),
+        ("\n[compiles] does it?\n= maybe\n> it does\n", "test.quiz:2: the question can only be answered with = yes or = no"),
+        ("[output] what?\n= 1\n= 2\n", "test.quiz:3: the question already has an answer"),
+        ("[output] what?\n? 1\n", "test.quiz:2: expected a line starting with"),
+    ];
+
+    for &(text, expected) in cases.iter() {
+        let error = quiz::parse("test.quiz", text).unwrap_err();
+        assert!(error.starts_with(expected), "expected `{}`, got `{}`", expected, error);
+    }
+}
+
+#[test]
+fn answers_are_checked_by_kind() {
+    let text = "[choice] which?\n- no\n* yes\n> b\n[output] what?\n= hello  world\n> hi\n[compiles] does it?\n= no\n> no\n";
+    let questions = quiz::parse("test.quiz", text).unwrap();
+
+    assert_eq!(questions[0].check("b"), Some(true));
+    assert_eq!(questions[0].check(" A \n"), Some(false));
+    assert_eq!(questions[0].check("c"), None);
+    assert_eq!(questions[0].expected(), "b) yes");
+
+    assert_eq!(questions[1].check("hello world"), Some(true));
+    assert_eq!(questions[1].check("hello"), Some(false));
+
+    assert_eq!(questions[2].check("N"), Some(true));
+    assert_eq!(questions[2].check("yes"), Some(false));
+    assert_eq!(questions[2].check("maybe"), None);
+}
+
+#[test]
+fn quiz_results_are_saved_per_user() {
+    let home = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("quiz_results_are_saved_per_user");
+    let _ = fs::remove_dir_all(&home);
+
+    let variables = lessons::find("variables").unwrap();
+    let options = Options {
+        command: Some("quiz".to_string()),
+        seed: Some(3),
+        user: Some("alice".to_string()),
+        ..Options::default()
+    };
+
+    // the same seed asks the questions in the same order, so taking the quiz
+    // twice, with the same answers, gets the same score. "a" answers the
+    // multiple choice questions, and "no" the ones asking if code compiles,
+    // either of them is skipped when it's not an answer.
+    for _ in 0..2 {
+        let (mut ctx, capture) = Context::capture(Format::Human, options.clone(), &"a\nno\n".repeat(5));
+        ctx.home = Some(home.clone());
+        lessons::present(&mut ctx, variables);
+        assert!(!ctx.aborted());
+        assert!(capture.contents().contains("saved to alice's results."));
+    }
+
+    let saved = fs::read_to_string(home.join("quiz").join("alice.jsonl")).unwrap();
+    let lines: Vec<&str> = saved.lines().collect();
+    assert_eq!(lines.len(), 2);
+    assert!(lines[0].starts_with("{\"user\":\"alice\",\"lesson\":\"variables\",\"score\":"));
+    assert_eq!(lines[0].split(",\"time\"").next(), lines[1].split(",\"time\"").next());
+}
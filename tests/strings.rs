@@ -0,0 +1,42 @@
+// Strings, on the stack and on the heap
+
+// the addresses are different every run, so they're checked against each
+// other, a move keeps the buffer, a clone gets one of its own.
+
+extern crate rusty;
+
+use rusty::strings::{self, Look};
+
+fn ptr(look: &Look) -> usize {
+    match *look {
+        Look::Live { ptr, .. } => ptr,
+        Look::Moved { .. } => panic!("{} was moved", look.name()),
+    }
+}
+
+#[test]
+fn a_move_keeps_the_buffer_and_a_clone_does_not() {
+    let s1 = String::from("hello");
+    let before = strings::look("s1", &s1);
+    let s2 = s1;
+    let after = strings::look("s2", &s2);
+    assert_eq!(ptr(&before), ptr(&after));
+    let s3 = s2.clone();
+    assert_ne!(ptr(&strings::look("s3", &s3)), ptr(&after));
+
+    let lines = strings::draw(&[strings::moved(&before, "s2"), after.clone(), strings::look("s2", &s2)]);
+    assert_eq!(lines.len(), 4);
+    assert!(lines[1].ends_with("moved into s2, it can't be used anymore"), "{}", lines[1]);
+    assert!(lines[2].ends_with("len  5, cap  5   --> |h|e|l|l|o|"), "{}", lines[2]);
+    assert!(lines[3].ends_with("--> the same buffer as s2"), "{}", lines[3]);
+}
+
+#[test]
+fn the_spare_capacity_is_left_blank() {
+    assert_eq!(strings::buffer(b"a b", 5), "|a|_|b| | |");
+    assert_eq!(strings::buffer("é".as_bytes(), 2), "|?|?|");
+    assert_eq!(strings::buffer(&[b'x'; 40], 40), format!("{} .. 8 more", "|x".repeat(32) + "|"));
+
+    let empty = String::new();
+    assert!(strings::draw(&[strings::look("s", &empty)])[1].ends_with("--> nothing, it's empty"));
+}
@@ -0,0 +1,55 @@
+// when values come and go
+
+// the journal has to agree with the order rust drops things in, the reverse
+// of the order they were made in, with function arguments after everything
+// declared inside the function.
+
+extern crate rusty;
+
+use rusty::tracer::{Journal, Kind, Traced};
+
+fn lines(journal: &Journal) -> Vec<String> {
+    journal.take().iter().map(|entry| entry.to_string()).collect()
+}
+
+fn keeps(s: Traced<String>) -> usize {
+    let _copy = s.clone_as("copy");
+    s.len()
+}
+
+#[test]
+fn values_are_dropped_in_reverse() {
+    let journal = Journal::new();
+    {
+        let _scope = journal.scope("block");
+        let a = journal.make("a", 1);
+        let _b = journal.make("b", 2);
+        let c = a.hand_to("c");
+        assert_eq!(*c, 1);
+    }
+    assert_eq!(lines(&journal), vec![
+        "{ block", "  a is created", "  b is created", "  a moves into c",
+        "  c is dropped", "  b is dropped", "} block",
+    ]);
+
+    let s = journal.make("s", String::from("hello"));
+    let len = journal.call("keeps", || keeps(s.hand_to("s")));
+    assert_eq!(len, 5);
+    let entries = journal.take();
+    assert_eq!((entries[4].kind, entries[4].depth, entries[4].name), (Kind::Dropped, 1, "copy"));
+    let entries: Vec<String> = entries.iter().map(|entry| entry.to_string()).collect();
+    assert_eq!(entries, vec![
+        "s is created", "{ keeps", "  s moves into s", "  s is cloned into copy",
+        "  copy is dropped", "  s is dropped", "} keeps",
+    ]);
+}
+
+#[test]
+fn a_value_taken_out_goes_quietly() {
+    let journal = Journal::new();
+    let mut s = journal.make("s", String::from("a"));
+    s.push('b');
+    assert_eq!(format!("{} {:?}", s, s), "ab \"ab\"");
+    assert_eq!(s.into_inner(), "ab");
+    assert_eq!(lines(&journal), vec!["s is created"]);
+}
@@ -0,0 +1,73 @@
+// chars, bytes and graphemes
+
+// the grapheme clusters are checked against examples from UAX #29, and the
+// categories against what the unicode character database says.
+
+extern crate rusty;
+
+use rusty::unicode;
+
+#[test]
+fn len_counts_bytes_and_chars_counts_code_points() {
+    let report = unicode::explore("caf\u{e9} 😻");
+    assert_eq!((report.bytes, report.chars.len(), report.utf16), (10, 6, 7));
+    let cat = &report.chars[5];
+    assert_eq!((cat.offset, cat.code_point()), (6, "U+1F63B".to_string()));
+    assert_eq!(cat.utf8, vec![0xf0, 0x9f, 0x98, 0xbb]);
+    assert_eq!(cat.utf16, vec![0xd83d, 0xde3b]);
+    assert_eq!(report.chars[3].utf8, vec![0xc3, 0xa9]);
+
+    assert_eq!(unicode::explore("    ").bytes, 4);
+    assert_eq!(unicode::unescape("e\\u{301} \\u{zz}"), "e\u{301} \\u{zz}");
+}
+
+#[test]
+fn graphemes_are_what_a_reader_counts() {
+    let cases: &[(&str, &[&str])] = &[
+        ("e\u{301}", &["e\u{301}"]),
+        ("\r\n\n", &["\r\n", "\n"]),
+        // a waving hand with a skin tone, a family joined with zero width
+        // joiners, and a flag made of two regional indicators.
+        ("👋🏽", &["👋🏽"]),
+        ("👨\u{200d}👩\u{200d}👧", &["👨\u{200d}👩\u{200d}👧"]),
+        ("🇯🇵🇫🇷🇩", &["🇯🇵", "🇫🇷", "🇩"]),
+        // hangul, as one syllable, and as the jamo it's made of.
+        ("한\u{1112}\u{1161}\u{11ab}", &["한", "\u{1112}\u{1161}\u{11ab}"]),
+        ("\u{928}\u{93f}", &["\u{928}\u{93f}"]),
+        // a joiner only joins emoji, not letters.
+        ("a\u{200d}b", &["a\u{200d}", "b"]),
+        ("\u{301}a", &["\u{301}", "a"]),
+        ("", &[]),
+    ];
+    for &(text, expected) in cases {
+        assert_eq!(unicode::graphemes(text), expected, "{:?}", text);
+    }
+}
+
+#[test]
+fn every_char_gets_a_category() {
+    let code = |c| unicode::category(c).code;
+    assert_eq!(code('A'), "Lu");
+    assert_eq!(code('ß'), "Ll");
+    assert_eq!(code('ǅ'), "Lt");
+    assert_eq!(code('한'), "Lo");
+    assert_eq!(code('\u{301}'), "Mn");
+    assert_eq!(code('\u{93e}'), "Mc");
+    assert_eq!(code('7'), "Nd");
+    assert_eq!(code('٣'), "Nd");
+    assert_eq!(code('Ⅻ'), "Nl");
+    assert_eq!(code('½'), "No");
+    assert_eq!(code('_'), "Pc");
+    assert_eq!(code('('), "Ps");
+    assert_eq!(code('«'), "Pi");
+    assert_eq!(code('€'), "Sc");
+    assert_eq!(code('+'), "Sm");
+    assert_eq!(code('😻'), "So");
+    assert_eq!(code('\u{1f3fd}'), "Sk");
+    assert_eq!(code(' '), "Zs");
+    assert_eq!(code('\u{3000}'), "Zs");
+    assert_eq!(code('\n'), "Cc");
+    assert_eq!(code('\u{200d}'), "Cf");
+    assert_eq!(code('\u{e000}'), "Co");
+    assert_eq!(unicode::category('\u{301}').name, "nonspacing mark");
+}